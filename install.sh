@@ -32,4 +32,25 @@ echo "  sudo mv ./$BINARY_NAME /usr/local/bin/$BINARY_NAME"
 echo ""
 
 echo "Available commands:"
-echo "  hyprspace   Launch the TUI"
\ No newline at end of file
+echo "  hyprspace                          Launch the TUI"
+echo "  hyprspace launch <name>            Launch a workspace script by name"
+echo "  hyprspace launch-ws <N>            Launch the script targeting workspace N"
+echo "  hyprspace last                     Launch the most recently launched workspace again"
+echo "  hyprspace pick [--print-path]      Choose a script and print its name or path"
+echo "  hyprspace new                      Create a new workspace script"
+echo "  hyprspace append <name>            Add a window to a workspace script"
+echo "  hyprspace duplicate <name>         Copy a script under a new name and workspace number"
+echo "  hyprspace step <name>              Run the windows of a script one by one, confirming each"
+echo "  hyprspace switch <name>            Only switch to the workspace of a script"
+echo "  hyprspace list [--json]            List workspace scripts"
+echo "  hyprspace export <file.tar.gz>     Bundle all workspace scripts"
+echo "  hyprspace import <file.tar.gz>     Restore workspace scripts from an archive"
+echo "  hyprspace export-def <name>        Print the portable TOML definition of a script"
+echo "  hyprspace import-def <file.toml>   Generate a script from a TOML definition"
+echo "  hyprspace install-autostart <name> Launch a workspace when Hyprland starts"
+echo "  hyprspace trash list               List deleted scripts (also trash restore/empty)"
+echo "  hyprspace doctor                   Check all workspace scripts for problems"
+echo "  hyprspace dedupe                   Report near-identical scripts"
+echo "  hyprspace reset-term               Restore a terminal left garbled by a crash"
+echo "  hyprspace normalize                Regenerate all scripts with the current template"
+echo "  hyprspace completions <shell>      Print bash/zsh/fish completions"
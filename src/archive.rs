@@ -0,0 +1,137 @@
+use std::env;
+use std::fs::{self, DirBuilder};
+use std::io;
+use std::os::unix::fs::DirBuilderExt;
+use std::path::{Path, PathBuf};
+use std::process::{self, Command};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::config::Config;
+use crate::workspace::{confirm, list_workspaces};
+
+/// Run `tar` with the given arguments, turning a non-zero exit into an error.
+fn run_tar(args: &[&str]) -> io::Result<()> {
+    let status = Command::new("tar").args(args).status()?;
+    if !status.success() {
+        return Err(io::Error::other(format!("tar exited with {status}")));
+    }
+    Ok(())
+}
+
+/// Create a fresh staging directory under the temp dir, readable by the current user only.
+///
+/// The name carries a time-based suffix and the directory must not exist yet, so a path
+/// prepared in advance by someone else (directory or symlink) is never reused.
+fn create_staging_dir() -> io::Result<PathBuf> {
+    let mut builder = DirBuilder::new();
+    builder.mode(0o700);
+    for attempt in 0u32..100 {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_nanos());
+        let suffix = nanos ^ (u128::from(process::id()) << 64) ^ u128::from(attempt);
+        let path = env::temp_dir().join(format!("hyprspace-import-{suffix:x}"));
+        match builder.create(&path) {
+            Ok(()) => return Ok(path),
+            Err(err) if err.kind() == io::ErrorKind::AlreadyExists => continue,
+            Err(err) => return Err(err),
+        }
+    }
+    Err(io::Error::new(
+        io::ErrorKind::AlreadyExists,
+        "could not create a staging directory",
+    ))
+}
+
+/// Bundle every workspace-*.sh script of `dir` into a .tar.gz archive.
+pub fn export_workspaces(dir: &Path, archive: &Path) -> io::Result<()> {
+    let workspaces = list_workspaces(dir)?;
+
+    if workspaces.is_empty() {
+        println!(
+            "No workspace scripts found in {}, nothing to export.",
+            dir.to_string_lossy()
+        );
+        return Ok(());
+    }
+
+    let archive = archive.to_string_lossy();
+    let dir = dir.to_string_lossy();
+    let mut args = vec!["-czf", archive.as_ref(), "-C", dir.as_ref()];
+    args.extend(workspaces.iter().map(|ws| ws.base_name.as_str()));
+    run_tar(&args)?;
+
+    println!("Exported {} script(s) to {}", workspaces.len(), archive);
+    Ok(())
+}
+
+/// Extract workspace-*.sh scripts from a .tar.gz archive into `dir`.
+///
+/// Existing scripts are only replaced after confirmation, unless `[confirm] overwrite = false`.
+pub fn import_workspaces(dir: &Path, archive: &Path, config: &Config) -> io::Result<()> {
+    // Extract into a private staging directory first so nothing is overwritten blindly
+    let staging = create_staging_dir()?;
+
+    let result = import_from_staging(dir, archive, &staging, config);
+    let _ = fs::remove_dir_all(&staging);
+    result
+}
+
+fn import_from_staging(
+    dir: &Path,
+    archive: &Path,
+    staging: &Path,
+    config: &Config,
+) -> io::Result<()> {
+    let archive = archive.to_string_lossy();
+    let staging_str = staging.to_string_lossy();
+    run_tar(&["-xzf", archive.as_ref(), "-C", staging_str.as_ref()])?;
+
+    let mut imported = 0usize;
+
+    for ws in list_workspaces(staging)? {
+        let target = dir.join(&ws.base_name);
+
+        // A symlink would copy whatever it points to, e.g. a private key, into the scripts
+        if !fs::symlink_metadata(&ws.full_path)?.is_file() {
+            eprintln!(
+                "Skipped {}: not a regular file in the archive",
+                ws.base_name
+            );
+            continue;
+        }
+        // Nor is anything written through a symlink (even a dangling one) at the destination
+        let existing = match fs::symlink_metadata(&target) {
+            Ok(meta) => Some(meta),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => None,
+            Err(err) => return Err(err),
+        };
+        if existing.as_ref().is_some_and(|meta| !meta.is_file()) {
+            eprintln!(
+                "Skipped {}: {} is not a regular file",
+                ws.base_name,
+                target.to_string_lossy()
+            );
+            continue;
+        }
+
+        if existing.is_some()
+            && config.confirm.overwrite
+            && !confirm(&format!("{} already exists, overwrite?", ws.base_name))?
+        {
+            println!("Skipped {}", ws.base_name);
+            continue;
+        }
+
+        // fs::copy carries the permission bits over, including the executable bit
+        fs::copy(&ws.full_path, &target)?;
+        println!("Imported {}", ws.base_name);
+        imported += 1;
+    }
+
+    println!(
+        "Imported {imported} script(s) into {}",
+        dir.to_string_lossy()
+    );
+    Ok(())
+}
@@ -0,0 +1,130 @@
+use std::env;
+use std::fs;
+use std::io::{self, Write};
+use std::path::PathBuf;
+
+use crate::workspace::{find_workspace, list_all_workspaces};
+
+/// Returns the path to the Hyprland config (~/.config/hypr/hyprland.conf)
+pub fn hyprland_config_path() -> PathBuf {
+    let config_home = env::var_os("XDG_CONFIG_HOME")
+        .filter(|v| !v.is_empty())
+        .map(PathBuf::from)
+        .unwrap_or_else(|| {
+            let home = env::var("HOME").expect("HOME environment variable not set");
+            PathBuf::from(home).join(".config")
+        });
+
+    config_home.join("hypr").join("hyprland.conf")
+}
+
+/// The `hyprspace` command line launching `name`, in `profile` when set
+fn launch_words<'a>(name: &'a str, profile: Option<&'a str>) -> Vec<&'a str> {
+    let mut words = vec!["hyprspace"];
+    if let Some(profile) = profile {
+        words.extend(["--profile", profile]);
+    }
+    words.extend(["launch", name]);
+    words
+}
+
+fn autostart_line(name: &str, profile: Option<&str>) -> String {
+    format!("exec-once = {}", launch_words(name, profile).join(" "))
+}
+
+/// Whether `line` is an `exec-once = hyprspace [--profile <profile>] launch <name>` entry,
+/// ignoring spacing.
+fn is_autostart_line(line: &str, name: &str, profile: Option<&str>) -> bool {
+    let Some((key, value)) = line.split_once('=') else {
+        return false;
+    };
+
+    key.trim() == "exec-once"
+        && value.split_whitespace().collect::<Vec<_>>() == launch_words(name, profile)
+}
+
+/// Append an `exec-once` line launching `name` (from `profile`) to the Hyprland config,
+/// unless already present.
+pub fn install_autostart(dirs: &[PathBuf], name: &str, profile: Option<&str>) -> io::Result<()> {
+    // `hyprspace launch` refuses ambiguous names, so refuse them here too
+    find_workspace(&list_all_workspaces(dirs)?, name)?;
+
+    let config = hyprland_config_path();
+    let content = match fs::read_to_string(&config) {
+        Ok(content) => content,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => String::new(),
+        Err(err) => return Err(err),
+    };
+
+    if content
+        .lines()
+        .any(|line| is_autostart_line(line, name, profile))
+    {
+        println!(
+            "{name} already starts with Hyprland ({})",
+            config.to_string_lossy()
+        );
+        return Ok(());
+    }
+
+    if let Some(parent) = config.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    let mut file = fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&config)?;
+
+    // Make sure our line doesn't end up glued to the last one
+    if !content.is_empty() && !content.ends_with('\n') {
+        writeln!(file)?;
+    }
+    writeln!(file, "{}", autostart_line(name, profile))?;
+
+    println!(
+        "Added '{}' to {}",
+        autostart_line(name, profile),
+        config.to_string_lossy()
+    );
+    Ok(())
+}
+
+/// Remove every `exec-once` line launching `name` (from `profile`) from the Hyprland config.
+pub fn uninstall_autostart(name: &str, profile: Option<&str>) -> io::Result<()> {
+    let config = hyprland_config_path();
+    let content = match fs::read_to_string(&config) {
+        Ok(content) => content,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => {
+            println!(
+                "{} does not exist, nothing to remove.",
+                config.to_string_lossy()
+            );
+            return Ok(());
+        }
+        Err(err) => return Err(err),
+    };
+
+    let kept: Vec<&str> = content
+        .lines()
+        .filter(|line| !is_autostart_line(line, name, profile))
+        .collect();
+
+    let removed = content.lines().count() - kept.len();
+    if removed == 0 {
+        println!("{name} is not set to start with Hyprland.");
+        return Ok(());
+    }
+
+    let mut new_content = kept.join("\n");
+    if !kept.is_empty() && content.ends_with('\n') {
+        new_content.push('\n');
+    }
+    fs::write(&config, new_content)?;
+
+    println!(
+        "Removed {removed} autostart line(s) for {name} from {}",
+        config.to_string_lossy()
+    );
+    Ok(())
+}
@@ -0,0 +1,258 @@
+use std::io;
+use std::path::PathBuf;
+use std::time::Duration;
+
+const USAGE: &str = "usage: hyprspace [--config-path] [--instance <sig>] [--login-shell] [--batch] [--scratch] [--monitor <name>] [--systemd-scope] [--dry-run] [--time] [--wait-for-hyprland <secs>] [--compact | --no-compact] [--inline] [--watch] [--read-only] [--launch-single] [--yes] [--profile <name>] [--dir <path>]... [launch <name> | launch --filter <query> [--first] | launch-ws <N> | last | pick [--print-path] [--select <name>] | new [--from-clipboard] | append <name> | duplicate <name> | step <name> | switch <name> | list [--json | --json-lines] [--ws <N>] [--limit <N>] [--no-pager] [--archived] | trash [list | restore <name> | empty] | doctor | dedupe | reset-term | normalize | export <file.tar.gz> | import <file.tar.gz> | export-def <name> | import-def <file.toml> | install-autostart <name> | uninstall-autostart <name> | completions <bash|zsh|fish>]";
+
+/// Options and positional arguments from the command line
+#[derive(Debug, Default)]
+pub struct Cli {
+    /// Hyprland instance signature to target (`--instance <sig>`)
+    pub instance: Option<String>,
+    /// Run scripts through a login shell (`--login-shell`)
+    pub login_shell: bool,
+    /// Launch through a single `hyprctl --batch` call (`--batch`)
+    pub batch: bool,
+    /// Spawn the windows in the current workspace, skipping the switch (`--scratch`)
+    pub scratch: bool,
+    /// Open every window on this monitor, whatever the script says (`--monitor <name>`)
+    pub monitor: Option<String>,
+    /// Start every window in a transient systemd scope (`--systemd-scope`)
+    pub systemd_scope: bool,
+    /// Print the commands a launch would run instead of running them (`--dry-run`)
+    pub dry_run: bool,
+    /// Refuse every command that changes scripts (`--read-only`)
+    pub read_only: bool,
+    /// Launch the only script right away instead of opening the TUI (`--launch-single`)
+    pub launch_single: bool,
+    /// Answer yes to every confirmation (`--yes` / `-y`)
+    pub yes: bool,
+    /// Report launch timing (`--time`)
+    pub time: bool,
+    /// How long to wait for Hyprland before launching (`--wait-for-hyprland <secs>`)
+    pub wait_for_hyprland: Option<Duration>,
+    /// Start the TUI with dense rows (`--compact`) or full ones (`--no-compact`),
+    /// `None` for the density remembered from the last run
+    pub compact: Option<bool>,
+    /// Reload the TUI list when scripts change on disk (`--watch`)
+    pub watch: bool,
+    /// Print the resolved directories and config file, then exit (`--config-path`)
+    pub config_path: bool,
+    /// Draw the TUI below the prompt instead of on the alternate screen (`--inline`)
+    pub inline: bool,
+    /// Print machine-readable JSON (`--json`)
+    pub json: bool,
+    /// Print one JSON object per line (`--json-lines`)
+    pub json_lines: bool,
+    /// List the archived scripts instead (`--archived`)
+    pub archived: bool,
+    /// Only list scripts targeting this workspace (`--ws <N>`)
+    pub ws: Option<u32>,
+    /// List at most this many scripts (`--limit <N>`)
+    pub limit: Option<usize>,
+    /// Print the list directly even when it is taller than the terminal (`--no-pager`)
+    pub no_pager: bool,
+    /// Seed `new` with windows pasted from the clipboard (`--from-clipboard`)
+    pub from_clipboard: bool,
+    /// Print the picked script's path instead of its name (`--print-path`)
+    pub print_path: bool,
+    /// Script highlighted when the TUI opens (`--select <name>`)
+    pub select: Option<String>,
+    /// Read and create scripts in ~/.config/hyprspace/profiles/<name> (`--profile <name>`)
+    pub profile: Option<String>,
+    /// Extra script directories, in order (`--dir <path>`, repeatable)
+    pub dirs: Vec<PathBuf>,
+    /// Query `launch` matches scripts against (`--filter`)
+    pub filter: Option<String>,
+    /// With `--filter`, launch straight away when a single script matches (`--first` / `--no-tui`)
+    pub first: bool,
+    /// Remaining positional arguments (subcommand and its operands)
+    pub args: Vec<String>,
+}
+
+impl Cli {
+    /// Parse the process arguments (without the program name).
+    pub fn parse(args: impl IntoIterator<Item = String>) -> io::Result<Self> {
+        let mut cli = Cli::default();
+        let mut args = args.into_iter();
+
+        while let Some(arg) = args.next() {
+            if let Some(value) = arg.strip_prefix("--instance=") {
+                cli.instance = Some(value.to_string());
+                continue;
+            }
+            if let Some(value) = arg.strip_prefix("--monitor=") {
+                cli.monitor = Some(parse_monitor(value)?);
+                continue;
+            }
+            if let Some(value) = arg.strip_prefix("--profile=") {
+                cli.profile = Some(parse_profile(value)?);
+                continue;
+            }
+            if let Some(value) = arg.strip_prefix("--ws=") {
+                cli.ws = Some(parse_ws(value)?);
+                continue;
+            }
+            if let Some(value) = arg.strip_prefix("--limit=") {
+                cli.limit = Some(parse_limit(value)?);
+                continue;
+            }
+            if let Some(value) = arg.strip_prefix("--wait-for-hyprland=") {
+                cli.wait_for_hyprland = Some(parse_seconds(value)?);
+                continue;
+            }
+            if let Some(value) = arg.strip_prefix("--select=") {
+                cli.select = Some(value.to_string());
+                continue;
+            }
+            if let Some(value) = arg.strip_prefix("--filter=") {
+                cli.filter = Some(value.to_string());
+                continue;
+            }
+            if let Some(value) = arg.strip_prefix("--dir=") {
+                cli.dirs.push(PathBuf::from(value));
+                continue;
+            }
+
+            match arg.as_str() {
+                "--instance" => {
+                    cli.instance = Some(args.next().ok_or_else(usage_error)?);
+                }
+                "--monitor" => {
+                    let value = args.next().ok_or_else(usage_error)?;
+                    cli.monitor = Some(parse_monitor(&value)?);
+                }
+                "--profile" => {
+                    let value = args.next().ok_or_else(usage_error)?;
+                    cli.profile = Some(parse_profile(&value)?);
+                }
+                "--ws" => {
+                    let value = args.next().ok_or_else(usage_error)?;
+                    cli.ws = Some(parse_ws(&value)?);
+                }
+                "--limit" => {
+                    let value = args.next().ok_or_else(usage_error)?;
+                    cli.limit = Some(parse_limit(&value)?);
+                }
+                "--wait-for-hyprland" => {
+                    let value = args.next().ok_or_else(usage_error)?;
+                    cli.wait_for_hyprland = Some(parse_seconds(&value)?);
+                }
+                "--filter" => {
+                    cli.filter = Some(args.next().ok_or_else(usage_error)?);
+                }
+                "--select" => {
+                    cli.select = Some(args.next().ok_or_else(usage_error)?);
+                }
+                "--dir" => {
+                    cli.dirs
+                        .push(PathBuf::from(args.next().ok_or_else(usage_error)?));
+                }
+                "--login-shell" => cli.login_shell = true,
+                "--batch" => cli.batch = true,
+                "--dry-run" => cli.dry_run = true,
+                "--scratch" => cli.scratch = true,
+                "--systemd-scope" => cli.systemd_scope = true,
+                "--read-only" => cli.read_only = true,
+                "--launch-single" => cli.launch_single = true,
+                "--yes" | "-y" => cli.yes = true,
+                "--time" => cli.time = true,
+                "--compact" => cli.compact = Some(true),
+                "--no-compact" => cli.compact = Some(false),
+                "--inline" => cli.inline = true,
+                "--watch" => cli.watch = true,
+                "--config-path" => cli.config_path = true,
+                "--json" => cli.json = true,
+                "--json-lines" => cli.json_lines = true,
+                "--archived" => cli.archived = true,
+                "--no-pager" => cli.no_pager = true,
+                "--print-path" => cli.print_path = true,
+                "--from-clipboard" => cli.from_clipboard = true,
+                "--first" | "--no-tui" => cli.first = true,
+                _ if arg.starts_with("--") => return Err(usage_error()),
+                _ => cli.args.push(arg),
+            }
+        }
+
+        Ok(cli)
+    }
+
+    /// The subcommand, if any
+    pub fn command(&self) -> Option<&str> {
+        self.arg(0)
+    }
+
+    /// Positional argument at `idx` (0 is the subcommand)
+    pub fn arg(&self, idx: usize) -> Option<&str> {
+        self.args.get(idx).map(String::as_str)
+    }
+}
+
+/// A workspace number, as `--ws` and `launch-ws` take it
+pub fn parse_ws(value: &str) -> io::Result<u32> {
+    // Hyprland numbers its workspaces from 1
+    value.parse().ok().filter(|&n| n > 0).ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("invalid workspace number '{value}'"),
+        )
+    })
+}
+
+fn parse_limit(value: &str) -> io::Result<usize> {
+    value.parse().ok().filter(|&n| n > 0).ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("invalid limit '{value}' (expected a number of scripts)"),
+        )
+    })
+}
+
+/// A profile name, which is a single directory name
+fn parse_profile(value: &str) -> io::Result<String> {
+    if value.is_empty() || value.starts_with('.') || value.contains('/') {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("invalid profile '{value}' (expected a directory name such as work)"),
+        ));
+    }
+    Ok(value.to_string())
+}
+
+/// A monitor name or `desc:` selector, which must fit inside a `[rules]` block
+fn parse_monitor(value: &str) -> io::Result<String> {
+    let value = value.trim();
+    if value.is_empty() || value.contains([';', ']']) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("invalid monitor '{value}' (expected a name such as DP-1)"),
+        ));
+    }
+    Ok(value.to_string())
+}
+
+fn parse_seconds(value: &str) -> io::Result<Duration> {
+    value
+        .parse::<f64>()
+        .ok()
+        .and_then(|secs| Duration::try_from_secs_f64(secs).ok())
+        .ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("invalid number of seconds '{value}'"),
+            )
+        })
+}
+
+/// Error returned for commands that would change scripts under `--read-only`
+pub fn read_only_error(command: &str) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::PermissionDenied,
+        format!("'{command}' changes workspace scripts and is disabled by --read-only"),
+    )
+}
+
+/// Error returned for malformed command lines
+pub fn usage_error() -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidInput, USAGE)
+}
@@ -0,0 +1,73 @@
+use std::io::{self, Write};
+use std::process::{Command, Stdio};
+use std::thread;
+
+/// Clipboard tools tried in order: Wayland first, then X11
+const COPY_TOOLS: &[(&str, &[&str])] = &[("wl-copy", &[]), ("xclip", &["-selection", "clipboard"])];
+
+/// Copy `text` to the system clipboard, returning the name of the tool used.
+///
+/// The tool is left running in the background, as X11 clipboards need their owner alive.
+pub fn copy(text: &str) -> io::Result<&'static str> {
+    for (tool, args) in COPY_TOOLS {
+        let spawned = Command::new(tool)
+            .args(*args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn();
+
+        let mut child = match spawned {
+            Ok(child) => child,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => continue,
+            Err(err) => return Err(err),
+        };
+
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin.write_all(text.as_bytes())?;
+        }
+
+        // Reap the process whenever it exits, without blocking the caller
+        thread::spawn(move || child.wait());
+
+        return Ok(tool);
+    }
+
+    Err(io::Error::new(
+        io::ErrorKind::NotFound,
+        "no clipboard tool found (install wl-clipboard or xclip)",
+    ))
+}
+
+/// Clipboard readers tried in order: Wayland first, then X11
+const PASTE_TOOLS: &[(&str, &[&str])] = &[
+    ("wl-paste", &["--no-newline"]),
+    ("xclip", &["-selection", "clipboard", "-o"]),
+];
+
+/// Read the text currently in the system clipboard.
+pub fn paste() -> io::Result<String> {
+    for (tool, args) in PASTE_TOOLS {
+        let output = match Command::new(tool)
+            .args(*args)
+            .stderr(Stdio::null())
+            .output()
+        {
+            Ok(output) => output,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => continue,
+            Err(err) => return Err(err),
+        };
+
+        if !output.status.success() {
+            return Err(io::Error::other(format!(
+                "{tool} could not read the clipboard"
+            )));
+        }
+        return Ok(String::from_utf8_lossy(&output.stdout).into_owned());
+    }
+
+    Err(io::Error::new(
+        io::ErrorKind::NotFound,
+        "no clipboard tool found (install wl-clipboard or xclip)",
+    ))
+}
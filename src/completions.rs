@@ -0,0 +1,150 @@
+use std::io;
+
+const BASH: &str = r#"_hyprspace_names() {
+    hyprspace list --json 2>/dev/null | grep -o '"name": *"[^"]*"' | sed 's/.*"\([^"]*\)"$/\1/'
+}
+
+_hyprspace() {
+    local cur prev
+    cur="${COMP_WORDS[COMP_CWORD]}"
+    prev="${COMP_WORDS[COMP_CWORD-1]}"
+
+    case "$prev" in
+        launch|append|duplicate|step|switch|export-def|install-autostart|uninstall-autostart)
+            COMPREPLY=($(compgen -W "$(_hyprspace_names)" -- "$cur"))
+            return
+            ;;
+        export|import|import-def)
+            COMPREPLY=($(compgen -f -- "$cur"))
+            return
+            ;;
+        completions)
+            COMPREPLY=($(compgen -W "bash zsh fish" -- "$cur"))
+            return
+            ;;
+        trash)
+            COMPREPLY=($(compgen -W "list restore empty" -- "$cur"))
+            return
+            ;;
+    esac
+
+    if [[ $COMP_CWORD -eq 1 ]]; then
+        COMPREPLY=($(compgen -W "launch launch-ws last pick new append duplicate step switch list trash doctor dedupe reset-term normalize export import export-def import-def install-autostart uninstall-autostart completions" -- "$cur"))
+    fi
+}
+
+complete -F _hyprspace hyprspace
+"#;
+
+const ZSH: &str = r#"#compdef hyprspace
+
+_hyprspace_names() {
+    local -a names
+    names=(${(f)"$(hyprspace list --json 2>/dev/null | grep -o '"name": *"[^"]*"' | sed 's/.*"\([^"]*\)"$/\1/')"})
+    _describe 'workspace' names
+}
+
+_hyprspace() {
+    local -a commands
+    commands=(
+        'launch:Launch a workspace script by name'
+        'launch-ws:Launch the script targeting a workspace number'
+        'last:Launch the most recently launched workspace again'
+        'pick:Choose a script and print its name or path'
+        'new:Create a new workspace script'
+        'append:Add a window to a workspace script'
+        'duplicate:Copy a workspace script under a new name and workspace number'
+        'step:Run the windows of a script one by one, confirming each'
+        'switch:Only switch to the workspace of a script, spawning nothing'
+        'list:List workspace scripts'
+        'trash:List, restore or purge deleted workspace scripts'
+        'doctor:Check all workspace scripts for problems'
+        'dedupe:Report scripts spawning the same or nearly the same windows'
+        'reset-term:Restore a terminal left garbled by a crash'
+        'normalize:Regenerate all workspace scripts with the current template'
+        'export:Bundle all workspace scripts into an archive'
+        'import:Restore workspace scripts from an archive'
+        'export-def:Print the TOML definition of a workspace script'
+        'import-def:Generate a workspace script from a TOML definition'
+        'install-autostart:Launch a workspace when Hyprland starts'
+        'uninstall-autostart:Stop launching a workspace when Hyprland starts'
+        'completions:Print a shell completion script'
+    )
+
+    if (( CURRENT == 2 )); then
+        _describe 'command' commands
+        return
+    fi
+
+    case "$words[2]" in
+        launch|append|duplicate|step|switch|export-def|install-autostart|uninstall-autostart) _hyprspace_names ;;
+        export|import|import-def) _files ;;
+        completions) _values 'shell' bash zsh fish ;;
+        trash) _values 'action' list restore empty ;;
+    esac
+}
+
+compdef _hyprspace hyprspace
+"#;
+
+const FISH: &str = r#"function __hyprspace_names
+    hyprspace list --json 2>/dev/null | string match -a -r -g '"name": *"([^"]*)"'
+end
+
+complete -c hyprspace -f
+complete -c hyprspace -n __fish_use_subcommand -a launch -d 'Launch a workspace script by name'
+complete -c hyprspace -n __fish_use_subcommand -a launch-ws -d 'Launch the script targeting a workspace number'
+complete -c hyprspace -n __fish_use_subcommand -a last -d 'Launch the most recently launched workspace again'
+complete -c hyprspace -n __fish_use_subcommand -a pick -d 'Choose a script and print its name or path'
+complete -c hyprspace -n __fish_use_subcommand -a new -d 'Create a new workspace script'
+complete -c hyprspace -n __fish_use_subcommand -a append -d 'Add a window to a workspace script'
+complete -c hyprspace -n __fish_use_subcommand -a duplicate -d 'Copy a workspace script under a new name and workspace number'
+complete -c hyprspace -n __fish_use_subcommand -a step -d 'Run the windows of a script one by one, confirming each'
+complete -c hyprspace -n __fish_use_subcommand -a switch -d 'Only switch to the workspace of a script, spawning nothing'
+complete -c hyprspace -n __fish_use_subcommand -a list -d 'List workspace scripts'
+complete -c hyprspace -n __fish_use_subcommand -a trash -d 'List, restore or purge deleted workspace scripts'
+complete -c hyprspace -n __fish_use_subcommand -a doctor -d 'Check all workspace scripts for problems'
+complete -c hyprspace -n __fish_use_subcommand -a dedupe -d 'Report scripts spawning the same or nearly the same windows'
+complete -c hyprspace -n __fish_use_subcommand -a reset-term -d 'Restore a terminal left garbled by a crash'
+complete -c hyprspace -n __fish_use_subcommand -a normalize -d 'Regenerate all workspace scripts with the current template'
+complete -c hyprspace -n __fish_use_subcommand -a export -d 'Bundle all workspace scripts into an archive'
+complete -c hyprspace -n __fish_use_subcommand -a import -d 'Restore workspace scripts from an archive'
+complete -c hyprspace -n __fish_use_subcommand -a export-def -d 'Print the TOML definition of a workspace script'
+complete -c hyprspace -n __fish_use_subcommand -a import-def -d 'Generate a workspace script from a TOML definition'
+complete -c hyprspace -n __fish_use_subcommand -a install-autostart -d 'Launch a workspace when Hyprland starts'
+complete -c hyprspace -n __fish_use_subcommand -a uninstall-autostart -d 'Stop launching a workspace when Hyprland starts'
+complete -c hyprspace -n __fish_use_subcommand -a completions -d 'Print a shell completion script'
+complete -c hyprspace -n '__fish_seen_subcommand_from launch append duplicate step switch export-def install-autostart uninstall-autostart' -a '(__hyprspace_names)'
+complete -c hyprspace -n '__fish_seen_subcommand_from export import import-def' -F
+complete -c hyprspace -n '__fish_seen_subcommand_from completions' -a 'bash zsh fish'
+complete -c hyprspace -n '__fish_seen_subcommand_from trash' -a 'list restore empty'
+complete -c hyprspace -n '__fish_seen_subcommand_from launch' -l filter -x -d 'Match scripts like the TUI filter'
+complete -c hyprspace -n '__fish_seen_subcommand_from launch' -l first -d 'Launch the only match without the TUI'
+complete -c hyprspace -n '__fish_seen_subcommand_from new' -l from-clipboard -d 'Start from hyprctl exec lines in the clipboard'
+complete -c hyprspace -n '__fish_seen_subcommand_from pick' -l print-path -d 'Print the script path instead of its name'
+complete -c hyprspace -n '__fish_seen_subcommand_from pick' -l select -x -a '(__hyprspace_names)' -d 'Highlight this script when the picker opens'
+complete -c hyprspace -n '__fish_seen_subcommand_from list' -l json -d 'Print JSON'
+complete -c hyprspace -n '__fish_seen_subcommand_from list' -l json-lines -d 'Print one JSON object per line'
+complete -c hyprspace -n '__fish_seen_subcommand_from list' -l archived -d 'List archived scripts instead'
+complete -c hyprspace -n '__fish_seen_subcommand_from list' -l limit -x -d 'List at most this many scripts'
+complete -c hyprspace -n '__fish_seen_subcommand_from list' -l no-pager -d 'Never page the list through $PAGER'
+complete -c hyprspace -n '__fish_seen_subcommand_from list' -l ws -x -d 'Only scripts targeting this workspace number'
+"#;
+
+/// Print the completion script for `shell` (bash, zsh or fish).
+pub fn print_completions(shell: &str) -> io::Result<()> {
+    let script = match shell {
+        "bash" => BASH,
+        "zsh" => ZSH,
+        "fish" => FISH,
+        other => {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("unsupported shell '{other}', expected bash, zsh or fish"),
+            ));
+        }
+    };
+
+    print!("{script}");
+    Ok(())
+}
@@ -0,0 +1,451 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::script::{
+    DEFAULT_HELPER, DEFAULT_RULES_TEMPLATE, RULES_PLACEHOLDERS, is_valid_helper_name,
+};
+use crate::state::state_dir;
+use crate::workspace::{resolve_workspace_dir, workspace_dir};
+
+/// Where the "Create new workspace script…" row is shown in the list
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CreateRow {
+    /// Above every script, always one keypress away
+    Top,
+    /// After the last script
+    #[default]
+    Bottom,
+}
+
+/// What pressing a digit does in the TUI
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DigitKeys {
+    /// Move the cursor to the next script whose workspace number starts with the digit
+    #[default]
+    Jump,
+    /// Launch the N-th visible script (`1`-`9`)
+    Launch,
+}
+
+/// What launching does with a script other users could have tampered with
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum UnsafeScripts {
+    /// Print a warning and launch anyway
+    #[default]
+    Warn,
+    /// Refuse to launch it
+    Refuse,
+}
+
+/// What launching does with a script needing a newer Hyprland than the running one
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OldHyprland {
+    /// Print a warning and launch anyway
+    Warn,
+    /// Refuse to launch it
+    #[default]
+    Refuse,
+}
+
+/// Which actions ask before going ahead (`[confirm]` table)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ConfirmSettings {
+    /// `delete`: moving the checked scripts to the trash in the TUI (default off)
+    pub delete: bool,
+    /// `launch`: launching from the TUI (default off)
+    pub launch: bool,
+    /// `overwrite`: replacing an existing script from the wizard, `append` or `import` (default on)
+    pub overwrite: bool,
+    /// `current_workspace`: launching a script onto the workspace you are on (default on)
+    pub current_workspace: bool,
+}
+
+impl Default for ConfirmSettings {
+    fn default() -> Self {
+        Self {
+            delete: false,
+            launch: false,
+            overwrite: true,
+            current_workspace: true,
+        }
+    }
+}
+
+/// How new scripts and windows are generated (`[generator]` table)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GeneratorSettings {
+    /// `helper`: name of the shell function spawning each window
+    pub helper: String,
+    /// `rules`: rules of each window, with `{workspace}`, `{width}`, `{height}`, `{x}`, `{y}`
+    /// and `{command}` placeholders
+    pub rules: String,
+    /// `max_windows`: the wizard asks before adding more windows than this (`0` = never)
+    pub max_windows: u32,
+}
+
+impl Default for GeneratorSettings {
+    fn default() -> Self {
+        Self {
+            helper: DEFAULT_HELPER.to_string(),
+            rules: DEFAULT_RULES_TEMPLATE.to_string(),
+            max_windows: 20,
+        }
+    }
+}
+
+/// Placeholders the TUI title and subtitle may use
+pub const TITLE_PLACEHOLDERS: &[&str] = &["count"];
+
+/// How the TUI presents itself (`[tui]` table)
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TuiSettings {
+    /// `title`: list title with a `{count}` placeholder, the built-in one when unset
+    pub title: Option<String>,
+    /// `subtitle`: line shown on the list's bottom border, with `{count}` too
+    pub subtitle: Option<String>,
+}
+
+/// User settings from ~/.config/hyprspace/config.toml
+#[derive(Debug, Clone, Default)]
+pub struct Config {
+    /// `create_row = "top" | "bottom"`
+    pub create_row: CreateRow,
+    /// `digit_keys = "jump" | "launch"`
+    pub digit_keys: DigitKeys,
+    /// `unsafe_scripts = "warn" | "refuse"`
+    pub unsafe_scripts: UnsafeScripts,
+    /// `old_hyprland = "warn" | "refuse"`
+    pub old_hyprland: OldHyprland,
+    /// `launch_single = true`: launch the only script instead of opening the TUI
+    pub launch_single: bool,
+    /// `[confirm]` table
+    pub confirm: ConfirmSettings,
+    /// `[generator]` table
+    pub generator: GeneratorSettings,
+    /// `[tui]` table
+    pub tui: TuiSettings,
+}
+
+/// A value on the right-hand side of `key = value`
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    String(String),
+    Integer(i64),
+    Bool(bool),
+}
+
+/// Returns the path to ~/.config/hyprspace/config.toml
+pub fn config_path() -> PathBuf {
+    workspace_dir().join("config.toml")
+}
+
+/// Print the resolved workspace directory, config file, state directory and extra
+/// script directories with whether each exists (`--config-path`).
+pub fn print_config_path(dirs: &[PathBuf]) {
+    let state = |path: &Path| match path.try_exists() {
+        Ok(true) if path.is_dir() => "directory",
+        Ok(true) => "file",
+        Ok(false) if path.is_symlink() => "broken symlink",
+        Ok(false) => "missing",
+        Err(_) => "unreadable",
+    };
+
+    let (dir, source) = resolve_workspace_dir();
+    println!(
+        "workspace dir: {} (from ${}, {})",
+        dir.to_string_lossy(),
+        source.variable(),
+        state(&dir)
+    );
+
+    let config = config_path();
+    println!(
+        "config file:   {} ({})",
+        config.to_string_lossy(),
+        state(&config)
+    );
+
+    let state_dir = state_dir();
+    println!(
+        "state dir:     {} ({})",
+        state_dir.to_string_lossy(),
+        state(&state_dir)
+    );
+
+    for extra in dirs.iter().filter(|d| **d != dir) {
+        println!(
+            "also reading:  {} ({})",
+            extra.to_string_lossy(),
+            state(extra)
+        );
+    }
+}
+
+/// Load the config file, falling back to defaults when it doesn't exist.
+pub fn load_config() -> io::Result<Config> {
+    let path = config_path();
+    let content = match fs::read_to_string(&path) {
+        Ok(content) => content,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(Config::default()),
+        Err(err) => return Err(err),
+    };
+
+    parse_config(&content).map_err(|message| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("{}: {message}", path.to_string_lossy()),
+        )
+    })
+}
+
+/// Build a [`Config`] from the file content; unknown keys are ignored.
+pub fn parse_config(content: &str) -> Result<Config, String> {
+    let mut config = Config::default();
+
+    for (line_no, key, value) in parse_entries(content)? {
+        let invalid = |expected: &str| format!("line {line_no}: `{key}` expects {expected}");
+
+        let confirm = &mut config.confirm;
+        let flag = match key.as_str() {
+            "create_row" => {
+                config.create_row = match value {
+                    Value::String(s) if s == "top" => CreateRow::Top,
+                    Value::String(s) if s == "bottom" => CreateRow::Bottom,
+                    _ => return Err(invalid("\"top\" or \"bottom\"")),
+                };
+                continue;
+            }
+            "digit_keys" => {
+                config.digit_keys = match value {
+                    Value::String(s) if s == "jump" => DigitKeys::Jump,
+                    Value::String(s) if s == "launch" => DigitKeys::Launch,
+                    _ => return Err(invalid("\"jump\" or \"launch\"")),
+                };
+                continue;
+            }
+            "unsafe_scripts" => {
+                config.unsafe_scripts = match value {
+                    Value::String(s) if s == "warn" => UnsafeScripts::Warn,
+                    Value::String(s) if s == "refuse" => UnsafeScripts::Refuse,
+                    _ => return Err(invalid("\"warn\" or \"refuse\"")),
+                };
+                continue;
+            }
+            "old_hyprland" => {
+                config.old_hyprland = match value {
+                    Value::String(s) if s == "warn" => OldHyprland::Warn,
+                    Value::String(s) if s == "refuse" => OldHyprland::Refuse,
+                    _ => return Err(invalid("\"warn\" or \"refuse\"")),
+                };
+                continue;
+            }
+            "generator.helper" => {
+                config.generator.helper = match value {
+                    Value::String(s) if is_valid_helper_name(&s) => s,
+                    _ => return Err(invalid("a shell function name (letters, digits and `_`)")),
+                };
+                continue;
+            }
+            "generator.rules" => {
+                let Value::String(template) = value else {
+                    return Err(invalid("a string"));
+                };
+                if let Some(unknown) = unknown_placeholder(&template, RULES_PLACEHOLDERS) {
+                    return Err(format!(
+                        "line {line_no}: unknown placeholder `{{{unknown}}}` in `{key}`, expected one of {}",
+                        RULES_PLACEHOLDERS.join(", ")
+                    ));
+                }
+                config.generator.rules = template;
+                continue;
+            }
+            "generator.max_windows" => {
+                config.generator.max_windows = match value {
+                    Value::Integer(n) => u32::try_from(n)
+                        .map_err(|_| invalid("a number of windows (0 to disable)"))?,
+                    _ => return Err(invalid("a number of windows (0 to disable)")),
+                };
+                continue;
+            }
+            "tui.title" | "tui.subtitle" => {
+                let Value::String(template) = value else {
+                    return Err(invalid("a string"));
+                };
+                if let Some(unknown) = unknown_placeholder(&template, TITLE_PLACEHOLDERS) {
+                    return Err(format!(
+                        "line {line_no}: unknown placeholder `{{{unknown}}}` in `{key}`, expected {}",
+                        TITLE_PLACEHOLDERS.join(", ")
+                    ));
+                }
+                let slot = if key == "tui.title" {
+                    &mut config.tui.title
+                } else {
+                    &mut config.tui.subtitle
+                };
+                *slot = Some(template);
+                continue;
+            }
+            "launch_single" => &mut config.launch_single,
+            "confirm.delete" => &mut confirm.delete,
+            "confirm.launch" => &mut confirm.launch,
+            "confirm.overwrite" => &mut confirm.overwrite,
+            "confirm.current_workspace" => &mut confirm.current_workspace,
+            _ => continue,
+        };
+        *flag = match value {
+            Value::Bool(b) => b,
+            _ => return Err(invalid("true or false")),
+        };
+    }
+
+    Ok(config)
+}
+
+/// The first `{name}` of `template` that is not one of `known`
+fn unknown_placeholder<'a>(template: &'a str, known: &[&str]) -> Option<&'a str> {
+    template
+        .split('{')
+        .skip(1)
+        .filter_map(|part| part.split_once('}').map(|(name, _)| name))
+        .find(|name| !known.contains(name))
+}
+
+/// Parse the small TOML subset hyprspace uses: `[table]` and `[[array]]` headers and
+/// `key = value` lines with strings, integers and booleans.
+///
+/// Keys inside a table are returned dotted (`table.key`), with their line number; keys
+/// of the N-th `[[array]]` table (from 0) come as `array.N.key`.
+pub(crate) fn parse_entries(content: &str) -> Result<Vec<(usize, String, Value)>, String> {
+    let mut entries = Vec::new();
+    let mut table = String::new();
+    let mut array_lengths: Vec<(String, usize)> = Vec::new();
+
+    for (idx, raw) in content.lines().enumerate() {
+        let line_no = idx + 1;
+        let line = strip_comment(raw).trim();
+        if line.is_empty() {
+            continue;
+        }
+
+        if let Some(name) = line.strip_prefix("[[").and_then(|l| l.strip_suffix("]]")) {
+            let name = name.trim();
+            let index = match array_lengths.iter_mut().find(|(known, _)| known == name) {
+                Some((_, len)) => {
+                    *len += 1;
+                    *len - 1
+                }
+                None => {
+                    array_lengths.push((name.to_string(), 1));
+                    0
+                }
+            };
+            table = format!("{name}.{index}");
+            continue;
+        }
+        if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            table = name.trim().to_string();
+            continue;
+        }
+
+        let Some((key, value)) = line.split_once('=') else {
+            return Err(format!("line {line_no}: expected `key = value`"));
+        };
+        let key = key.trim();
+        if key.is_empty() {
+            return Err(format!("line {line_no}: missing key"));
+        }
+
+        let value = parse_value(value.trim())
+            .ok_or_else(|| format!("line {line_no}: invalid value for `{key}`"))?;
+        let key = if table.is_empty() {
+            key.to_string()
+        } else {
+            format!("{table}.{key}")
+        };
+        entries.push((line_no, key, value));
+    }
+
+    Ok(entries)
+}
+
+/// Drop a trailing `# comment`, leaving `#` inside quoted strings alone.
+fn strip_comment(line: &str) -> &str {
+    let mut in_string = false;
+    let mut escaped = false;
+    for (idx, c) in line.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' if in_string => escaped = true,
+            '"' => in_string = !in_string,
+            '#' if !in_string => return &line[..idx],
+            _ => {}
+        }
+    }
+    line
+}
+
+fn parse_value(text: &str) -> Option<Value> {
+    if let Some(inner) = text.strip_prefix('"').and_then(|t| t.strip_suffix('"')) {
+        return Some(Value::String(unescape(inner)?));
+    }
+    match text {
+        "true" => Some(Value::Bool(true)),
+        "false" => Some(Value::Bool(false)),
+        _ => text.replace('_', "").parse().ok().map(Value::Integer),
+    }
+}
+
+/// Resolve the basic string escapes (`\"`, `\\`, `\n`, `\t`).
+fn unescape(text: &str) -> Option<String> {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next()? {
+            '"' => out.push('"'),
+            '\\' => out.push('\\'),
+            'n' => out.push('\n'),
+            't' => out.push('\t'),
+            _ => return None,
+        }
+    }
+    Some(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn array_tables_are_numbered_per_name() {
+        let entries = parse_entries(
+            "top = 1\n[[window]]\ncommand = \"kitty\" # first\n\n[tui]\ntitle = \"a # b\"\n[[window]]\ncommand = \"firefox\"\n[[rule]]\nfloat = true\n",
+        )
+        .unwrap();
+        let keys: Vec<(usize, &str)> = entries
+            .iter()
+            .map(|(line_no, key, _)| (*line_no, key.as_str()))
+            .collect();
+        assert_eq!(
+            keys,
+            [
+                (1, "top"),
+                (3, "window.0.command"),
+                (6, "tui.title"),
+                (8, "window.1.command"),
+                (10, "rule.0.float")
+            ]
+        );
+        assert_eq!(entries[2].2, Value::String("a # b".to_string()));
+        assert_eq!(entries[4].2, Value::Bool(true));
+
+        assert_eq!(
+            parse_entries("[[window]]\nkitty\n").unwrap_err(),
+            "line 2: expected `key = value`"
+        );
+    }
+}
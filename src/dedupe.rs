@@ -0,0 +1,236 @@
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+use crate::config::Config;
+use crate::script::ScriptDocument;
+use crate::workspace::{WorkspaceEntry, display_name, has_multiple_sources, list_all_workspaces};
+
+/// Share of commands two scripts must have in common to be reported as near duplicates
+const NEAR_THRESHOLD: f64 = 0.75;
+
+/// The windows of a script as `(command, rules without the workspace)`, sorted so
+/// their order in the script doesn't matter
+struct Profile<'a> {
+    ws: &'a WorkspaceEntry,
+    windows: Vec<(String, String)>,
+}
+
+/// How many entries `a` and `b` have in common, counting repeats
+fn common_count<T: Eq + std::hash::Hash>(
+    a: impl Iterator<Item = T>,
+    b: impl Iterator<Item = T>,
+) -> usize {
+    let mut counts: HashMap<T, usize> = HashMap::new();
+    for item in a {
+        *counts.entry(item).or_default() += 1;
+    }
+    b.filter(|item| {
+        counts
+            .get_mut(item)
+            .filter(|count| **count > 0)
+            .map(|count| *count -= 1)
+            .is_some()
+    })
+    .count()
+}
+
+/// Why two non-identical scripts look alike, if they do
+fn similarity(a: &Profile, b: &Profile) -> Option<String> {
+    let commands = common_count(
+        a.windows.iter().map(|w| &w.0),
+        b.windows.iter().map(|w| &w.0),
+    );
+    let largest = a.windows.len().max(b.windows.len());
+
+    if commands == a.windows.len() && commands == b.windows.len() {
+        let same_layout = common_count(a.windows.iter(), b.windows.iter());
+        if same_layout == largest {
+            return None;
+        }
+        return Some(format!(
+            "same commands, {} of {largest} window(s) laid out differently",
+            largest - same_layout
+        ));
+    }
+
+    let share = commands as f64 / largest as f64;
+    (share >= NEAR_THRESHOLD).then(|| {
+        format!(
+            "{commands} of {largest} commands in common ({:.0}%)",
+            share * 100.0
+        )
+    })
+}
+
+/// Compare the windows of every script in `dirs` and print the identical or nearly
+/// identical ones (`hyprspace dedupe`). Returns the number of groups and pairs reported.
+///
+/// Workspace numbers and window order are ignored: two scripts spawning the same
+/// commands with the same rules on different workspaces are identical.
+pub fn run_dedupe(dirs: &[PathBuf], config: &Config) -> io::Result<usize> {
+    let workspaces = list_all_workspaces(dirs)?;
+    let multiple_sources = has_multiple_sources(&workspaces);
+    let profiles: Vec<Profile> = workspaces
+        .iter()
+        .filter(|ws| ws.read_error.is_none())
+        .filter_map(|ws| {
+            let content = fs::read_to_string(&ws.full_path).ok()?;
+            let mut windows: Vec<(String, String)> =
+                ScriptDocument::parse_with_helper(&content, &config.generator.helper)
+                    .windows()
+                    .iter()
+                    .map(|w| (w.command.trim().to_string(), w.rules_without_workspace()))
+                    .collect();
+            windows.sort();
+            (!windows.is_empty()).then_some(Profile { ws, windows })
+        })
+        .collect();
+
+    println!("Compared {} script(s) with windows.\n", profiles.len());
+
+    // Identical scripts first, grouped; only one of each group is compared further
+    let mut groups: Vec<Vec<&Profile>> = Vec::new();
+    for profile in &profiles {
+        match groups
+            .iter_mut()
+            .find(|group| group[0].windows == profile.windows)
+        {
+            Some(group) => group.push(profile),
+            None => groups.push(vec![profile]),
+        }
+    }
+
+    let mut reported = 0;
+    for group in groups.iter().filter(|group| group.len() > 1) {
+        let names: Vec<String> = group
+            .iter()
+            .map(|p| display_name(p.ws, multiple_sources))
+            .collect();
+        println!(
+            "identical windows ({} window(s)): {}",
+            group[0].windows.len(),
+            names.join(", ")
+        );
+        reported += 1;
+    }
+
+    for (i, a) in groups.iter().enumerate() {
+        for b in &groups[i + 1..] {
+            if let Some(reason) = similarity(a[0], b[0]) {
+                println!(
+                    "{} ↔ {}: {reason}",
+                    display_name(a[0].ws, multiple_sources),
+                    display_name(b[0].ws, multiple_sources)
+                );
+                reported += 1;
+            }
+        }
+    }
+
+    if reported == 0 {
+        println!("No duplicates found.");
+    } else {
+        println!("\n{reported} group(s) or pair(s) could be consolidated.");
+    }
+    Ok(reported)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry() -> WorkspaceEntry {
+        WorkspaceEntry {
+            workspace_num: Some(1),
+            ..WorkspaceEntry::new(PathBuf::from("workspace-test.sh"))
+        }
+    }
+
+    /// A profile of `windows`, given as `(command, rules)`
+    fn profile<'a>(ws: &'a WorkspaceEntry, windows: &[(&str, &str)]) -> Profile<'a> {
+        let mut windows: Vec<(String, String)> = windows
+            .iter()
+            .map(|(command, rules)| (command.to_string(), rules.to_string()))
+            .collect();
+        windows.sort();
+        Profile { ws, windows }
+    }
+
+    #[test]
+    fn common_count_counts_repeats_once_per_match() {
+        assert_eq!(
+            common_count([1, 1, 2].into_iter(), [1, 2, 2].into_iter()),
+            2
+        );
+        assert_eq!(common_count([1, 1].into_iter(), [1, 1, 1].into_iter()), 2);
+        assert_eq!(common_count([1, 2].into_iter(), [3].into_iter()), 0);
+        assert_eq!(common_count(std::iter::empty::<u8>(), [1].into_iter()), 0);
+    }
+
+    #[test]
+    fn similarity_explains_what_two_scripts_share() {
+        let ws = entry();
+        let base = profile(&ws, &[("kitty", "float"), ("firefox", ""), ("nvim .", "")]);
+
+        assert_eq!(
+            similarity(
+                &base,
+                &profile(&ws, &[("nvim .", ""), ("kitty", "float"), ("firefox", "")])
+            ),
+            None
+        );
+        assert_eq!(
+            similarity(
+                &base,
+                &profile(&ws, &[("kitty", ""), ("firefox", ""), ("nvim .", "float")])
+            )
+            .as_deref(),
+            Some("same commands, 2 of 3 window(s) laid out differently")
+        );
+
+        let four = profile(
+            &ws,
+            &[
+                ("kitty", "float"),
+                ("firefox", ""),
+                ("nvim .", ""),
+                ("htop", ""),
+            ],
+        );
+        assert_eq!(
+            similarity(&base, &four).as_deref(),
+            Some("3 of 4 commands in common (75%)")
+        );
+        assert_eq!(
+            similarity(
+                &base,
+                &profile(&ws, &[("kitty", "float"), ("firefox", ""), ("htop", "")])
+            ),
+            None
+        );
+
+        // A repeated command only matches as many times as the other script has it
+        let kitties = profile(
+            &ws,
+            &[("kitty", ""), ("kitty", ""), ("kitty", ""), ("kitty", "")],
+        );
+        let one_kitty = profile(
+            &ws,
+            &[("kitty", ""), ("firefox", ""), ("nvim .", ""), ("htop", "")],
+        );
+        assert_eq!(similarity(&kitties, &one_kitty), None);
+        assert_eq!(
+            similarity(
+                &kitties,
+                &profile(
+                    &ws,
+                    &[("kitty", ""), ("kitty", ""), ("kitty", ""), ("htop", "")]
+                )
+            )
+            .as_deref(),
+            Some("3 of 4 commands in common (75%)")
+        );
+    }
+}
@@ -0,0 +1,336 @@
+//! Portable TOML definitions of a single script (`export-def` / `import-def`).
+//!
+//! A definition holds what the generator needs to rebuild the script: its workspace
+//! (`workspace = N`, or `special = "<name>"` for a special one), headers and windows. The Hyprland instance stays out of it since it only makes sense
+//! on the machine that wrote it.
+
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use crate::config::{Config, Value, parse_entries};
+use crate::script::{ScriptDocument, ScriptSpec, Unit, WindowSpec, build_script_content};
+use crate::workspace::{
+    Workspace, WorkspaceEntry, confirm_rewrite, is_valid_short_name, set_executable,
+};
+
+/// Quote `text` as a TOML basic string.
+fn toml_string(text: &str) -> String {
+    let mut out = String::with_capacity(text.len() + 2);
+    out.push('"');
+    for c in text.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\t' => out.push_str("\\t"),
+            _ => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+/// The TOML definition of `ws` (`hyprspace export-def <name>`).
+///
+/// Fails when the script has content the definition can't carry, like hand-written lines.
+pub fn export_definition(ws: &WorkspaceEntry, config: &Config) -> io::Result<String> {
+    let invalid = |reason: String| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("{}: {reason}", ws.base_name),
+        )
+    };
+
+    let content = fs::read_to_string(&ws.full_path)?;
+    let Some(workspace) = ws.workspace() else {
+        return Err(invalid(
+            "no `hyprctl dispatch workspace N` line".to_string(),
+        ));
+    };
+    let doc = ScriptDocument::parse_with_helper(&content, &config.generator.helper);
+    if let Some(content) = doc.hand_edited_content() {
+        return Err(invalid(format!("hand-edited, {content} can't be exported")));
+    }
+
+    let mut out = format!("# hyprspace definition of {}\n", ws.base_name);
+    out.push_str(&format!("name = {}\n", toml_string(&ws.name_short)));
+    match workspace {
+        Workspace::Numbered(num) => out.push_str(&format!("workspace = {num}\n")),
+        Workspace::Special(name) => out.push_str(&format!("special = {}\n", toml_string(&name))),
+    }
+    if let Some(description) = &ws.description {
+        out.push_str(&format!("description = {}\n", toml_string(description)));
+    }
+    if let Some(icon) = &ws.icon {
+        out.push_str(&format!("icon = {}\n", toml_string(icon)));
+    }
+    if let Some(order) = ws.order {
+        out.push_str(&format!("order = {order}\n"));
+    }
+    if doc.closes_existing() {
+        out.push_str("close_existing = true\n");
+    }
+
+    for window in doc.windows() {
+        out.push_str("\n[[window]]\n");
+        out.push_str(&format!("rules = {}\n", toml_string(&window.rules)));
+        out.push_str(&format!("command = {}\n", toml_string(&window.command)));
+        if let Some(cwd) = &window.cwd {
+            out.push_str(&format!("cwd = {}\n", toml_string(cwd)));
+        }
+        if let Some(delay) = &window.delay {
+            out.push_str(&format!("delay = {}\n", toml_string(delay)));
+        }
+        if let Some(hint) = &window.layout_hint {
+            out.push_str(&format!("layout_hint = {}\n", toml_string(hint)));
+        }
+        if window.focus {
+            out.push_str("focus = true\n");
+        }
+    }
+    Ok(out)
+}
+
+/// A definition read back from TOML: the script's short name and how to generate it
+#[derive(Debug)]
+pub struct Definition {
+    pub name: String,
+    pub spec: ScriptSpec,
+}
+
+/// Parse a definition written by [`export_definition`]; unknown keys are ignored.
+///
+/// The generated script uses `helper` and targets `instance`, if any.
+pub fn parse_definition(
+    content: &str,
+    helper: &str,
+    instance: Option<&str>,
+) -> Result<Definition, String> {
+    let mut name = None;
+    let mut workspace_num = None;
+    let mut special = None;
+    let mut description = None;
+    let mut icon = None;
+    let mut order = None;
+    let mut close_existing = false;
+    let mut windows: Vec<WindowSpec> = Vec::new();
+
+    for (line_no, key, value) in parse_entries(content)? {
+        let invalid = |expected: &str| format!("line {line_no}: `{key}` expects {expected}");
+
+        if let Some(rest) = key.strip_prefix("window.") {
+            let Some((index, field)) = rest.split_once('.') else {
+                continue;
+            };
+            let index: usize = index
+                .parse()
+                .map_err(|_| format!("line {line_no}: malformed `{key}`"))?;
+            while windows.len() <= index {
+                windows.push(WindowSpec::default());
+            }
+            let window = &mut windows[index];
+            match (field, value) {
+                ("rules", Value::String(s)) => window.rules = s,
+                ("command", Value::String(s)) => window.command = s,
+                ("cwd", Value::String(s)) => window.cwd = Some(s),
+                ("delay", Value::String(s)) => window.delay = Some(s),
+                ("delay", Value::Integer(n)) if n >= 0 => window.delay = Some(n.to_string()),
+                ("layout_hint", Value::String(s)) => window.layout_hint = Some(s),
+                ("focus", Value::Bool(b)) => window.focus = b,
+                ("rules" | "command" | "cwd" | "layout_hint", _) => {
+                    return Err(invalid("a string"));
+                }
+                ("delay", _) => return Err(invalid("a string or a number of seconds")),
+                ("focus", _) => return Err(invalid("true or false")),
+                _ => {}
+            }
+            continue;
+        }
+
+        match (key.as_str(), value) {
+            ("name", Value::String(s)) => name = Some(s),
+            ("workspace", Value::Integer(n)) => {
+                workspace_num = Some(
+                    u32::try_from(n)
+                        .ok()
+                        .filter(|&n| n > 0)
+                        .ok_or_else(|| invalid("a positive number"))?,
+                )
+            }
+            ("special", Value::String(s)) => special = Some(s),
+            ("description", Value::String(s)) => description = Some(s),
+            ("icon", Value::String(s)) => icon = Some(s),
+            ("order", Value::Integer(n)) => {
+                order = Some(i32::try_from(n).map_err(|_| invalid("a number"))?)
+            }
+            ("close_existing", Value::Bool(b)) => close_existing = b,
+            ("name" | "special" | "description" | "icon", _) => return Err(invalid("a string")),
+            ("workspace", _) => return Err(invalid("a positive number")),
+            ("order", _) => return Err(invalid("a number")),
+            ("close_existing", _) => return Err(invalid("true or false")),
+            _ => {}
+        }
+    }
+
+    let name = name.ok_or("missing `name`")?;
+    if !is_valid_short_name(&name) {
+        return Err(format!(
+            "`name` {name:?} may only use letters, digits, `_` and `-`"
+        ));
+    }
+    let workspace = match (workspace_num, special) {
+        (Some(num), None) => Workspace::Numbered(num),
+        (None, Some(special)) if !is_valid_short_name(&special) => {
+            return Err(format!(
+                "`special` {special:?} may only use letters, digits, `_` and `-`"
+            ));
+        }
+        (None, Some(special)) => Workspace::Special(special),
+        (Some(_), Some(_)) => return Err("`workspace` and `special` can't both be set".to_string()),
+        (None, None) => return Err("missing `workspace`".to_string()),
+    };
+    for (idx, window) in windows.iter_mut().enumerate() {
+        if window.command.trim().is_empty() {
+            return Err(format!("window #{} has no `command`", idx + 1));
+        }
+        window.unit = Unit::of_rules(&window.rules);
+    }
+
+    let spec = ScriptSpec {
+        workspace,
+        description,
+        icon,
+        order,
+        instance: instance.map(str::to_string),
+        close_existing,
+        helper: helper.to_string(),
+        windows,
+    };
+    Ok(Definition { name, spec })
+}
+
+/// Generate a native script into `dir` from the TOML definition in `file`
+/// (`hyprspace import-def <file.toml>`).
+///
+/// An existing script of the same name is only replaced once the changes are confirmed.
+pub fn import_definition(
+    dir: &Path,
+    file: &Path,
+    instance: Option<&str>,
+    config: &Config,
+) -> io::Result<()> {
+    let content = fs::read_to_string(file)?;
+    let definition =
+        parse_definition(&content, &config.generator.helper, instance).map_err(|message| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("{}: {message}", file.to_string_lossy()),
+            )
+        })?;
+
+    let path = dir.join(format!("workspace-{}.sh", definition.name));
+    let script = build_script_content(&definition.spec);
+    if !confirm_rewrite(&path, &script, config.confirm.overwrite)? {
+        // An unchanged script was already reported as such
+        if fs::read_to_string(&path).is_ok_and(|old| old != script) {
+            println!("Aborted, {} was not changed.", path.to_string_lossy());
+        }
+        return Ok(());
+    }
+
+    fs::create_dir_all(dir)?;
+    fs::write(&path, script)?;
+    set_executable(&path, true)?;
+    println!("Created script: {}", path.to_string_lossy());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::script::DEFAULT_HELPER;
+    use crate::workspace::list_workspaces;
+    use std::env;
+    use std::process;
+
+    fn window(rules: &str, command: &str) -> WindowSpec {
+        WindowSpec {
+            rules: rules.to_string(),
+            command: command.to_string(),
+            unit: Unit::of_rules(rules),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn exported_definitions_rebuild_the_same_script() {
+        let spec = ScriptSpec {
+            description: Some("Notes \"and\" \\ logs".to_string()),
+            icon: Some("📝".to_string()),
+            order: Some(-2),
+            close_existing: true,
+            windows: vec![
+                WindowSpec {
+                    cwd: Some("~/notes".to_string()),
+                    focus: true,
+                    ..window(
+                        "workspace 3 silent; float; size 50% 50%",
+                        "kitty --class notes",
+                    )
+                },
+                WindowSpec {
+                    delay: Some("1.5".to_string()),
+                    layout_hint: Some("preselect r".to_string()),
+                    ..window("workspace 3 silent", "firefox")
+                },
+            ],
+            ..ScriptSpec::new(Workspace::Numbered(3))
+        };
+        let content = build_script_content(&spec);
+
+        let dir = env::temp_dir().join(format!("hyprspace-definition-{}", process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("workspace-notes.sh"), &content).unwrap();
+        let entries = list_workspaces(&dir).unwrap();
+        let exported = export_definition(&entries[0], &Config::default());
+        fs::remove_dir_all(&dir).unwrap();
+
+        let definition = parse_definition(&exported.unwrap(), DEFAULT_HELPER, None).unwrap();
+        assert_eq!(definition.name, "notes");
+        assert_eq!(definition.spec.windows, spec.windows);
+        assert_eq!(build_script_content(&definition.spec), content);
+    }
+
+    #[test]
+    fn incomplete_definitions_are_rejected() {
+        let parse = |content: &str| {
+            parse_definition(content, DEFAULT_HELPER, None).map(|definition| definition.spec)
+        };
+
+        assert_eq!(parse("workspace = 1\n").unwrap_err(), "missing `name`");
+        assert_eq!(
+            parse("name = \"web\"\n").unwrap_err(),
+            "missing `workspace`"
+        );
+        assert_eq!(
+            parse("name = \"web\"\nworkspace = 1\nspecial = \"magic\"\n").unwrap_err(),
+            "`workspace` and `special` can't both be set"
+        );
+        assert_eq!(
+            parse("name = \"web\"\nworkspace = 1\n\n[[window]]\nrules = \"float\"\n\n[[window]]\nrules = \"float\"\ncommand = \"kitty\"\n").unwrap_err(),
+            "window #1 has no `command`"
+        );
+        assert_eq!(
+            parse("name = \"web\"\nworkspace = 0\n").unwrap_err(),
+            "line 2: `workspace` expects a positive number"
+        );
+
+        let spec = parse(
+            "name = \"term\"\nspecial = \"magic\"\n\n[[window]]\ncommand = \"kitty\"\ndelay = 2\n",
+        )
+        .unwrap();
+        assert_eq!(spec.workspace, Workspace::Special("magic".to_string()));
+        assert_eq!(spec.windows[0].delay.as_deref(), Some("2"));
+    }
+}
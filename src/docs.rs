@@ -0,0 +1,72 @@
+use std::io;
+use std::process::{Command, Stdio};
+use std::thread;
+
+/// Hyprland wiki page describing window rules
+pub const WINDOW_RULES_URL: &str = "https://wiki.hyprland.org/Configuring/Window-Rules/";
+
+/// Hyprland wiki page listing dispatchers
+pub const DISPATCHERS_URL: &str = "https://wiki.hyprland.org/Configuring/Dispatchers/";
+
+/// Rules applied once when the window opens
+const STATIC_RULES: &[&str] = &[
+    "float",
+    "tile",
+    "fullscreen",
+    "maximize",
+    "move",
+    "size",
+    "center",
+    "pseudo",
+    "monitor",
+    "workspace",
+    "pin",
+    "noinitialfocus",
+    "group",
+    "suppressevent",
+    "fullscreenstate",
+];
+
+/// `layoutmsg` messages of the dwindle layout; anything else is taken for master
+const DWINDLE_MESSAGES: &[&str] = &["preselect", "togglesplit", "swapsplit", "movetoroot"];
+
+/// Wiki section documenting the window rule `keyword` (e.g. `size`).
+pub fn rule_doc_url(keyword: &str) -> String {
+    let section = if STATIC_RULES.contains(&keyword) {
+        "static-rules"
+    } else {
+        "dynamic-rules"
+    };
+    format!("{WINDOW_RULES_URL}#{section}")
+}
+
+/// Wiki section documenting a `layoutmsg` message (e.g. `preselect r`).
+pub fn layoutmsg_doc_url(message: &str) -> &'static str {
+    let first = message.split_whitespace().next().unwrap_or_default();
+    if DWINDLE_MESSAGES.contains(&first) {
+        "https://wiki.hyprland.org/Configuring/Dwindle-Layout/#dispatchers"
+    } else {
+        "https://wiki.hyprland.org/Configuring/Master-Layout/#dispatchers"
+    }
+}
+
+/// Open `url` in the default browser through `xdg-open`, without waiting for it.
+pub fn open_url(url: &str) -> io::Result<()> {
+    let mut child = Command::new("xdg-open")
+        .arg(url)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|err| {
+            if err.kind() == io::ErrorKind::NotFound {
+                io::Error::new(err.kind(), "xdg-open not found (install xdg-utils)")
+            } else {
+                err
+            }
+        })?;
+
+    // Reap the process whenever it exits, without blocking the caller
+    thread::spawn(move || child.wait());
+    Ok(())
+}
@@ -0,0 +1,168 @@
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
+
+use crate::launcher::{command_in_path, unsafe_permissions};
+use crate::script::{parse_windows, workspace_mismatches};
+use crate::workspace::{
+    display_name, has_multiple_sources, is_workspace_file_name, list_all_workspaces,
+    name_collisions, spans_multiple_workspaces,
+};
+
+/// Scan every script in `dirs`, print a health report and return the number of problems found.
+pub fn run_doctor(dirs: &[PathBuf]) -> io::Result<usize> {
+    let workspaces = list_all_workspaces(dirs)?;
+    let collisions = name_collisions(&workspaces);
+    let multiple_sources = has_multiple_sources(&workspaces);
+    let mut problems = 0usize;
+
+    let dir_list: Vec<String> = dirs
+        .iter()
+        .map(|d| d.to_string_lossy().into_owned())
+        .collect();
+    println!(
+        "Checking {} script(s) in {}\n",
+        workspaces.len(),
+        dir_list.join(", ")
+    );
+
+    // Which scripts target each workspace number, to spot duplicates
+    let mut targets: HashMap<u32, Vec<String>> = HashMap::new();
+    for ws in &workspaces {
+        if let Some(num) = ws.workspace_num {
+            targets
+                .entry(num)
+                .or_default()
+                .push(display_name(ws, multiple_sources));
+        }
+    }
+
+    for ws in &workspaces {
+        let mut issues = Vec::new();
+        let name = display_name(ws, multiple_sources);
+
+        if collisions.contains(&ws.name_short) {
+            let others: Vec<String> = workspaces
+                .iter()
+                .filter(|other| {
+                    other.name_short == ws.name_short && other.full_path != ws.full_path
+                })
+                .map(|other| other.full_path.to_string_lossy().into_owned())
+                .collect();
+            issues.push(format!(
+                "name '{}' is also used by {}",
+                ws.name_short,
+                others.join(", ")
+            ));
+        }
+
+        if let Some(err) = &ws.read_error {
+            issues.push(format!("cannot be read: {err}"));
+        } else {
+            // A script that vanished or became unreadable since the listing is reported, not fatal
+            match fs::metadata(&ws.full_path) {
+                Ok(metadata) if metadata.permissions().mode() & 0o111 == 0 => {
+                    issues.push("not executable (run chmod +x)".to_string());
+                }
+                Ok(_) => {}
+                Err(err) => issues.push(format!("cannot be inspected: {err}")),
+            }
+            if let Some(problem) = unsafe_permissions(&ws.full_path) {
+                issues.push(problem);
+            }
+
+            // Missing workspace line, malformed rules, invalid headers
+            issues.extend(ws.warnings.iter().cloned());
+
+            if let Some(num) = ws.workspace_num {
+                let others: Vec<&str> = targets[&num]
+                    .iter()
+                    .map(String::as_str)
+                    .filter(|other| *other != name)
+                    .collect();
+                if !others.is_empty() {
+                    issues.push(format!(
+                        "workspace {num} is also targeted by {}",
+                        others.join(", ")
+                    ));
+                }
+            }
+
+            match fs::read_to_string(&ws.full_path) {
+                Ok(content) => {
+                    let windows = parse_windows(&content);
+                    if let Some(num) = ws.workspace_num
+                        && !spans_multiple_workspaces(&content)
+                    {
+                        issues.extend(workspace_mismatches(num, &windows));
+                    }
+
+                    for window in &windows {
+                        if let Some(program) = window.program()
+                            && !program.contains(['$', '{'])
+                            && !command_in_path(program)
+                        {
+                            issues.push(format!("command not found in PATH: {program}"));
+                        }
+                    }
+                }
+                Err(err) => issues.push(format!("cannot be read: {err}")),
+            }
+        }
+
+        if issues.is_empty() {
+            println!("  ✔ {name}");
+        } else {
+            println!("  ✘ {name}");
+            for issue in &issues {
+                println!("      - {issue}");
+            }
+            problems += issues.len();
+        }
+    }
+
+    // Broken symlinks never show up in list_workspaces, look for them separately
+    for dir in dirs {
+        problems += report_broken_symlinks(dir, multiple_sources)?;
+    }
+
+    println!();
+    if problems == 0 {
+        println!("No problems found.");
+    } else {
+        println!("{problems} problem(s) found.");
+    }
+
+    Ok(problems)
+}
+
+/// Print every broken workspace symlink in `dir` and return how many there are.
+fn report_broken_symlinks(dir: &Path, full_paths: bool) -> io::Result<usize> {
+    if !dir.is_dir() {
+        return Ok(0);
+    }
+
+    let mut count = 0;
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        let name = path.file_name().map(|n| n.to_string_lossy().to_string());
+
+        if let Some(name) = name
+            && is_workspace_file_name(&name)
+            && path.is_symlink()
+            && !path.exists()
+        {
+            if full_paths {
+                println!("  ✘ {}", path.to_string_lossy());
+            } else {
+                println!("  ✘ {name}");
+            }
+            println!("      - broken symlink");
+            count += 1;
+        }
+    }
+
+    Ok(count)
+}
@@ -0,0 +1,85 @@
+use crate::workspace::WorkspaceEntry;
+
+/// Bonus for every matched character
+const SCORE_MATCH: i64 = 16;
+/// Extra bonus when a character directly follows the previous match
+const BONUS_CONSECUTIVE: i64 = 12;
+/// Extra bonus when a character starts the text or a word (after `-`, `_`, `.`, `/` or a space)
+const BONUS_WORD_START: i64 = 10;
+/// Penalty per skipped character between two matches
+const PENALTY_GAP: i64 = 1;
+
+/// A successful fuzzy match of a query against some text
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FuzzyMatch {
+    /// Higher is better
+    pub score: i64,
+    /// Byte offsets in the text of every matched character, ascending
+    pub positions: Vec<usize>,
+}
+
+fn is_word_start(chars: &[(usize, char)], idx: usize) -> bool {
+    idx == 0 || matches!(chars[idx - 1].1, '-' | '_' | '.' | '/' | ' ')
+}
+
+/// Match `query` as a case-insensitive subsequence of `text`, so `bke` matches `backend`.
+///
+/// Every possible position of the first query character is tried and the best scoring
+/// alignment wins: consecutive runs and word starts score higher, gaps lower.
+pub fn fuzzy_match(text: &str, query: &str) -> Option<FuzzyMatch> {
+    let query: Vec<char> = query.chars().flat_map(char::to_lowercase).collect();
+    if query.is_empty() {
+        return None;
+    }
+    let chars: Vec<(usize, char)> = text
+        .char_indices()
+        .map(|(offset, c)| (offset, c.to_lowercase().next().unwrap_or(c)))
+        .collect();
+
+    let mut best: Option<FuzzyMatch> = None;
+
+    for start in (0..chars.len()).filter(|&i| chars[i].1 == query[0]) {
+        let mut positions = vec![chars[start].0];
+        let mut score = SCORE_MATCH
+            + if is_word_start(&chars, start) {
+                BONUS_WORD_START
+            } else {
+                0
+            };
+        let mut last = start;
+
+        for &wanted in &query[1..] {
+            let Some(idx) = (last + 1..chars.len()).find(|&i| chars[i].1 == wanted) else {
+                // Later starts only leave fewer characters to match
+                return best;
+            };
+
+            score += SCORE_MATCH;
+            if idx == last + 1 {
+                score += BONUS_CONSECUTIVE;
+            } else {
+                score -= PENALTY_GAP * (idx - last - 1) as i64;
+            }
+            if is_word_start(&chars, idx) {
+                score += BONUS_WORD_START;
+            }
+            positions.push(chars[idx].0);
+            last = idx;
+        }
+
+        if best.as_ref().is_none_or(|b| score > b.score) {
+            best = Some(FuzzyMatch { score, positions });
+        }
+    }
+
+    best
+}
+
+/// Best score of `query` against a script's short name and file name (`None` = no match)
+pub fn score_workspace(ws: &WorkspaceEntry, query: &str) -> Option<i64> {
+    [&ws.name_short, &ws.base_name]
+        .into_iter()
+        .filter_map(|text| fuzzy_match(text, query))
+        .map(|m| m.score)
+        .max()
+}
@@ -0,0 +1,160 @@
+//! ASCII preview of where a script's windows land on the monitor.
+
+use crate::script::{Unit, WindowSpec, convert_rules};
+
+/// Where a floating window sits, in percent of the monitor
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Placement {
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+}
+
+/// Two percent values following a rule keyword, e.g. `10% 15%` (`onscreen` is skipped).
+fn percent_pair<'a>(words: impl Iterator<Item = &'a str>) -> Option<(f64, f64)> {
+    let mut values = words
+        .filter(|word| *word != "onscreen")
+        .map(|word| word.strip_suffix('%')?.parse::<f64>().ok());
+    let pair = (values.next()??, values.next()??);
+    values.next().is_none().then_some(pair)
+}
+
+/// Where `window` sits according to its `size` and `move` rules; without `move` (or with
+/// `center`) it is centered, as Hyprland does with floating windows.
+///
+/// Pixel values are read against `monitor` (logical width and height). Tiled windows,
+/// windows without a size, pixels without a monitor and expressions such as `100%-w`
+/// give `None`.
+pub fn placement(window: &WindowSpec, monitor: Option<(u32, u32)>) -> Option<Placement> {
+    if !window.is_floating() {
+        return None;
+    }
+    let rules = match (window.unit, monitor) {
+        (None | Some(Unit::Percent), _) => window.rules.clone(),
+        (Some(_), Some((width, height))) => {
+            convert_rules(&window.rules, Unit::Percent, width, height)
+        }
+        (Some(_), None) => return None,
+    };
+
+    let (mut size, mut position, mut center) = (None, None, false);
+    for rule in rules.split(';') {
+        let mut words = rule.split_whitespace();
+        match words.next() {
+            Some("size") => size = Some(percent_pair(words)?),
+            Some("move") => position = Some(percent_pair(words)?),
+            Some("center") => center = true,
+            _ => {}
+        }
+    }
+
+    let (width, height) = size?;
+    let (x, y) = match position {
+        Some(position) if !center => position,
+        _ => ((100.0 - width) / 2.0, (100.0 - height) / 2.0),
+    };
+    Some(Placement {
+        x,
+        y,
+        width,
+        height,
+    })
+}
+
+/// Draw `windows` on a `cols`×`rows` grid standing for the monitor: every placed window is
+/// a `+--+` outline with its number on the top edge. Later windows are drawn over earlier
+/// ones, and `on_top` (an index) over all of them.
+pub fn layout_grid(
+    windows: &[WindowSpec],
+    monitor: Option<(u32, u32)>,
+    cols: usize,
+    rows: usize,
+    on_top: Option<usize>,
+) -> Vec<String> {
+    let mut grid = vec![vec![' '; cols]; rows];
+    if cols < 2 || rows < 2 {
+        return grid.into_iter().map(String::from_iter).collect();
+    }
+
+    let mut order: Vec<usize> = (0..windows.len())
+        .filter(|idx| Some(*idx) != on_top)
+        .collect();
+    order.extend(on_top.filter(|idx| *idx < windows.len()));
+
+    // Percent to cell, keeping at least two cells per side so the outline shows
+    let span = |start: f64, length: f64, cells: usize| {
+        let last = cells - 1;
+        let from = ((start / 100.0 * cells as f64).floor().max(0.0) as usize).min(last - 1);
+        let to = (((start + length) / 100.0 * cells as f64).ceil() as usize).saturating_sub(1);
+        (from, to.clamp(from + 1, last))
+    };
+
+    for idx in order {
+        let Some(place) = placement(&windows[idx], monitor) else {
+            continue;
+        };
+        let (left, right) = span(place.x, place.width, cols);
+        let (top, bottom) = span(place.y, place.height, rows);
+
+        for row in [top, bottom] {
+            grid[row][left..=right].fill('-');
+        }
+        for line in &mut grid[top..=bottom] {
+            line[left] = '|';
+            line[right] = '|';
+        }
+        for (row, col) in [(top, left), (top, right), (bottom, left), (bottom, right)] {
+            grid[row][col] = '+';
+        }
+        for (offset, digit) in (idx + 1).to_string().chars().enumerate() {
+            let col = left + 1 + offset;
+            if col < right {
+                grid[top][col] = digit;
+            }
+        }
+    }
+
+    grid.into_iter().map(String::from_iter).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn layout_grid_outlines_placed_windows() {
+        let window = |rules: &str| WindowSpec {
+            rules: rules.to_string(),
+            command: "kitty".to_string(),
+            unit: Unit::of_rules(rules),
+            ..Default::default()
+        };
+        let windows = [
+            window("float; size 50% 50%; move 0% 0%"),
+            window("float; size 800 400; move 800 400"),
+            window("float; size 50% 50%; center"),
+            window("workspace 2; size 50% 50%"),
+            window("float; move 10% 10%"),
+        ];
+
+        let grid = layout_grid(&windows, Some((1600, 800)), 16, 8, None);
+        assert_eq!(
+            grid,
+            [
+                "+1-----+        ",
+                "|      |        ",
+                "|   +3-----+    ",
+                "+---|--+   |    ",
+                "    |   +2-|---+",
+                "    +------+   |",
+                "        |      |",
+                "        +------+",
+            ]
+        );
+        // Pixels can't be placed without the monitor size; tiled and unsized windows never are
+        assert!(placement(&windows[1], None).is_none());
+        assert!(placement(&windows[3], Some((1600, 800))).is_none());
+        assert!(placement(&windows[4], Some((1600, 800))).is_none());
+    }
+}
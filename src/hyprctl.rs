@@ -0,0 +1,377 @@
+use std::collections::HashSet;
+use std::env;
+use std::fmt;
+use std::io;
+use std::process::Command;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::json;
+use crate::runner::{CommandRunner, SystemRunner};
+
+/// Delay between two readiness checks in [`wait_until_ready`]
+const READY_POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Build a `hyprctl` command, targeting `instance` when set.
+pub fn hyprctl(instance: Option<&str>) -> Command {
+    let mut command = Command::new("hyprctl");
+    if let Some(sig) = instance {
+        command.arg("--instance").arg(sig);
+    }
+    command
+}
+
+/// Run `hyprctl <args>` and return its stdout, failing on a non-zero exit.
+pub fn run(instance: Option<&str>, args: &[&str]) -> io::Result<String> {
+    run_with(&mut SystemRunner, instance, args)
+}
+
+/// [`run`] through `runner`.
+pub fn run_with(
+    runner: &mut dyn CommandRunner,
+    instance: Option<&str>,
+    args: &[&str],
+) -> io::Result<String> {
+    let output = runner.output(hyprctl(instance).args(args)).map_err(|err| {
+        if err.kind() == io::ErrorKind::NotFound {
+            io::Error::new(err.kind(), "hyprctl not found, is Hyprland installed?")
+        } else {
+            err
+        }
+    })?;
+    let stdout = String::from_utf8_lossy(&output.stdout).trim().to_string();
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+        let message = if stderr.is_empty() { stdout } else { stderr };
+        return Err(io::Error::other(format!("hyprctl failed: {message}")));
+    }
+
+    Ok(stdout)
+}
+
+/// Run `hyprctl dispatch <args>` through `runner`, failing when the dispatcher rejects it.
+pub fn dispatch_with(
+    runner: &mut dyn CommandRunner,
+    instance: Option<&str>,
+    args: &[&str],
+) -> io::Result<()> {
+    let args: Vec<&str> = ["dispatch"]
+        .into_iter()
+        .chain(args.iter().copied())
+        .collect();
+    let reply = run_with(runner, instance, &args)?;
+
+    // hyprctl exits 0 even when the dispatcher rejects the call
+    if reply != "ok" && !reply.is_empty() {
+        return Err(io::Error::other(format!("hyprctl: {reply}")));
+    }
+
+    Ok(())
+}
+
+/// Spawn `command` with the given window `rules` through `hyprctl dispatch exec`.
+pub fn dispatch_exec(instance: Option<&str>, rules: &str, command: &str) -> io::Result<()> {
+    dispatch_exec_with(&mut SystemRunner, instance, rules, command)
+}
+
+/// [`dispatch_exec`] through `runner`.
+pub fn dispatch_exec_with(
+    runner: &mut dyn CommandRunner,
+    instance: Option<&str>,
+    rules: &str,
+    command: &str,
+) -> io::Result<()> {
+    let target = if rules.is_empty() {
+        command.to_string()
+    } else {
+        format!("[{rules}] {command}")
+    };
+    dispatch_with(runner, instance, &["exec", &target])
+}
+
+/// Ids of the workspaces that currently hold at least one window.
+///
+/// Returns nothing without calling hyprctl when no Hyprland instance is known.
+pub fn occupied_workspaces(instance: Option<&str>) -> io::Result<HashSet<u32>> {
+    if instance.is_none() && env::var_os("HYPRLAND_INSTANCE_SIGNATURE").is_none() {
+        return Ok(HashSet::new());
+    }
+
+    let reply = run(instance, &["-j", "workspaces"])?;
+    let parsed = json::parse(&reply).ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            "hyprctl workspaces -j returned invalid JSON",
+        )
+    })?;
+
+    Ok(parsed
+        .as_array()
+        .unwrap_or_default()
+        .iter()
+        .filter(|ws| ws.get("windows").and_then(json::Value::as_i64).unwrap_or(0) > 0)
+        .filter_map(|ws| u32::try_from(ws.get("id")?.as_i64()?).ok())
+        .collect())
+}
+
+/// Id of the focused workspace.
+///
+/// Returns `None` without calling hyprctl when no Hyprland instance is known.
+pub fn active_workspace(instance: Option<&str>) -> io::Result<Option<u32>> {
+    if instance.is_none() && env::var_os("HYPRLAND_INSTANCE_SIGNATURE").is_none() {
+        return Ok(None);
+    }
+
+    let reply = run(instance, &["-j", "activeworkspace"])?;
+    let parsed = json::parse(&reply).ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            "hyprctl activeworkspace -j returned invalid JSON",
+        )
+    })?;
+
+    Ok(parsed
+        .get("id")
+        .and_then(json::Value::as_i64)
+        .and_then(|id| u32::try_from(id).ok()))
+}
+
+/// Size of the focused monitor in layout pixels (its resolution divided by its scale),
+/// which is what `size` and `move` rules are expressed in.
+///
+/// Returns `None` without calling hyprctl when no Hyprland instance is known.
+pub fn focused_monitor_size(instance: Option<&str>) -> io::Result<Option<(u32, u32)>> {
+    if instance.is_none() && env::var_os("HYPRLAND_INSTANCE_SIGNATURE").is_none() {
+        return Ok(None);
+    }
+
+    let reply = run(instance, &["-j", "monitors"])?;
+    let parsed = json::parse(&reply).ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            "hyprctl monitors -j returned invalid JSON",
+        )
+    })?;
+
+    let monitors = parsed.as_array().unwrap_or_default();
+    let Some(monitor) = monitors
+        .iter()
+        .find(|m| matches!(m.get("focused"), Some(json::Value::Bool(true))))
+        .or(monitors.first())
+    else {
+        return Ok(None);
+    };
+    let scale = match monitor.get("scale") {
+        Some(json::Value::Number(scale)) if *scale > 0.0 => *scale,
+        _ => 1.0,
+    };
+    let logical = |key: &str| {
+        let pixels = monitor.get(key)?.as_i64()?;
+        u32::try_from((pixels as f64 / scale).round() as i64)
+            .ok()
+            .filter(|&n| n > 0)
+    };
+    Ok(logical("width").zip(logical("height")))
+}
+
+/// Wait for a window whose class (or initial class) is one of `classes`, on `workspace`
+/// when set, and focus it. The newest match wins.
+///
+/// Returns false when none showed up before `timeout`.
+pub fn focus_window_of_class(
+    runner: &mut dyn CommandRunner,
+    instance: Option<&str>,
+    classes: &[&str],
+    workspace: Option<u32>,
+    timeout: Duration,
+) -> io::Result<bool> {
+    let deadline = Instant::now() + timeout;
+
+    loop {
+        let reply = run_with(runner, instance, &["-j", "clients"])?;
+        let parsed = json::parse(&reply).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                "hyprctl clients -j returned invalid JSON",
+            )
+        })?;
+        let address = parsed
+            .as_array()
+            .unwrap_or_default()
+            .iter()
+            .filter(|client| {
+                workspace.is_none_or(|num| {
+                    client
+                        .get("workspace")
+                        .and_then(|ws| ws.get("id"))
+                        .and_then(json::Value::as_i64)
+                        == Some(i64::from(num))
+                })
+            })
+            .filter(|client| {
+                ["class", "initialClass"].iter().any(|key| {
+                    let class = client
+                        .get(key)
+                        .and_then(json::Value::as_str)
+                        .unwrap_or_default();
+                    classes.iter().any(|name| name.eq_ignore_ascii_case(class))
+                })
+            })
+            .filter_map(|client| client.get("address").and_then(json::Value::as_str))
+            .next_back();
+
+        if let Some(address) = address {
+            dispatch_with(
+                runner,
+                instance,
+                &["focuswindow", &format!("address:{address}")],
+            )?;
+            return Ok(true);
+        }
+        if Instant::now() >= deadline {
+            return Ok(false);
+        }
+        thread::sleep(READY_POLL_INTERVAL);
+    }
+}
+
+/// A Hyprland release number such as `0.41.2`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct HyprlandVersion {
+    pub major: u32,
+    pub minor: u32,
+    pub patch: u32,
+}
+
+impl HyprlandVersion {
+    /// Parse `0.40`, `0.41.2` or `v0.41.2`; a missing patch number counts as 0.
+    pub fn parse(text: &str) -> Option<Self> {
+        let text = text.trim();
+        let mut parts = text.strip_prefix('v').unwrap_or(text).split('.');
+        let version = HyprlandVersion {
+            major: parts.next()?.parse().ok()?,
+            minor: parts.next()?.parse().ok()?,
+            patch: parts.next().map_or(Some(0), |patch| patch.parse().ok())?,
+        };
+        parts.next().is_none().then_some(version)
+    }
+}
+
+impl fmt::Display for HyprlandVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+/// Version of the running Hyprland, from `hyprctl version -j`.
+///
+/// Returns `None` without calling hyprctl when no Hyprland instance is known, and when
+/// the reply carries no version it understands.
+pub fn running_version(instance: Option<&str>) -> io::Result<Option<HyprlandVersion>> {
+    if instance.is_none() && env::var_os("HYPRLAND_INSTANCE_SIGNATURE").is_none() {
+        return Ok(None);
+    }
+
+    let reply = run(instance, &["-j", "version"])?;
+    version_from_reply(&reply)
+}
+
+/// The version in a `hyprctl version -j` reply.
+fn version_from_reply(reply: &str) -> io::Result<Option<HyprlandVersion>> {
+    let parsed = json::parse(reply).ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            "hyprctl version -j returned invalid JSON",
+        )
+    })?;
+
+    // Recent releases report `version`, older ones only a `tag` such as `v0.40.0-52-g1c4c0ee4`
+    Ok(["version", "tag"].iter().find_map(|key| {
+        let text = parsed.get(key)?.as_str()?;
+        HyprlandVersion::parse(text.split('-').next()?)
+    }))
+}
+
+/// Whether a Hyprland instance is reachable: its signature is known and `hyprctl version` answers.
+fn is_ready(instance: Option<&str>) -> bool {
+    if instance.is_none() && env::var_os("HYPRLAND_INSTANCE_SIGNATURE").is_none() {
+        return false;
+    }
+    run(instance, &["version"]).is_ok()
+}
+
+/// Poll until Hyprland answers, for launches that may run before the compositor is up.
+pub fn wait_until_ready(instance: Option<&str>, timeout: Duration) -> io::Result<()> {
+    let deadline = Instant::now() + timeout;
+
+    while !is_ready(instance) {
+        if Instant::now() >= deadline {
+            return Err(io::Error::new(
+                io::ErrorKind::TimedOut,
+                format!(
+                    "Hyprland was not ready after {:.1}s (no HYPRLAND_INSTANCE_SIGNATURE or hyprctl version failed)",
+                    timeout.as_secs_f64()
+                ),
+            ));
+        }
+        thread::sleep(READY_POLL_INTERVAL);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn version(major: u32, minor: u32, patch: u32) -> Option<HyprlandVersion> {
+        Some(HyprlandVersion {
+            major,
+            minor,
+            patch,
+        })
+    }
+
+    #[test]
+    fn versions_parse_with_or_without_patch_and_prefix() {
+        assert_eq!(HyprlandVersion::parse("0.40"), version(0, 40, 0));
+        assert_eq!(HyprlandVersion::parse(" v0.41.2\n"), version(0, 41, 2));
+        assert_eq!(
+            HyprlandVersion::parse("0.41.2").unwrap().to_string(),
+            "0.41.2"
+        );
+        assert!(HyprlandVersion::parse("0.40") < HyprlandVersion::parse("0.40.1"));
+
+        for text in [
+            "",
+            "0",
+            "v",
+            "0.40.0-52-g1c4c0ee4",
+            "0.40.1.2",
+            "0.x",
+            "0.40.",
+            "V0.40",
+        ] {
+            assert_eq!(HyprlandVersion::parse(text), None, "{text}");
+        }
+    }
+
+    #[test]
+    fn running_version_falls_back_to_the_tag() {
+        assert_eq!(
+            version_from_reply(r#"{"version": "0.45.2", "tag": "v0.40.0"}"#).unwrap(),
+            version(0, 45, 2)
+        );
+        assert_eq!(
+            version_from_reply(r#"{"tag": "v0.40.0-52-g1c4c0ee4"}"#).unwrap(),
+            version(0, 40, 0)
+        );
+        assert_eq!(
+            version_from_reply(r#"{"version": "git", "tag": "v0.41.2-3-gabc"}"#).unwrap(),
+            version(0, 41, 2)
+        );
+        assert_eq!(version_from_reply(r#"{"branch": "main"}"#).unwrap(), None);
+        assert!(version_from_reply("Hyprland 0.40.0 built from branch").is_err());
+    }
+}
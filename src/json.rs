@@ -0,0 +1,216 @@
+use crate::workspace::WorkspaceEntry;
+
+/// Encode `s` as a JSON string literal.
+pub fn string(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+/// Encode an optional string, `null` when absent.
+pub fn opt_string(s: Option<&str>) -> String {
+    s.map(string).unwrap_or_else(|| "null".to_string())
+}
+
+/// Encode a workspace entry as a single-line JSON object.
+pub fn workspace_entry(ws: &WorkspaceEntry) -> String {
+    let workspace = ws
+        .workspace_num
+        .map(|n| n.to_string())
+        .unwrap_or_else(|| "null".to_string());
+
+    format!(
+        r#"{{"name": {}, "file": {}, "path": {}, "dir": {}, "workspace": {}, "special": {}, "description": {}, "icon": {}, "order": {}, "error": {}, "warnings": [{}]}}"#,
+        string(&ws.name_short),
+        string(&ws.base_name),
+        string(&ws.full_path.to_string_lossy()),
+        string(&ws.source_dir.to_string_lossy()),
+        workspace,
+        opt_string(ws.special.as_deref()),
+        opt_string(ws.description.as_deref()),
+        opt_string(ws.icon.as_deref()),
+        ws.order
+            .map(|n| n.to_string())
+            .unwrap_or_else(|| "null".to_string()),
+        opt_string(ws.read_error.as_deref()),
+        ws.warnings
+            .iter()
+            .map(|w| string(w))
+            .collect::<Vec<_>>()
+            .join(", "),
+    )
+}
+
+/// A parsed JSON value, enough to read `hyprctl -j` output
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(String),
+    Array(Vec<Value>),
+    Object(Vec<(String, Value)>),
+}
+
+impl Value {
+    /// The member `key` of an object
+    pub fn get(&self, key: &str) -> Option<&Value> {
+        match self {
+            Value::Object(members) => members.iter().find(|(k, _)| k == key).map(|(_, v)| v),
+            _ => None,
+        }
+    }
+
+    pub fn as_array(&self) -> Option<&[Value]> {
+        match self {
+            Value::Array(items) => Some(items),
+            _ => None,
+        }
+    }
+
+    pub fn as_i64(&self) -> Option<i64> {
+        match self {
+            Value::Number(n) if n.fract() == 0.0 => Some(*n as i64),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Value::String(s) => Some(s),
+            _ => None,
+        }
+    }
+}
+
+/// Parse a complete JSON document, `None` when it is malformed.
+pub fn parse(text: &str) -> Option<Value> {
+    let mut parser = Parser {
+        chars: text.chars().peekable(),
+    };
+    let value = parser.value()?;
+    parser.skip_whitespace();
+    parser.chars.peek().is_none().then_some(value)
+}
+
+struct Parser<'a> {
+    chars: std::iter::Peekable<std::str::Chars<'a>>,
+}
+
+impl Parser<'_> {
+    fn skip_whitespace(&mut self) {
+        while self.chars.next_if(|c| c.is_whitespace()).is_some() {}
+    }
+
+    fn expect(&mut self, word: &str) -> Option<()> {
+        word.chars()
+            .try_for_each(|c| self.chars.next_if_eq(&c).map(drop))
+    }
+
+    fn value(&mut self) -> Option<Value> {
+        self.skip_whitespace();
+        match *self.chars.peek()? {
+            '{' => self.object(),
+            '[' => self.array(),
+            '"' => self.string().map(Value::String),
+            't' => self.expect("true").map(|_| Value::Bool(true)),
+            'f' => self.expect("false").map(|_| Value::Bool(false)),
+            'n' => self.expect("null").map(|_| Value::Null),
+            _ => self.number(),
+        }
+    }
+
+    fn object(&mut self) -> Option<Value> {
+        self.chars.next();
+        let mut members = Vec::new();
+        self.skip_whitespace();
+        if self.chars.next_if_eq(&'}').is_some() {
+            return Some(Value::Object(members));
+        }
+        loop {
+            self.skip_whitespace();
+            let key = self.string()?;
+            self.skip_whitespace();
+            self.expect(":")?;
+            members.push((key, self.value()?));
+            self.skip_whitespace();
+            match self.chars.next()? {
+                ',' => continue,
+                '}' => return Some(Value::Object(members)),
+                _ => return None,
+            }
+        }
+    }
+
+    fn array(&mut self) -> Option<Value> {
+        self.chars.next();
+        let mut items = Vec::new();
+        self.skip_whitespace();
+        if self.chars.next_if_eq(&']').is_some() {
+            return Some(Value::Array(items));
+        }
+        loop {
+            items.push(self.value()?);
+            self.skip_whitespace();
+            match self.chars.next()? {
+                ',' => continue,
+                ']' => return Some(Value::Array(items)),
+                _ => return None,
+            }
+        }
+    }
+
+    fn string(&mut self) -> Option<String> {
+        self.expect("\"")?;
+        let mut out = String::new();
+        loop {
+            match self.chars.next()? {
+                '"' => return Some(out),
+                '\\' => match self.chars.next()? {
+                    '"' => out.push('"'),
+                    '\\' => out.push('\\'),
+                    '/' => out.push('/'),
+                    'b' => out.push('\u{8}'),
+                    'f' => out.push('\u{c}'),
+                    'n' => out.push('\n'),
+                    'r' => out.push('\r'),
+                    't' => out.push('\t'),
+                    'u' => {
+                        let hex: String =
+                            (0..4).map(|_| self.chars.next()).collect::<Option<_>>()?;
+                        // Surrogate pairs are not needed for hyprctl output
+                        out.push(
+                            char::from_u32(u32::from_str_radix(&hex, 16).ok()?)
+                                .unwrap_or('\u{fffd}'),
+                        );
+                    }
+                    _ => return None,
+                },
+                c => out.push(c),
+            }
+        }
+    }
+
+    fn number(&mut self) -> Option<Value> {
+        let mut text = String::new();
+        while let Some(c) = self
+            .chars
+            .next_if(|c| c.is_ascii_digit() || matches!(c, '-' | '+' | '.' | 'e' | 'E'))
+        {
+            text.push(c);
+        }
+        text.parse().ok().map(Value::Number)
+    }
+}
@@ -1,21 +1,1204 @@
+use std::env;
+use std::fs;
 use std::io;
+use std::os::unix::fs::{MetadataExt, PermissionsExt};
+use std::path::{Path, PathBuf};
 use std::process::{Command, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
 
-use crate::workspace::WorkspaceEntry;
+use crate::config::{OldHyprland, UnsafeScripts};
+use crate::hyprctl;
+use crate::runner::{CommandRunner, RecordingRunner, SystemRunner, shell_quote};
+use crate::script::{
+    WindowSpec, delay_duration, fill_launch_placeholders, launch_placeholders, parse_windows,
+    with_monitor,
+};
+use crate::state::save_last_launched;
+use crate::workspace::{Workspace, WorkspaceEntry, prompt, workspace_dir};
+
+/// Whether `program` can be executed, either as a path or by looking it up in PATH.
+pub fn command_in_path(program: &str) -> bool {
+    if program.contains('/') {
+        return Path::new(program).is_file();
+    }
+
+    env::var_os("PATH")
+        .map(|paths| env::split_paths(&paths).any(|dir| dir.join(program).is_file()))
+        .unwrap_or(false)
+}
+
+/// How a script should be launched
+#[derive(Debug, Clone, Default)]
+pub struct LaunchOptions {
+    /// Hyprland instance signature the script's hyprctl calls should target
+    pub instance: Option<String>,
+    /// Run the script through `bash -lc` so it sees the user's login environment
+    pub login_shell: bool,
+    /// Dispatch the workspace and all windows in a single `hyprctl --batch` call
+    pub batch: bool,
+    /// Print the commands the launch would run instead of running them (`--dry-run`)
+    pub dry_run: bool,
+    /// Print how long the launch took (`--time`)
+    pub time: bool,
+    /// Wait up to this long for Hyprland to answer before launching (`--wait-for-hyprland`)
+    pub wait_for_hyprland: Option<Duration>,
+    /// Whether scripts writable by others are launched with a warning or refused
+    pub unsafe_scripts: UnsafeScripts,
+    /// Whether scripts needing a newer Hyprland are launched with a warning or refused
+    pub old_hyprland: OldHyprland,
+    /// Only dispatch these windows (positions in the script), one by one
+    pub windows: Option<Vec<usize>>,
+    /// Spawn the windows where you are, without their `workspace` rules nor the switch (`--scratch`)
+    pub scratch: bool,
+    /// Open every window on this monitor, replacing the script's `monitor` rules (`--monitor`)
+    pub monitor: Option<String>,
+    /// Start every window in a transient systemd scope under the script's slice (`--systemd-scope`)
+    pub systemd_scope: bool,
+    /// Hook run after every launch, see [`post_launch_hook_path`]; `None` runs none
+    pub post_launch_hook: Option<PathBuf>,
+}
+
+/// Why running the script at `path` could run someone else's code, if it could.
+///
+/// A script is unsafe when group or others may write to it, or when it belongs to
+/// neither you nor root. Symlinks are followed, so the target is what gets checked.
+pub fn unsafe_permissions(path: &Path) -> Option<String> {
+    let meta = fs::metadata(path).ok()?;
+    let mode = meta.permissions().mode();
+
+    if mode & 0o002 != 0 {
+        return Some(format!(
+            "world-writable (mode {:o}, run chmod o-w)",
+            mode & 0o777
+        ));
+    }
+    if mode & 0o020 != 0 {
+        return Some(format!(
+            "group-writable (mode {:o}, run chmod g-w)",
+            mode & 0o777
+        ));
+    }
+
+    // /proc/self belongs to the user running us
+    let uid = fs::metadata("/proc/self").ok()?.uid();
+    if meta.uid() != uid && meta.uid() != 0 {
+        return Some(format!("owned by another user (uid {})", meta.uid()));
+    }
+
+    None
+}
+
+/// Apply the `unsafe_scripts` setting before running `ws`.
+fn check_permissions(ws: &WorkspaceEntry, options: &LaunchOptions) -> io::Result<()> {
+    let Some(problem) = unsafe_permissions(&ws.full_path) else {
+        return Ok(());
+    };
+
+    match options.unsafe_scripts {
+        UnsafeScripts::Warn => {
+            eprintln!(
+                "Warning: {} is {problem}; anyone able to edit it chooses what runs.",
+                ws.base_name
+            );
+            Ok(())
+        }
+        UnsafeScripts::Refuse => Err(io::Error::new(
+            io::ErrorKind::PermissionDenied,
+            format!(
+                "refusing to launch {}: it is {problem} (set unsafe_scripts = \"warn\" to allow it)",
+                ws.base_name
+            ),
+        )),
+    }
+}
+
+/// Apply the `old_hyprland` setting before running `ws`, when it declares a minimum
+/// Hyprland version. An unreachable Hyprland, or one that doesn't tell its version, passes.
+fn check_hyprland_version(ws: &WorkspaceEntry, options: &LaunchOptions) -> io::Result<()> {
+    let Some(required) = ws.min_hyprland else {
+        return Ok(());
+    };
+    let Ok(Some(running)) = hyprctl::running_version(options.instance.as_deref()) else {
+        return Ok(());
+    };
+    if running >= required {
+        return Ok(());
+    }
+
+    let problem = format!(
+        "{} needs Hyprland {required} or newer, this is {running}",
+        ws.base_name
+    );
+    match options.old_hyprland {
+        OldHyprland::Refuse if !options.dry_run => Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            format!("refusing to launch: {problem} (set old_hyprland = \"warn\" to allow it)"),
+        )),
+        _ => {
+            eprintln!("Warning: {problem}; some of its rules may be ignored.");
+            Ok(())
+        }
+    }
+}
 
 /// Launch the selected script (after TUI has been restored)
-pub fn launch_script(ws: &WorkspaceEntry) -> io::Result<()> {
+pub fn launch_script(ws: &WorkspaceEntry, options: &LaunchOptions) -> io::Result<()> {
+    if !options.dry_run {
+        return launch_script_with(ws, options, &mut SystemRunner);
+    }
+
+    let mut runner = RecordingRunner::default();
+    launch_script_with(ws, options, &mut runner)?;
+    print_dry_run(&runner);
+    Ok(())
+}
+
+/// The workspace number of `ws` when it is the focused one, so launching would land
+/// its windows (and its close-existing preamble) right where you are.
+///
+/// Dry and scratch launches never qualify, and neither does an unreachable Hyprland.
+pub fn targets_current_workspace(ws: &WorkspaceEntry, options: &LaunchOptions) -> Option<u32> {
+    if options.dry_run || options.scratch {
+        return None;
+    }
+    let num = ws.workspace_num?;
+    let active = hyprctl::active_workspace(options.instance.as_deref()).ok()??;
+    (active == num).then_some(num)
+}
+
+/// List what a dry run would have started.
+fn print_dry_run(runner: &RecordingRunner) {
+    println!("Dry run, nothing was started. The launch would run:");
+    for command in &runner.commands {
+        println!("  $ {command}");
+    }
+}
+
+/// Only switch to the workspace of a script, spawning none of its windows (`hyprspace switch`).
+pub fn switch_to_workspace(ws: &WorkspaceEntry, options: &LaunchOptions) -> io::Result<()> {
+    let Some(workspace) = ws.workspace() else {
+        return Err(io::Error::other(format!(
+            "{} has no `hyprctl dispatch workspace N` line, there is no workspace to switch to",
+            ws.base_name
+        )));
+    };
+    let instance = options.instance.as_deref();
+    let [dispatcher, arg] = workspace.dispatch();
+    let args = ["dispatch", dispatcher.as_str(), arg.as_str()];
+
+    if options.dry_run {
+        let mut runner = RecordingRunner::default();
+        hyprctl::run_with(&mut runner, instance, &args)?;
+        print_dry_run(&runner);
+        return Ok(());
+    }
+
+    if let Some(timeout) = options.wait_for_hyprland {
+        hyprctl::wait_until_ready(instance, timeout)?;
+    }
+    let reply = hyprctl::run(instance, &args)?;
+    // hyprctl exits 0 even when the dispatcher rejects the call
+    if reply != "ok" && !reply.is_empty() {
+        return Err(io::Error::other(format!("hyprctl: {reply}")));
+    }
+
+    match workspace {
+        Workspace::Numbered(num) => println!("Switched to workspace {num} ({})", ws.name_short),
+        Workspace::Special(name) => println!("Toggled special:{name} ({})", ws.name_short),
+    }
+    Ok(())
+}
+
+/// Terminal emulators tried, in order, when `$TERMINAL` is not set
+const FALLBACK_TERMINALS: &[&str] = &[
+    "kitty",
+    "foot",
+    "alacritty",
+    "wezterm",
+    "ghostty",
+    "konsole",
+    "gnome-terminal",
+    "xterm",
+];
+
+/// Arguments placing a command line after the terminal's own (`-e` for most of them).
+fn terminal_exec_args(terminal: &str) -> &'static [&'static str] {
+    match Path::new(terminal)
+        .file_name()
+        .and_then(|name| name.to_str())
+    {
+        Some("kitty" | "foot") => &[],
+        Some("wezterm") => &["start", "--"],
+        Some("gnome-terminal" | "ptyxis") => &["--"],
+        _ => &["-e"],
+    }
+}
+
+/// The user's terminal as a program and its arguments: `$TERMINAL` (which may carry
+/// options), else the first of [`FALLBACK_TERMINALS`] in PATH.
+fn find_terminal() -> io::Result<Vec<String>> {
+    if let Ok(terminal) = env::var("TERMINAL") {
+        let words: Vec<String> = terminal.split_whitespace().map(str::to_string).collect();
+        if !words.is_empty() {
+            return Ok(words);
+        }
+    }
+    FALLBACK_TERMINALS
+        .iter()
+        .find(|name| command_in_path(name))
+        .map(|name| vec![name.to_string()])
+        .ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::NotFound,
+                format!(
+                    "no terminal found, set $TERMINAL (tried {})",
+                    FALLBACK_TERMINALS.join(", ")
+                ),
+            )
+        })
+}
+
+/// Open a terminal tracing the script with `bash -x`, kept open once it ends, without
+/// waiting for it. Returns the terminal's name.
+pub fn open_debug_terminal(ws: &WorkspaceEntry, instance: Option<&str>) -> io::Result<String> {
+    let words = find_terminal()?;
+    let (program, options) = words
+        .split_first()
+        .ok_or_else(|| io::Error::other("empty $TERMINAL"))?;
+
+    let mut command = Command::new(program);
+    command
+        .args(options)
+        .args(terminal_exec_args(program))
+        // The path is passed as $0 so it never needs shell quoting
+        .args(["bash", "-c", "bash -x \"$0\"; status=$?; echo; read -rp \"Script exited with status $status, press Enter to close\""])
+        .arg(&ws.full_path)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null());
+    if let Some(instance) = instance {
+        command.env("HYPRLAND_INSTANCE_SIGNATURE", instance);
+    }
+
+    let mut child = command.spawn().map_err(|err| {
+        if err.kind() == io::ErrorKind::NotFound {
+            io::Error::new(
+                err.kind(),
+                format!("terminal {program} not found, check $TERMINAL"),
+            )
+        } else {
+            err
+        }
+    })?;
+    // Reap the terminal whenever it closes, without blocking the caller
+    thread::spawn(move || child.wait());
+    Ok(program.clone())
+}
+
+/// Launch the selected script, starting the script, hyprctl and the hook through `runner`.
+///
+/// A dry run neither waits for Hyprland nor records the launch for `hyprspace last`.
+pub fn launch_script_with(
+    ws: &WorkspaceEntry,
+    options: &LaunchOptions,
+    runner: &mut dyn CommandRunner,
+) -> io::Result<()> {
+    check_permissions(ws, options)?;
+    check_hyprland_version(ws, options)?;
+    let values = launch_values(ws, options)?;
+
+    let unscoped;
+    let options = if options.systemd_scope && !systemd_available() {
+        eprintln!(
+            "systemd is not running, launching {} without a scope",
+            ws.base_name
+        );
+        unscoped = LaunchOptions {
+            systemd_scope: false,
+            ..options.clone()
+        };
+        &unscoped
+    } else {
+        options
+    };
+
     println!("Launching: {}", ws.base_name);
     println!("Path: {}", ws.full_path.to_string_lossy());
     println!();
 
-    let mut child = Command::new(ws.full_path.clone())
-        .stdin(Stdio::inherit())
-        .stdout(Stdio::inherit())
-        .stderr(Stdio::inherit())
-        .spawn()?;
+    if let Some(timeout) = options.wait_for_hyprland
+        && !options.dry_run
+    {
+        hyprctl::wait_until_ready(options.instance.as_deref(), timeout)?;
+    }
+
+    let start = Instant::now();
+    // Rewriting rules or commands, or filling placeholders, needs the windows dispatched
+    // here rather than by the script
+    let dispatches = if values.is_some()
+        || options.windows.is_some()
+        || options.scratch
+        || options.monitor.is_some()
+        || options.systemd_scope
+    {
+        launch_windows(
+            ws,
+            options,
+            values.as_deref().unwrap_or_default(),
+            start,
+            runner,
+        )?
+    } else if options.batch {
+        launch_batch(ws, options, start, runner)?
+    } else {
+        run_script(ws, options, start, runner)?
+    };
+    let total = start.elapsed();
+
+    if !options.dry_run {
+        focus_after_launch(ws, options, runner);
+    }
+
+    if options.time {
+        print_timing(&dispatches, total, options.batch);
+    }
+
+    // Only for `hyprspace last`, so failing to record it must not fail the launch
+    if !options.dry_run
+        && let Err(err) = save_last_launched(&ws.full_path)
+    {
+        eprintln!("Could not remember the last launch: {err}");
+    }
+
+    run_post_launch_hook(ws, options, runner);
+
+    Ok(())
+}
+
+/// Ask for the value of every `{{name}}` placeholder in the commands of `windows`.
+///
+/// An empty answer cancels the launch, which also covers launching without a terminal.
+fn ask_placeholder_values<'a>(
+    windows: impl IntoIterator<Item = &'a WindowSpec>,
+) -> io::Result<Vec<(String, String)>> {
+    let mut values: Vec<(String, String)> = Vec::new();
+    for window in windows {
+        for name in launch_placeholders(&window.exec_command()) {
+            if values.iter().any(|(known, _)| known == name) {
+                continue;
+            }
+            let value = prompt(&format!("Value for {{{{{name}}}}}: "))?;
+            if value.is_empty() {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("no value given for {{{{{name}}}}}, launch cancelled"),
+                ));
+            }
+            values.push((name.to_string(), value));
+        }
+    }
+    Ok(values)
+}
+
+/// Placeholder values of the windows about to be launched, `None` when their commands
+/// have no placeholder. A dry run asks nothing and shows them unfilled.
+///
+/// Scripts only have placeholders to fill when launched through hyprspace.
+fn launch_values(
+    ws: &WorkspaceEntry,
+    options: &LaunchOptions,
+) -> io::Result<Option<Vec<(String, String)>>> {
+    let windows = parse_windows(&fs::read_to_string(&ws.full_path)?);
+    let chosen: Vec<&WindowSpec> = windows
+        .iter()
+        .enumerate()
+        .filter(|(idx, _)| {
+            options
+                .windows
+                .as_ref()
+                .is_none_or(|selection| selection.contains(idx))
+        })
+        .map(|(_, window)| window)
+        .collect();
+
+    if chosen
+        .iter()
+        .all(|window| launch_placeholders(&window.exec_command()).is_empty())
+    {
+        return Ok(None);
+    }
+    if options.dry_run {
+        return Ok(Some(Vec::new()));
+    }
+    ask_placeholder_values(chosen).map(Some)
+}
+
+/// Dispatch a script's windows one at a time, asking before each one (`hyprspace step`).
+///
+/// Delays are not honored: you choose when the next window opens. A failing dispatch is
+/// reported and stepping goes on, so the misbehaving window can be singled out.
+pub fn step_script(ws: &WorkspaceEntry, options: &LaunchOptions) -> io::Result<()> {
+    if !options.dry_run {
+        return step_script_with(ws, options, &mut SystemRunner);
+    }
+
+    let mut runner = RecordingRunner::default();
+    step_script_with(ws, options, &mut runner)?;
+    print_dry_run(&runner);
+    Ok(())
+}
+
+/// [`step_script`] with every hyprctl call going through `runner`.
+pub fn step_script_with(
+    ws: &WorkspaceEntry,
+    options: &LaunchOptions,
+    runner: &mut dyn CommandRunner,
+) -> io::Result<()> {
+    check_permissions(ws, options)?;
+    let instance = options.instance.as_deref();
+    let windows = parse_windows(&fs::read_to_string(&ws.full_path)?);
+    if windows.is_empty() {
+        return Err(io::Error::other(format!(
+            "no rule_exec windows found in {}, nothing to step through",
+            ws.base_name
+        )));
+    }
+
+    // Like a dry launch, a dry step shows the placeholders unfilled
+    let values = if options.dry_run {
+        Vec::new()
+    } else {
+        ask_placeholder_values(&windows)?
+    };
+
+    println!("Stepping through: {}", ws.base_name);
+    println!("Path: {}", ws.full_path.to_string_lossy());
+    println!();
+
+    if let Some(timeout) = options.wait_for_hyprland
+        && !options.dry_run
+    {
+        hyprctl::wait_until_ready(instance, timeout)?;
+    }
 
-    let _ = child.wait();
+    if let Some(workspace) = ws.workspace()
+        && !options.scratch
+    {
+        let answer = prompt(&format!("Switch to {workspace} first? [Y/n]: "))?.to_lowercase();
+        if answer.is_empty() || answer == "y" || answer == "yes" {
+            let [dispatcher, arg] = workspace.dispatch();
+            hyprctl::dispatch_with(runner, instance, &[&dispatcher, &arg])?;
+        }
+    }
 
+    let (mut ran, mut failed) = (0, 0);
+    for (idx, window) in windows.iter().enumerate() {
+        let mut rules = if options.scratch {
+            window.rules_without_workspace()
+        } else {
+            window.rules.clone()
+        };
+        if let Some(monitor) = &options.monitor {
+            rules = with_monitor(&rules, monitor);
+        }
+        let command = fill_launch_placeholders(&window.exec_command(), &values);
+        println!();
+        println!("Window #{}/{}: {command}", idx + 1, windows.len());
+        println!("  rules: [{rules}]");
+        if let Some(seconds) = &window.delay {
+            println!("  (the script sleeps {seconds} before it)");
+        }
+        if let Some(message) = &window.layout_hint {
+            println!("  layout hint: {message}");
+        }
+
+        let answer = prompt("Run it? [Y]es / [s]kip / [q]uit: ")?.to_lowercase();
+        match answer.as_str() {
+            "" | "y" | "yes" => {}
+            "s" | "skip" => continue,
+            _ => break,
+        }
+
+        let dispatched = match &window.layout_hint {
+            Some(message) => hyprctl::dispatch_with(runner, instance, &["layoutmsg", message]),
+            None => Ok(()),
+        }
+        .and_then(|()| hyprctl::dispatch_exec_with(runner, instance, &rules, &command));
+        match dispatched {
+            Ok(()) => {
+                ran += 1;
+                println!("  dispatched");
+            }
+            Err(err) => {
+                failed += 1;
+                println!("  failed: {err}");
+            }
+        }
+    }
+
+    println!();
+    println!("{ran} window(s) dispatched, {failed} failed.");
     Ok(())
 }
+
+/// Execute the script itself and wait for it.
+///
+/// When timing, the script's output is relayed line by line and the time of every
+/// `ok` reply from hyprctl is returned, one per dispatch.
+fn run_script(
+    ws: &WorkspaceEntry,
+    options: &LaunchOptions,
+    start: Instant,
+    runner: &mut dyn CommandRunner,
+) -> io::Result<Vec<Duration>> {
+    let mut command = if options.login_shell {
+        // The path is passed as $0 so it never needs shell quoting
+        let mut command = Command::new("bash");
+        command.arg("-lc").arg("exec \"$0\"").arg(&ws.full_path);
+        command
+    } else {
+        Command::new(ws.full_path.clone())
+    };
+    command.stdin(Stdio::inherit()).stderr(Stdio::inherit());
+
+    // hyprctl picks its target instance from this variable
+    if let Some(instance) = &options.instance {
+        command.env("HYPRLAND_INSTANCE_SIGNATURE", instance);
+    }
+
+    let mut dispatches = Vec::new();
+    let mut relay = |line: &str| {
+        if line.trim() == "ok" {
+            dispatches.push(start.elapsed());
+        }
+        println!("{line}");
+    };
+    let on_line: Option<&mut dyn FnMut(&str)> = if options.time { Some(&mut relay) } else { None };
+    let status = runner.status(&mut command, on_line)?;
+    if !status.success() {
+        return Err(io::Error::other(format!(
+            "{} exited with {status}",
+            ws.base_name
+        )));
+    }
+
+    Ok(dispatches)
+}
+
+/// Print the `--time` report: every dispatch with its offset, then the totals.
+fn print_timing(dispatches: &[Duration], total: Duration, batch: bool) {
+    let ms = |d: Duration| d.as_secs_f64() * 1000.0;
+
+    println!("\nTiming:");
+    if batch && dispatches.len() == 1 {
+        println!("  batch dispatched after {:.1} ms", ms(dispatches[0]));
+    } else {
+        let mut previous = Duration::ZERO;
+        for (idx, at) in dispatches.iter().enumerate() {
+            println!(
+                "  dispatch #{:<3} {:>8.1} ms  (+{:.1} ms)",
+                idx + 1,
+                ms(*at),
+                ms(at.saturating_sub(previous))
+            );
+            previous = *at;
+        }
+    }
+
+    match dispatches.last() {
+        Some(last) => println!(
+            "  last dispatch after {:.1} ms, {:.1} ms in total",
+            ms(*last),
+            ms(total)
+        ),
+        None => println!("  no hyprctl reply seen, {:.1} ms in total", ms(total)),
+    }
+}
+
+/// Whether `text` contains a `;` outside of `[...]`, which would split a batch command
+fn has_top_level_semicolon(text: &str) -> bool {
+    let mut depth = 0i32;
+    for c in text.chars() {
+        match c {
+            '[' => depth += 1,
+            ']' => depth -= 1,
+            ';' if depth <= 0 => return true,
+            _ => {}
+        }
+    }
+    false
+}
+
+/// Build the `hyprctl --batch` argument dispatching the workspace and every window.
+///
+/// Hyprland splits batches on `;` but ignores those inside `[...]`, so rules are safe;
+/// a `;` inside a command cannot be carried and is reported as an error.
+pub fn batch_request(
+    workspace: Option<&Workspace>,
+    windows: &[WindowSpec],
+) -> Result<String, String> {
+    let mut commands = Vec::new();
+
+    if let Some(workspace) = workspace {
+        let [dispatcher, arg] = workspace.dispatch();
+        commands.push(format!("dispatch {dispatcher} {arg}"));
+    }
+
+    for (idx, window) in windows.iter().enumerate() {
+        if window.delay.is_some() {
+            return Err(format!(
+                "window #{} is preceded by a delay, which a batch cannot honor",
+                idx + 1
+            ));
+        }
+        if has_top_level_semicolon(&window.exec_command()) || window.rules.contains(['[', ']']) {
+            return Err(format!(
+                "window #{} cannot be expressed in a hyprctl batch (`;` or brackets in its command/rules)",
+                idx + 1
+            ));
+        }
+        if let Some(message) = &window.layout_hint {
+            commands.push(format!("dispatch layoutmsg {message}"));
+        }
+        if window.rules.is_empty() {
+            commands.push(format!("dispatch exec {}", window.exec_command()));
+        } else {
+            commands.push(format!(
+                "dispatch exec [{}] {}",
+                window.rules,
+                window.exec_command()
+            ));
+        }
+    }
+
+    Ok(commands.join(" ; "))
+}
+
+/// Dispatch the workspace and all windows with one `hyprctl --batch` call.
+///
+/// Falls back to running the script when its windows can't be batched.
+fn launch_batch(
+    ws: &WorkspaceEntry,
+    options: &LaunchOptions,
+    start: Instant,
+    runner: &mut dyn CommandRunner,
+) -> io::Result<Vec<Duration>> {
+    let content = fs::read_to_string(&ws.full_path)?;
+    let windows = parse_windows(&content);
+
+    let request = match batch_request(ws.workspace().as_ref(), &windows) {
+        Ok(request) => request,
+        Err(reason) => {
+            println!("Cannot batch this script: {reason}");
+            println!("Running it directly instead.\n");
+            return run_script(ws, options, start, runner);
+        }
+    };
+
+    let reply = hyprctl::run_with(runner, options.instance.as_deref(), &["--batch", &request])?;
+    let dispatched = start.elapsed();
+
+    let failures: Vec<&str> = reply
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && *line != "ok")
+        .collect();
+    if !failures.is_empty() {
+        return Err(io::Error::other(format!(
+            "hyprctl --batch reported: {}",
+            failures.join("; ")
+        )));
+    }
+
+    println!("Dispatched {} window(s) in one batch.", windows.len());
+    Ok(vec![dispatched])
+}
+
+/// Dispatch the workspace and the windows one by one, each after its own delay and
+/// layout hint; custom lines of the script are skipped.
+///
+/// Only the windows of `options.windows` are spawned when set. In scratch mode the
+/// workspace is left alone and the windows drop their `workspace` rules. With
+/// `options.monitor`, every window gets that `monitor` rule.
+fn launch_windows(
+    ws: &WorkspaceEntry,
+    options: &LaunchOptions,
+    values: &[(String, String)],
+    start: Instant,
+    runner: &mut dyn CommandRunner,
+) -> io::Result<Vec<Duration>> {
+    let instance = options.instance.as_deref();
+    let windows = parse_windows(&fs::read_to_string(&ws.full_path)?);
+    let mut dispatches = Vec::new();
+
+    if let Some(workspace) = ws.workspace()
+        && !options.scratch
+    {
+        let [dispatcher, arg] = workspace.dispatch();
+        hyprctl::dispatch_with(runner, instance, &[&dispatcher, &arg])?;
+        dispatches.push(start.elapsed());
+    }
+
+    let chosen: Vec<(usize, &WindowSpec)> = windows
+        .iter()
+        .enumerate()
+        .filter(|(idx, _)| {
+            options
+                .windows
+                .as_ref()
+                .is_none_or(|selection| selection.contains(idx))
+        })
+        .collect();
+    for &(idx, window) in &chosen {
+        if let Some(delay) = window.delay.as_deref().and_then(delay_duration)
+            && !options.dry_run
+        {
+            thread::sleep(delay);
+        }
+        if let Some(message) = &window.layout_hint {
+            hyprctl::dispatch_with(runner, instance, &["layoutmsg", message])?;
+        }
+        let mut rules = if options.scratch {
+            window.rules_without_workspace()
+        } else {
+            window.rules.clone()
+        };
+        if let Some(monitor) = &options.monitor {
+            rules = with_monitor(&rules, monitor);
+        }
+        let mut command = fill_launch_placeholders(&window.exec_command(), values);
+        if options.systemd_scope {
+            command = in_scope(&command, &scope_slice(ws));
+        }
+        hyprctl::dispatch_exec_with(runner, instance, &rules, &command)
+            .map_err(|err| io::Error::new(err.kind(), format!("window #{}: {err}", idx + 1)))?;
+        dispatches.push(start.elapsed());
+    }
+
+    let mut place = if options.scratch {
+        " in the current workspace".to_string()
+    } else {
+        String::new()
+    };
+    if let Some(monitor) = &options.monitor {
+        place.push_str(&format!(" on monitor {monitor}"));
+    }
+    println!(
+        "Dispatched {} of {} window(s){place}.",
+        chosen.len(),
+        windows.len()
+    );
+    if options.systemd_scope {
+        let slice = scope_slice(ws);
+        println!("They run in {slice}: `systemctl --user stop {slice}` closes them all.");
+    }
+    Ok(dispatches)
+}
+
+/// How long [`focus_after_launch`] waits for the window to show up
+const FOCUS_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Focus the launched window marked with `# hyprspace-focus`, if any.
+///
+/// Failing to find it is reported but never fails the launch itself.
+fn focus_after_launch(
+    ws: &WorkspaceEntry,
+    options: &LaunchOptions,
+    runner: &mut dyn CommandRunner,
+) {
+    let Ok(content) = fs::read_to_string(&ws.full_path) else {
+        return;
+    };
+    let Some(window) = parse_windows(&content)
+        .into_iter()
+        .enumerate()
+        .filter(|(idx, _)| {
+            options
+                .windows
+                .as_ref()
+                .is_none_or(|selection| selection.contains(idx))
+        })
+        .map(|(_, window)| window)
+        .find(|window| window.focus)
+    else {
+        return;
+    };
+
+    let classes = window.class_names();
+    let workspace = if options.scratch {
+        None
+    } else {
+        ws.workspace_num
+    };
+    let program = window.program().unwrap_or("window");
+    match hyprctl::focus_window_of_class(
+        runner,
+        options.instance.as_deref(),
+        &classes,
+        workspace,
+        FOCUS_TIMEOUT,
+    ) {
+        Ok(true) => println!("Focused {program}."),
+        Ok(false) => eprintln!(
+            "No {program} window showed up within {}s, nothing was focused (its class may differ from {})",
+            FOCUS_TIMEOUT.as_secs(),
+            classes.join(" / ")
+        ),
+        Err(err) => eprintln!("Could not focus {program}: {err}"),
+    }
+}
+
+/// Whether `systemd-run --user` can be used: systemd is the init system and the tool is installed.
+fn systemd_available() -> bool {
+    Path::new("/run/systemd/system").is_dir() && command_in_path("systemd-run")
+}
+
+/// Slice grouping the windows of `ws`, e.g. `hyprspace-backend.slice`.
+///
+/// systemd nests slices on `-`, so every one of them also sits under `hyprspace.slice`.
+pub fn scope_slice(ws: &WorkspaceEntry) -> String {
+    let name: String = ws
+        .name_short
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '_' || c == '-' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    format!("hyprspace-{name}.slice")
+}
+
+/// `command` (a `sh -c` line, as Hyprland runs it) started in a transient scope under `slice`.
+fn in_scope(command: &str, slice: &str) -> String {
+    format!(
+        "systemd-run --user --scope --quiet --collect --slice={slice} -- sh -c {}",
+        shell_quote(command)
+    )
+}
+
+/// Path of the optional user hook run after every launch
+pub fn post_launch_hook_path() -> PathBuf {
+    workspace_dir().join("hooks").join("post-launch")
+}
+
+/// Run the post-launch hook of `options`, if present and executable.
+///
+/// The hook receives the workspace short name and number (empty when unknown).
+/// A failing hook is reported but never fails the launch itself.
+fn run_post_launch_hook(
+    ws: &WorkspaceEntry,
+    options: &LaunchOptions,
+    runner: &mut dyn CommandRunner,
+) {
+    let Some(hook) = &options.post_launch_hook else {
+        return;
+    };
+
+    let executable = fs::metadata(hook)
+        .map(|meta| meta.is_file() && meta.permissions().mode() & 0o111 != 0)
+        .unwrap_or(false);
+    if !executable {
+        return;
+    }
+
+    let num = ws.workspace_num.map(|n| n.to_string()).unwrap_or_default();
+
+    match runner.status(Command::new(hook).arg(&ws.name_short).arg(num), None) {
+        Ok(status) if !status.success() => {
+            eprintln!("post-launch hook exited with {status}");
+        }
+        Err(err) => eprintln!("Failed to run post-launch hook: {err}"),
+        Ok(_) => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runner::RecordedCommand;
+    use std::process;
+
+    /// Write `content` as a script in a fresh temporary directory and describe it.
+    fn script(name: &str, workspace_num: u32, content: &str) -> WorkspaceEntry {
+        let dir = env::temp_dir().join(format!("hyprspace-launcher-{}-{name}", process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(format!("workspace-{name}.sh"));
+        fs::write(&path, content).unwrap();
+
+        WorkspaceEntry {
+            workspace_num: Some(workspace_num),
+            ..WorkspaceEntry::new(path)
+        }
+    }
+
+    /// Commands a dry launch of `ws` records
+    fn recorded(ws: &WorkspaceEntry, options: LaunchOptions) -> Vec<RecordedCommand> {
+        let options = LaunchOptions {
+            dry_run: true,
+            ..options
+        };
+        let mut runner = RecordingRunner::default();
+        launch_script_with(ws, &options, &mut runner).unwrap();
+        fs::remove_dir_all(&ws.source_dir).unwrap();
+
+        runner.commands
+    }
+
+    const TWO_WINDOWS: &str = "#!/bin/bash\nhyprctl dispatch workspace 4\n\nrule_exec \"workspace 4 silent; float\" \\\n  kitty\n\nrule_exec \"workspace 4 silent\" firefox\n";
+
+    #[test]
+    fn runs_the_script_itself() {
+        let ws = script("plain", 4, TWO_WINDOWS);
+        let path = ws.full_path.to_string_lossy().into_owned();
+
+        let commands = recorded(&ws, LaunchOptions::default());
+        assert_eq!(commands.len(), 1);
+        assert_eq!(commands[0].program, path);
+        assert!(commands[0].args.is_empty());
+        assert!(commands[0].envs.is_empty());
+    }
+
+    #[test]
+    fn runs_the_post_launch_hook_with_the_name_and_number() {
+        let ws = script("hooked", 4, TWO_WINDOWS);
+        let hook = ws.source_dir.join("post-launch");
+        fs::write(&hook, "#!/bin/sh\n").unwrap();
+        fs::set_permissions(&hook, fs::Permissions::from_mode(0o755)).unwrap();
+        let hook_path = hook.to_string_lossy().into_owned();
+        let options = LaunchOptions {
+            post_launch_hook: Some(hook),
+            ..LaunchOptions::default()
+        };
+
+        let commands = recorded(&ws, options);
+        assert_eq!(commands.len(), 2);
+        assert_eq!(commands[1].program, hook_path);
+        assert_eq!(commands[1].args, ["hooked", "4"]);
+
+        // A missing hook is skipped
+        let ws = script("unhooked", 4, TWO_WINDOWS);
+        let options = LaunchOptions {
+            post_launch_hook: Some(ws.source_dir.join("post-launch")),
+            ..LaunchOptions::default()
+        };
+        assert_eq!(recorded(&ws, options).len(), 1);
+    }
+
+    #[test]
+    fn passes_the_instance_to_the_script() {
+        let ws = script("instance", 4, TWO_WINDOWS);
+        let options = LaunchOptions {
+            instance: Some("abc".to_string()),
+            ..LaunchOptions::default()
+        };
+
+        let commands = recorded(&ws, options);
+        assert_eq!(
+            commands[0].envs,
+            vec![("HYPRLAND_INSTANCE_SIGNATURE".to_string(), "abc".to_string())]
+        );
+    }
+
+    #[test]
+    fn login_shell_execs_the_script_through_bash() {
+        let ws = script("login", 4, TWO_WINDOWS);
+        let path = ws.full_path.to_string_lossy().into_owned();
+        let options = LaunchOptions {
+            login_shell: true,
+            ..LaunchOptions::default()
+        };
+
+        let commands = recorded(&ws, options);
+        assert_eq!(commands[0].program, "bash");
+        assert_eq!(commands[0].args, ["-lc", "exec \"$0\"", path.as_str()]);
+    }
+
+    #[test]
+    fn batch_dispatches_everything_in_one_hyprctl_call() {
+        let ws = script("batch", 4, TWO_WINDOWS);
+        let options = LaunchOptions {
+            batch: true,
+            instance: Some("abc".to_string()),
+            ..LaunchOptions::default()
+        };
+
+        let commands = recorded(&ws, options);
+        assert_eq!(commands.len(), 1);
+        assert_eq!(commands[0].program, "hyprctl");
+        assert_eq!(
+            commands[0].args,
+            [
+                "--instance",
+                "abc",
+                "--batch",
+                "dispatch workspace 4 ; dispatch exec [workspace 4 silent; float] kitty ; dispatch exec [workspace 4 silent] firefox",
+            ]
+        );
+    }
+
+    #[test]
+    fn batch_falls_back_to_the_script_when_a_delay_is_needed() {
+        let ws = script(
+            "delayed",
+            2,
+            "#!/bin/bash\nhyprctl dispatch workspace 2\nrule_exec \"float\" kitty\nsleep 1\nrule_exec \"float\" firefox\n",
+        );
+        let path = ws.full_path.to_string_lossy().into_owned();
+        let options = LaunchOptions {
+            batch: true,
+            ..LaunchOptions::default()
+        };
+
+        let commands = recorded(&ws, options);
+        assert_eq!(commands.len(), 1);
+        assert_eq!(commands[0].program, path);
+    }
+
+    #[test]
+    fn selected_windows_are_dispatched_one_by_one() {
+        let ws = script("partial", 4, TWO_WINDOWS);
+        let options = LaunchOptions {
+            windows: Some(vec![1]),
+            ..LaunchOptions::default()
+        };
+
+        let commands = recorded(&ws, options);
+        let args: Vec<Vec<String>> = commands.into_iter().map(|command| command.args).collect();
+        assert_eq!(
+            args,
+            [
+                vec!["dispatch", "workspace", "4"],
+                vec!["dispatch", "exec", "[workspace 4 silent] firefox"],
+            ]
+        );
+    }
+
+    #[test]
+    fn scratch_mode_stays_on_the_current_workspace() {
+        let ws = script("scratch", 4, TWO_WINDOWS);
+        let options = LaunchOptions {
+            scratch: true,
+            ..LaunchOptions::default()
+        };
+
+        let commands = recorded(&ws, options);
+        let args: Vec<Vec<String>> = commands.into_iter().map(|command| command.args).collect();
+        assert_eq!(
+            args,
+            [
+                vec!["dispatch", "exec", "[float] kitty"],
+                vec!["dispatch", "exec", "firefox"]
+            ]
+        );
+    }
+
+    #[test]
+    fn monitor_override_replaces_the_monitor_rule() {
+        let ws = script(
+            "monitor",
+            4,
+            "#!/bin/bash\nhyprctl dispatch workspace 4\nrule_exec \"workspace 4; monitor HDMI-A-1; float\" kitty\nrule_exec \"workspace 4\" firefox\n",
+        );
+        let options = LaunchOptions {
+            monitor: Some("DP-2".to_string()),
+            ..LaunchOptions::default()
+        };
+
+        let commands = recorded(&ws, options);
+        let args: Vec<Vec<String>> = commands.into_iter().map(|command| command.args).collect();
+        assert_eq!(
+            args,
+            [
+                vec!["dispatch", "workspace", "4"],
+                vec![
+                    "dispatch",
+                    "exec",
+                    "[workspace 4; float; monitor DP-2] kitty"
+                ],
+                vec!["dispatch", "exec", "[workspace 4; monitor DP-2] firefox"],
+            ]
+        );
+    }
+
+    #[test]
+    fn placeholders_dispatch_the_windows_from_here() {
+        let ws = script(
+            "template",
+            4,
+            "#!/bin/bash\nhyprctl dispatch workspace 4\nrule_exec \"workspace 4\" kitty --directory ~/code/{{project}}\n",
+        );
+
+        // A dry run asks nothing and leaves them unfilled
+        let commands = recorded(&ws, LaunchOptions::default());
+        let args: Vec<Vec<String>> = commands.into_iter().map(|command| command.args).collect();
+        assert_eq!(
+            args,
+            [
+                vec!["dispatch", "workspace", "4"],
+                vec![
+                    "dispatch",
+                    "exec",
+                    "[workspace 4] kitty --directory ~/code/{{project}}"
+                ],
+            ]
+        );
+    }
+
+    #[test]
+    fn batch_request_sends_layout_hints_before_their_window() {
+        let windows = parse_windows(
+            "rule_exec \"workspace 1\" kitty\nhyprctl dispatch layoutmsg preselect r\nrule_exec \"workspace 1\" firefox\n",
+        );
+        assert_eq!(
+            batch_request(Some(&Workspace::Numbered(1)), &windows).unwrap(),
+            "dispatch workspace 1 ; dispatch exec [workspace 1] kitty ; dispatch layoutmsg preselect r ; dispatch exec [workspace 1] firefox"
+        );
+    }
+
+    #[test]
+    fn batch_request_toggles_special_workspaces() {
+        let windows = parse_windows("rule_exec \"workspace special:magic silent; float\" kitty\n");
+        assert_eq!(
+            batch_request(Some(&Workspace::Special("magic".to_string())), &windows).unwrap(),
+            "dispatch togglespecialworkspace magic ; dispatch exec [workspace special:magic silent; float] kitty"
+        );
+    }
+
+    #[test]
+    fn scoped_commands_keep_their_shell_line_intact() {
+        let mut ws = script("scope", 1, "");
+        fs::remove_dir_all(&ws.source_dir).unwrap();
+        ws.name_short = "my.dev".to_string();
+
+        assert_eq!(
+            in_scope("cd ~/'a b' && nvim .", &scope_slice(&ws)),
+            r"systemd-run --user --scope --quiet --collect --slice=hyprspace-my_dev.slice -- sh -c 'cd ~/'\''a b'\'' && nvim .'"
+        );
+    }
+
+    #[test]
+    fn batch_request_rejects_semicolons_in_commands() {
+        let windows = parse_windows("rule_exec \"float\" sh -c 'a; b'\n");
+        assert!(batch_request(None, &windows).is_err());
+    }
+
+    #[test]
+    fn recorded_commands_display_as_shell() {
+        let command = RecordedCommand {
+            program: "hyprctl".to_string(),
+            args: vec![
+                "--batch".to_string(),
+                "dispatch exec [float] it's".to_string(),
+            ],
+            envs: vec![("HYPRLAND_INSTANCE_SIGNATURE".to_string(), "abc".to_string())],
+        };
+        assert_eq!(
+            command.to_string(),
+            r"HYPRLAND_INSTANCE_SIGNATURE=abc hyprctl --batch 'dispatch exec [float] it'\''s'"
+        );
+    }
+}
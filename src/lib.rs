@@ -0,0 +1,35 @@
+//! Hyprspace core: discovery, generation and launching of Hyprland workspace scripts.
+//!
+//! The `hyprspace` binary is a thin frontend over this crate; other frontends can
+//! build on the re-exported items below.
+
+pub mod archive;
+pub mod autostart;
+pub mod cli;
+pub mod clipboard;
+pub mod completions;
+pub mod config;
+pub mod dedupe;
+pub mod definition;
+pub mod diff;
+pub mod docs;
+pub mod doctor;
+pub mod fuzzy;
+pub mod grid;
+pub mod hyprctl;
+pub mod json;
+pub mod launcher;
+pub mod list;
+pub mod normalize;
+pub mod picker;
+pub mod runner;
+pub mod script;
+pub mod state;
+pub mod term;
+pub mod trash;
+pub mod tui;
+pub mod workspace;
+
+pub use launcher::{LaunchOptions, launch_script};
+pub use script::{ScriptSpec, WindowSpec, build_script_content};
+pub use workspace::{WorkspaceEntry, list_workspaces, workspace_dir};
@@ -0,0 +1,167 @@
+use std::io;
+
+use unicode_width::UnicodeWidthStr;
+
+use crate::json;
+use crate::term;
+use crate::workspace::{
+    Workspace, WorkspaceEntry, display_name, has_multiple_sources, name_collisions,
+};
+
+/// Width of the number inside `[ws N]` badges, so the widest workspace number fits,
+/// as does any `[special:<name>]` badge
+pub fn badge_width(workspaces: &[WorkspaceEntry]) -> usize {
+    workspaces
+        .iter()
+        .filter_map(|ws| match ws.workspace()? {
+            Workspace::Numbered(num) => Some(num.to_string().len()),
+            // `special:<name>` in place of `ws N`
+            Workspace::Special(name) => Some(name.len() + 5),
+        })
+        .max()
+        .unwrap_or(1)
+}
+
+/// Columns `text` takes on a terminal: emoji and CJK characters count twice.
+pub fn display_width(text: &str) -> usize {
+    text.width()
+}
+
+/// `text` followed by enough spaces to take `width` columns.
+pub fn pad_to_width(text: &str, width: usize) -> String {
+    format!(
+        "{text}{}",
+        " ".repeat(width.saturating_sub(display_width(text)))
+    )
+}
+
+/// Widest icon (0 when no script has one) and widest short name, in columns, so
+/// whatever follows them lines up.
+pub fn column_widths(workspaces: &[WorkspaceEntry]) -> (usize, usize) {
+    let icon = workspaces
+        .iter()
+        .filter_map(|ws| ws.icon.as_deref())
+        .map(display_width)
+        .max()
+        .unwrap_or(0);
+    let name = workspaces
+        .iter()
+        .map(|ws| display_width(&ws.name_short))
+        .max()
+        .unwrap_or(0);
+    (icon, name)
+}
+
+/// The icon column of a row: the icon (or blanks) padded to `width`, then a space;
+/// nothing when no script has an icon.
+pub fn icon_cell(ws: &WorkspaceEntry, width: usize) -> String {
+    if width == 0 {
+        return String::new();
+    }
+    format!(
+        "{} ",
+        pad_to_width(ws.icon.as_deref().unwrap_or_default(), width)
+    )
+}
+
+/// The `[ws N]` badge of a script, right-aligned to `width`: `[ws !]` when unreadable,
+/// `[ws ?]` without a workspace line, `[special:magic]` for a special workspace.
+pub fn badge(ws: &WorkspaceEntry, width: usize) -> String {
+    let num = match (ws.workspace(), &ws.read_error) {
+        (_, Some(_)) => "!".to_string(),
+        (Some(Workspace::Numbered(num)), None) => num.to_string(),
+        (Some(Workspace::Special(name)), None) => {
+            return format!("{:<total$}", format!("[special:{name}]"), total = width + 5);
+        }
+        (None, None) => "?".to_string(),
+    };
+    format!("[ws {num:>width$}]")
+}
+
+/// The first window's program and how many windows follow it, e.g. `(kitty, +2)`,
+/// or nothing for a script without windows.
+pub fn programs_summary(ws: &WorkspaceEntry) -> Option<String> {
+    let first = ws.programs.first()?;
+    Some(match ws.window_count.saturating_sub(1) {
+        0 => format!("({first})"),
+        more => format!("({first}, +{more})"),
+    })
+}
+
+/// Print every script's parse warnings to stderr, keeping stdout for the list itself.
+pub fn print_warnings(workspaces: &[WorkspaceEntry]) {
+    for ws in workspaces {
+        for warning in &ws.warnings {
+            eprintln!("hyprspace: {}: {warning}", ws.full_path.to_string_lossy());
+        }
+    }
+}
+
+/// Print the workspaces as plain text, one per line, only the first `limit` when set.
+///
+/// Scripts are shown by full path once they come from several directories. With
+/// `paged`, a list taller than the terminal goes through `$PAGER`.
+pub fn print_list(
+    workspaces: &[WorkspaceEntry],
+    limit: Option<usize>,
+    paged: bool,
+) -> io::Result<()> {
+    let collisions = name_collisions(workspaces);
+    let multiple_sources = has_multiple_sources(workspaces);
+    let width = badge_width(workspaces);
+    let (icon_width, name_width) = column_widths(workspaces);
+    let shown = limit.unwrap_or(usize::MAX).min(workspaces.len());
+
+    let mut text = String::new();
+    for ws in &workspaces[..shown] {
+        let ws_info = badge(ws, width);
+
+        let icon = icon_cell(ws, icon_width);
+        let file = display_name(ws, multiple_sources);
+        let mut line = format!(
+            "{ws_info} {icon}{} ({file})",
+            pad_to_width(&ws.name_short, name_width)
+        );
+        if collisions.contains(&ws.name_short) {
+            line.push_str(" ⚠ name used in several directories");
+        }
+        if let Some(summary) = programs_summary(ws) {
+            line.push_str(&format!(" {summary}"));
+        }
+        if let Some(desc) = &ws.description {
+            line.push_str(&format!(" — {desc}"));
+        }
+        text.push_str(&line);
+        text.push('\n');
+    }
+    if shown < workspaces.len() {
+        text.push_str(&format!(
+            "… and {} more script(s), raise --limit to see them\n",
+            workspaces.len() - shown
+        ));
+    }
+
+    if paged {
+        term::page(&text)
+    } else {
+        print!("{text}");
+        Ok(())
+    }
+}
+
+/// Print the workspaces as a JSON array, one object per line.
+pub fn print_json(workspaces: &[WorkspaceEntry]) {
+    println!("[");
+    for (idx, ws) in workspaces.iter().enumerate() {
+        let sep = if idx + 1 < workspaces.len() { "," } else { "" };
+        println!("  {}{sep}", json::workspace_entry(ws));
+    }
+    println!("]");
+}
+
+/// Print the workspaces as newline-delimited JSON: one complete object per line, no array.
+pub fn print_json_lines(workspaces: &[WorkspaceEntry]) {
+    for ws in workspaces {
+        println!("{}", json::workspace_entry(ws));
+    }
+}
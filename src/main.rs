@@ -1,33 +1,531 @@
-mod workspace;
-mod launcher;
-mod tui;
+use std::env;
+use std::io::{self, IsTerminal, Write};
+use std::path::{Path, PathBuf};
+use std::process;
+use std::thread;
+use std::time::Duration;
 
-use std::io;
+use hyprspace::archive::{export_workspaces, import_workspaces};
+use hyprspace::autostart::{install_autostart, uninstall_autostart};
+use hyprspace::cli::{Cli, parse_ws, read_only_error, usage_error};
+use hyprspace::completions::print_completions;
+use hyprspace::config::{Config, load_config, print_config_path};
+use hyprspace::dedupe::run_dedupe;
+use hyprspace::definition::{export_definition, import_definition};
+use hyprspace::doctor::run_doctor;
+use hyprspace::fuzzy::score_workspace;
+use hyprspace::launcher::{
+    post_launch_hook_path, step_script, switch_to_workspace, targets_current_workspace,
+};
+use hyprspace::list::{print_json, print_json_lines, print_list, print_warnings};
+use hyprspace::normalize::run_normalize;
+use hyprspace::state::load_last_launched;
+use hyprspace::term::{self, reset_terminal};
+use hyprspace::trash::run_trash;
+use hyprspace::tui::{Action, TuiOptions, run_tui};
+use hyprspace::workspace::{
+    WorkspaceEntry, append_window, archive_dirs, confirm, convert_script_units,
+    create_from_clipboard, create_new_script, duplicate_script, ensure_workspace_dir, find_profile,
+    find_workspace, find_workspace_by_num, list_all_workspaces, set_assume_yes, workspace_dirs,
+};
+use hyprspace::{LaunchOptions, launch_script};
 
-use crate::launcher::launch_script;
-use crate::tui::{run_tui, Action};
-use crate::workspace::{create_new_script, ensure_workspace_dir, list_workspaces};
+/// Process exit codes, so shell scripts can branch on the outcome (see the README)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ExitCode {
+    Success = 0,
+    /// Any other error
+    Error = 1,
+    /// Malformed command line or invalid option value
+    Usage = 2,
+    /// The TUI was left without choosing anything
+    Cancelled = 3,
+    /// No workspace (or more than one) matches the requested name
+    NotFound = 4,
+    /// The script could not be started or exited with a failure
+    LaunchFailed = 5,
+    /// `doctor` found problems
+    ProblemsFound = 6,
+}
+
+/// An error together with the exit code it maps to
+struct Failure {
+    code: ExitCode,
+    error: io::Error,
+}
+
+impl Failure {
+    fn new(code: ExitCode, error: io::Error) -> Self {
+        Self { code, error }
+    }
+}
+
+impl From<io::Error> for Failure {
+    fn from(error: io::Error) -> Self {
+        // Argument parsing and validation report InvalidInput
+        let code = if error.kind() == io::ErrorKind::InvalidInput {
+            ExitCode::Usage
+        } else {
+            ExitCode::Error
+        };
+        Self { code, error }
+    }
+}
+
+fn main() {
+    // run() has restored the terminal by the time it returns
+    let code = match run() {
+        Ok(code) => code,
+        Err(failure) => {
+            eprintln!("hyprspace: {}", failure.error);
+            failure.code
+        }
+    };
+    process::exit(code as i32);
+}
+
+fn run() -> Result<ExitCode, Failure> {
+    let cli = Cli::parse(env::args().skip(1))?;
+    set_assume_yes(cli.yes);
+    if cli.config_path {
+        // Diagnose without creating anything
+        print_config_path(&workspace_dirs(cli.profile.as_deref(), &cli.dirs));
+        return Ok(ExitCode::Success);
+    }
+    if cli.command() == Some("reset-term") {
+        // Needs neither scripts nor a readable config, whatever state they are in
+        reset_terminal()?;
+        return Ok(ExitCode::Success);
+    }
+    let mut dir = ensure_workspace_dir()?;
+    if let Some(profile) = &cli.profile {
+        dir = find_profile(profile).map_err(|e| Failure::new(ExitCode::NotFound, e))?;
+    }
+    let dirs = workspace_dirs(cli.profile.as_deref(), &cli.dirs);
+    let config = load_config()?;
+
+    let launch_options = LaunchOptions {
+        instance: cli.instance.clone(),
+        login_shell: cli.login_shell,
+        batch: cli.batch,
+        dry_run: cli.dry_run,
+        time: cli.time,
+        wait_for_hyprland: cli.wait_for_hyprland,
+        unsafe_scripts: config.unsafe_scripts,
+        old_hyprland: config.old_hyprland,
+        windows: None,
+        scratch: cli.scratch,
+        systemd_scope: cli.systemd_scope,
+        monitor: cli.monitor.clone(),
+        post_launch_hook: Some(post_launch_hook_path()),
+    };
+
+    if let Some(command @ ("new" | "append" | "duplicate" | "import" | "import-def" | "normalize")) =
+        cli.command()
+        && cli.read_only
+    {
+        return Err(read_only_error(command).into());
+    }
+    if cli.command() == Some("trash")
+        && let Some(subcommand @ ("restore" | "empty")) = cli.arg(1)
+        && cli.read_only
+    {
+        return Err(read_only_error(&format!("trash {subcommand}")).into());
+    }
+
+    match cli.command() {
+        Some("launch") if cli.arg(1).is_none() && cli.filter.is_some() => {
+            return launch_filtered(&cli, &dirs, &launch_options, config);
+        }
+        Some("launch") => {
+            let name = cli.arg(1).ok_or_else(usage_error)?;
+            let workspaces = list_all_workspaces(&dirs)?;
+            let ws = find_workspace(&workspaces, name)
+                .map_err(|e| Failure::new(ExitCode::NotFound, e))?;
+            return launch_confirmed(ws, &launch_options, &config);
+        }
+        Some("launch-ws") => {
+            let num = parse_ws(cli.arg(1).ok_or_else(usage_error)?)?;
+            let workspaces = list_all_workspaces(&dirs)?;
+            match find_workspace_by_num(&workspaces, num) {
+                Ok(ws) => return launch_confirmed(ws, &launch_options, &config),
+                // Several scripts share the number: choose among them when a terminal can
+                Err(err) if err.kind() == io::ErrorKind::InvalidInput && term::supports_tui() => {
+                    return run_interactive(&cli, &dirs, &launch_options, config, None, Some(num));
+                }
+                // Otherwise they are listed; none of them is missing
+                Err(err) if err.kind() == io::ErrorKind::InvalidInput => {
+                    return Err(Failure::new(ExitCode::Error, err));
+                }
+                Err(err) => return Err(Failure::new(ExitCode::NotFound, err)),
+            }
+        }
+        Some("last") => {
+            let Some(path) = load_last_launched() else {
+                let error = io::Error::new(
+                    io::ErrorKind::NotFound,
+                    "no launch recorded yet, launch a workspace once first",
+                );
+                return Err(Failure::new(ExitCode::NotFound, error));
+            };
+            let workspaces = list_all_workspaces(&dirs)?;
+            let Some(ws) = workspaces.iter().find(|ws| ws.full_path == path) else {
+                let error = io::Error::new(
+                    io::ErrorKind::NotFound,
+                    format!(
+                        "the last launched script {} no longer exists",
+                        path.to_string_lossy()
+                    ),
+                );
+                return Err(Failure::new(ExitCode::NotFound, error));
+            };
+            return launch_confirmed(ws, &launch_options, &config);
+        }
+        Some("pick") => {
+            let tui_options = TuiOptions {
+                instance: cli.instance.clone(),
+                pick: true,
+                compact: cli.compact,
+                inline: cli.inline,
+                filter: None,
+                select: cli.select.clone(),
+                check_current_workspace: false,
+                profile: cli.profile.clone(),
+                dirs: dirs.clone(),
+                watch: if cli.watch { dirs.clone() } else { Vec::new() },
+                read_only: cli.read_only,
+                notice: None,
+                config: config.clone(),
+            };
+            let (workspaces, action) = run_tui(list_all_workspaces(&dirs)?, tui_options)?;
+            let Some(ws) = action.and_then(|action| match action {
+                Action::Launch(idx) => workspaces.get(idx),
+                Action::LaunchWindows(..)
+                | Action::Switch(_)
+                | Action::Duplicate(_)
+                | Action::CreateNew(_)
+                | Action::ConvertUnits(_)
+                | Action::LaunchAndStay(_)
+                | Action::DryRun(_) => None,
+            }) else {
+                // Print nothing so `$(hyprspace pick)` stays empty
+                return Ok(ExitCode::Cancelled);
+            };
+
+            if cli.print_path {
+                println!("{}", ws.full_path.to_string_lossy());
+            } else {
+                println!("{}", ws.name_short);
+            }
+            return Ok(ExitCode::Success);
+        }
+        Some("new") if cli.from_clipboard => {
+            create_from_clipboard(&dir, cli.instance.as_deref(), &config)?;
+        }
+        Some("new") => {
+            create_new_script(&dir, cli.instance.as_deref(), &config)?;
+        }
+        Some("append") => {
+            let name = cli.arg(1).ok_or_else(usage_error)?;
+            let workspaces = list_all_workspaces(&dirs)?;
+            let ws = find_workspace(&workspaces, name)
+                .map_err(|e| Failure::new(ExitCode::NotFound, e))?;
+            append_window(ws, cli.instance.as_deref(), &config)?;
+        }
+        Some("duplicate") => {
+            let name = cli.arg(1).ok_or_else(usage_error)?;
+            let workspaces = list_all_workspaces(&dirs)?;
+            let ws = find_workspace(&workspaces, name)
+                .map_err(|e| Failure::new(ExitCode::NotFound, e))?;
+            duplicate_script(ws, &config)?;
+        }
+        Some("step") => {
+            let name = cli.arg(1).ok_or_else(usage_error)?;
+            let workspaces = list_all_workspaces(&dirs)?;
+            let ws = find_workspace(&workspaces, name)
+                .map_err(|e| Failure::new(ExitCode::NotFound, e))?;
+            step_script(ws, &launch_options)
+                .map_err(|e| Failure::new(ExitCode::LaunchFailed, e))?;
+        }
+        Some("switch") => {
+            let name = cli.arg(1).ok_or_else(usage_error)?;
+            let workspaces = list_all_workspaces(&dirs)?;
+            let ws = find_workspace(&workspaces, name)
+                .map_err(|e| Failure::new(ExitCode::NotFound, e))?;
+            switch_to_workspace(ws, &launch_options)
+                .map_err(|e| Failure::new(ExitCode::LaunchFailed, e))?;
+        }
+        Some("list") => {
+            let listed = if cli.archived {
+                archive_dirs(&dirs)
+            } else {
+                dirs.clone()
+            };
+            let mut workspaces = list_all_workspaces(&listed)?;
+            if let Some(num) = cli.ws {
+                workspaces.retain(|ws| ws.workspace_num == Some(num));
+            }
+            // Warnings are part of each JSON object
+            if cli.json_lines {
+                workspaces.truncate(cli.limit.unwrap_or(usize::MAX));
+                print_json_lines(&workspaces);
+            } else if cli.json {
+                workspaces.truncate(cli.limit.unwrap_or(usize::MAX));
+                print_json(&workspaces);
+            } else {
+                print_warnings(&workspaces);
+                print_list(&workspaces, cli.limit, !cli.no_pager)?;
+            }
+        }
+        Some("install-autostart") => {
+            let name = cli.arg(1).ok_or_else(usage_error)?;
+            install_autostart(&dirs, name, cli.profile.as_deref())?;
+        }
+        Some("uninstall-autostart") => {
+            let name = cli.arg(1).ok_or_else(usage_error)?;
+            uninstall_autostart(name, cli.profile.as_deref())?;
+        }
+        Some("completions") => {
+            let shell = cli.arg(1).ok_or_else(usage_error)?;
+            print_completions(shell)?;
+        }
+        Some("export") => {
+            let file = cli.arg(1).ok_or_else(usage_error)?;
+            export_workspaces(&dir, Path::new(file))?;
+        }
+        Some("import") => {
+            let file = cli.arg(1).ok_or_else(usage_error)?;
+            import_workspaces(&dir, Path::new(file), &config)?;
+        }
+        Some("export-def") => {
+            let name = cli.arg(1).ok_or_else(usage_error)?;
+            let workspaces = list_all_workspaces(&dirs)?;
+            let ws = find_workspace(&workspaces, name)
+                .map_err(|e| Failure::new(ExitCode::NotFound, e))?;
+            print!("{}", export_definition(ws, &config)?);
+        }
+        Some("import-def") => {
+            let file = cli.arg(1).ok_or_else(usage_error)?;
+            import_definition(&dir, Path::new(file), cli.instance.as_deref(), &config)?;
+        }
+        Some("doctor") => {
+            if run_doctor(&dirs)? > 0 {
+                return Ok(ExitCode::ProblemsFound);
+            }
+        }
+        Some("trash") => {
+            run_trash(&dirs, cli.arg(1), cli.arg(2)).map_err(|e| match e.kind() {
+                io::ErrorKind::NotFound => Failure::new(ExitCode::NotFound, e),
+                _ => e.into(),
+            })?;
+        }
+        Some("dedupe") => {
+            run_dedupe(&dirs, &config)?;
+        }
+        Some("normalize") => {
+            run_normalize(&dirs, &config, cli.dry_run)?;
+        }
+        Some(_) => return Err(usage_error().into()),
+        None => {
+            if (cli.launch_single || config.launch_single)
+                && cli.select.is_none()
+                && let Some(code) = launch_single_script(&dirs, &launch_options, &config)?
+            {
+                return Ok(code);
+            }
+            return run_interactive(&cli, &dirs, &launch_options, config, None, None);
+        }
+    }
+
+    Ok(ExitCode::Success)
+}
+
+/// `launch --filter <query>`: launch the only match straight away with `--first`,
+/// otherwise open the TUI filtered on the query.
+fn launch_filtered(
+    cli: &Cli,
+    dirs: &[PathBuf],
+    launch_options: &LaunchOptions,
+    config: Config,
+) -> Result<ExitCode, Failure> {
+    let query = cli.filter.as_deref().unwrap_or_default();
+    let workspaces = list_all_workspaces(dirs)?;
+    let matches: Vec<&WorkspaceEntry> = workspaces
+        .iter()
+        .filter(|ws| score_workspace(ws, query).is_some())
+        .collect();
+
+    match matches.as_slice() {
+        [] => {
+            let error = io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("no workspace matches '{query}'"),
+            );
+            Err(Failure::new(ExitCode::NotFound, error))
+        }
+        [ws] if cli.first => launch_confirmed(ws, launch_options, &config),
+        _ => run_interactive(cli, dirs, launch_options, config, Some(query), None),
+    }
+}
+
+/// Launch `ws`, first asking when it targets the focused workspace and a terminal can answer.
+///
+/// Keybinds run without one, so they launch right away as before.
+fn launch_confirmed(
+    ws: &WorkspaceEntry,
+    launch_options: &LaunchOptions,
+    config: &Config,
+) -> Result<ExitCode, Failure> {
+    if config.confirm.current_workspace
+        && io::stdin().is_terminal()
+        && let Some(num) = targets_current_workspace(ws, launch_options)
+    {
+        let question = format!(
+            "{} targets workspace {num}, the one you are on. Launch here anyway?",
+            ws.base_name
+        );
+        if !confirm(&question)? {
+            return Ok(ExitCode::Cancelled);
+        }
+    }
+    launch_script(ws, launch_options).map_err(|e| Failure::new(ExitCode::LaunchFailed, e))?;
+    Ok(ExitCode::Success)
+}
+
+/// Seconds [`launch_single_script`] leaves to cancel with Ctrl-C
+const SINGLE_LAUNCH_COUNTDOWN: u64 = 3;
 
-fn main() -> io::Result<()> {
-    let dir = ensure_workspace_dir()?;
-    let workspaces = list_workspaces(&dir)?;
+/// Launch the script when it is the only one, after a short countdown; `None` when there
+/// are several (or it can't be read) and the TUI should open instead.
+fn launch_single_script(
+    dirs: &[PathBuf],
+    launch_options: &LaunchOptions,
+    config: &Config,
+) -> Result<Option<ExitCode>, Failure> {
+    let workspaces = list_all_workspaces(dirs)?;
+    let [ws] = workspaces.as_slice() else {
+        return Ok(None);
+    };
+    if ws.read_error.is_some() {
+        return Ok(None);
+    }
+
+    for left in (1..=SINGLE_LAUNCH_COUNTDOWN).rev() {
+        eprint!(
+            "\rLaunching {} in {left}s (press Ctrl-C to cancel)",
+            ws.name_short
+        );
+        io::stderr().flush()?;
+        thread::sleep(Duration::from_secs(1));
+    }
+    eprintln!();
+    launch_confirmed(ws, launch_options, config).map(Some)
+}
 
-    let (workspaces, action) = run_tui(workspaces)?;
+/// Run the TUI, then launch or create what was chosen.
+///
+/// With `workspace_num`, only the scripts targeting that workspace are listed.
+fn run_interactive(
+    cli: &Cli,
+    dirs: &[PathBuf],
+    launch_options: &LaunchOptions,
+    config: Config,
+    filter: Option<&str>,
+    workspace_num: Option<u32>,
+) -> Result<ExitCode, Failure> {
+    let mut workspaces = list_all_workspaces(dirs)?;
+    if let Some(num) = workspace_num {
+        workspaces.retain(|ws| ws.workspace_num == Some(num));
+    }
+
+    let mut tui_options = TuiOptions {
+        instance: cli.instance.clone(),
+        pick: false,
+        compact: cli.compact,
+        inline: cli.inline,
+        filter: filter.map(str::to_string),
+        select: cli.select.clone(),
+        check_current_workspace: !launch_options.dry_run && !launch_options.scratch,
+        profile: cli.profile.clone(),
+        dirs: dirs.to_vec(),
+        // Reloading would bring back the scripts left out
+        watch: if cli.watch && workspace_num.is_none() {
+            dirs.to_vec()
+        } else {
+            Vec::new()
+        },
+        read_only: cli.read_only,
+        notice: None,
+        config: config.clone(),
+    };
+    let (workspaces, action) = loop {
+        let (listed, action) = run_tui(workspaces, tui_options.clone())?;
+        let Some(Action::LaunchAndStay(idx)) = action else {
+            break (listed, action);
+        };
+        // Back to the list with the script still selected and the outcome in the footer
+        if let Some(ws) = listed.get(idx) {
+            tui_options.notice = Some(match launch_script(ws, launch_options) {
+                Ok(()) => format!("Launched {}", ws.base_name),
+                Err(err) => format!("Launching {} failed: {err}", ws.base_name),
+            });
+            tui_options.select = Some(ws.name_short.clone());
+        }
+        workspaces = listed;
+    };
 
     match action {
         Some(Action::Launch(idx)) => {
             if let Some(ws) = workspaces.get(idx) {
-                launch_script(ws)?;
+                launch_script(ws, launch_options)
+                    .map_err(|e| Failure::new(ExitCode::LaunchFailed, e))?;
+            }
+        }
+        Some(Action::LaunchWindows(idx, windows)) => {
+            if let Some(ws) = workspaces.get(idx) {
+                let options = LaunchOptions {
+                    windows: Some(windows),
+                    ..launch_options.clone()
+                };
+                launch_script(ws, &options).map_err(|e| Failure::new(ExitCode::LaunchFailed, e))?;
+            }
+        }
+        Some(Action::Switch(idx)) => {
+            if let Some(ws) = workspaces.get(idx) {
+                switch_to_workspace(ws, launch_options)
+                    .map_err(|e| Failure::new(ExitCode::LaunchFailed, e))?;
+            }
+        }
+        Some(Action::DryRun(idx)) => {
+            if let Some(ws) = workspaces.get(idx) {
+                let options = LaunchOptions {
+                    dry_run: true,
+                    ..launch_options.clone()
+                };
+                launch_script(ws, &options).map_err(|e| Failure::new(ExitCode::LaunchFailed, e))?;
+            }
+        }
+        // Handled by the loop above
+        Some(Action::LaunchAndStay(_)) => {}
+        Some(Action::Duplicate(idx)) => {
+            if let Some(ws) = workspaces.get(idx) {
+                duplicate_script(ws, &config)?;
+            }
+        }
+        Some(Action::ConvertUnits(idx)) => {
+            if let Some(ws) = workspaces.get(idx) {
+                convert_script_units(ws, cli.instance.as_deref(), &config)?;
             }
         }
-        Some(Action::CreateNew) => {
+        Some(Action::CreateNew(dir)) => {
             // We are back in normal terminal mode here
-            create_new_script(&dir)?;
+            create_new_script(&dir, cli.instance.as_deref(), &config)?;
         }
         None => {
             // User quit with q / Esc
+            return Ok(ExitCode::Cancelled);
         }
     }
 
-    Ok(())
+    Ok(ExitCode::Success)
 }
@@ -0,0 +1,131 @@
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+use crate::config::Config;
+use crate::diff::{DiffLine, diff_lines};
+use crate::script::{ScriptDocument, ScriptSpec, build_script_content};
+use crate::workspace::{
+    WorkspaceEntry, confirm, display_name, has_multiple_sources, list_all_workspaces,
+};
+
+/// A script whose regenerated content differs from what is on disk
+struct Rewrite<'a> {
+    ws: &'a WorkspaceEntry,
+    content: String,
+    added: usize,
+    removed: usize,
+}
+
+/// The content `ws` regenerated with the current generator settings, or why it can't be
+/// regenerated without losing anything.
+fn regenerate(ws: &WorkspaceEntry, content: &str, config: &Config) -> Result<String, String> {
+    if let Some(err) = &ws.read_error {
+        return Err(format!("cannot be read: {err}"));
+    }
+    let Some(workspace) = ws.workspace() else {
+        return Err("no `hyprctl dispatch workspace N` line".to_string());
+    };
+    if let Some(warning) = ws.warnings.first() {
+        return Err(format!("{warning} (see `hyprspace doctor`)"));
+    }
+
+    let doc = ScriptDocument::parse_with_helper(content, &config.generator.helper);
+    if let Some(content) = doc.hand_edited_content() {
+        return Err(format!("hand-edited, {content} would be lost"));
+    }
+
+    let spec = ScriptSpec {
+        workspace,
+        description: ws.description.clone(),
+        icon: ws.icon.clone(),
+        order: ws.order,
+        instance: doc.instance(),
+        close_existing: doc.closes_existing(),
+        helper: config.generator.helper.clone(),
+        windows: doc.windows(),
+    };
+    let new_content = build_script_content(&spec);
+
+    // Whatever the parser missed must not silently change what the script spawns
+    if ScriptDocument::parse_with_helper(&new_content, &spec.helper).windows() != spec.windows {
+        return Err("its windows would not survive the rewrite".to_string());
+    }
+    Ok(new_content)
+}
+
+/// Regenerate every script in `dirs` with the current generator settings (`hyprspace normalize`).
+///
+/// Shows which scripts would change and asks before writing; scripts with content the
+/// generator can't reproduce are listed and left alone. With `dry_run`, only the summary is shown.
+pub fn run_normalize(dirs: &[PathBuf], config: &Config, dry_run: bool) -> io::Result<()> {
+    let workspaces = list_all_workspaces(dirs)?;
+    let multiple_sources = has_multiple_sources(&workspaces);
+    let mut rewrites = Vec::new();
+    let mut skipped = Vec::new();
+    for ws in &workspaces {
+        let content = fs::read_to_string(&ws.full_path).unwrap_or_default();
+        match regenerate(ws, &content, config) {
+            Ok(new_content) if new_content == content => {}
+            Ok(new_content) => {
+                let diff = diff_lines(&content, &new_content);
+                rewrites.push(Rewrite {
+                    ws,
+                    added: diff
+                        .iter()
+                        .filter(|l| matches!(l, DiffLine::Added(_)))
+                        .count(),
+                    removed: diff
+                        .iter()
+                        .filter(|l| matches!(l, DiffLine::Removed(_)))
+                        .count(),
+                    content: new_content,
+                });
+            }
+            Err(reason) => skipped.push((ws, reason)),
+        }
+    }
+
+    println!("Checked {} script(s).", workspaces.len());
+    if !skipped.is_empty() {
+        println!("\nLeft alone:");
+        for (ws, reason) in &skipped {
+            println!("  {}: {reason}", display_name(ws, multiple_sources));
+        }
+    }
+    if rewrites.is_empty() {
+        let which = if skipped.is_empty() {
+            "Every"
+        } else {
+            "Every other"
+        };
+        println!("\n{which} script already matches the current template.");
+        return Ok(());
+    }
+
+    println!("\nWould rewrite:");
+    for rewrite in &rewrites {
+        println!(
+            "  {}  (+{} -{} lines)",
+            display_name(rewrite.ws, multiple_sources),
+            rewrite.added,
+            rewrite.removed
+        );
+    }
+    println!();
+
+    if dry_run {
+        println!("Dry run, nothing was written.");
+        return Ok(());
+    }
+    if !confirm(&format!("Rewrite {} script(s)?", rewrites.len()))? {
+        println!("Aborted, no script was changed.");
+        return Ok(());
+    }
+
+    for rewrite in &rewrites {
+        fs::write(&rewrite.ws.full_path, &rewrite.content)?;
+        println!("Rewrote {}", display_name(rewrite.ws, multiple_sources));
+    }
+    Ok(())
+}
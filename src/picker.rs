@@ -0,0 +1,129 @@
+use std::io::{self, Write};
+
+use crate::fuzzy::score_workspace;
+use crate::list::{badge, badge_width, programs_summary};
+use crate::tui::{Action, TuiOptions, current_workspace_to_confirm};
+use crate::workspace::{WorkspaceEntry, assume_yes};
+
+/// Print `label` on stderr and read one trimmed line, `None` at end of input.
+fn ask(label: &str) -> io::Result<Option<String>> {
+    eprint!("{label}");
+    io::stderr().flush()?;
+    let mut buf = String::new();
+    if io::stdin().read_line(&mut buf)? == 0 {
+        return Ok(None);
+    }
+    Ok(Some(buf.trim().to_string()))
+}
+
+/// Print the numbered scripts of `shown` (indices into `workspaces`) on stderr.
+fn print_choices(workspaces: &[WorkspaceEntry], shown: &[usize], can_create: bool) {
+    let width = badge_width(workspaces);
+    let index_width = shown.len().to_string().len();
+    for (pos, &idx) in shown.iter().enumerate() {
+        let ws = &workspaces[idx];
+        let mut line = format!(
+            "{:>index_width$}. {} {}",
+            pos + 1,
+            badge(ws, width),
+            ws.name_short
+        );
+        if let Some(summary) = programs_summary(ws) {
+            line.push_str(&format!(" {summary}"));
+        }
+        if let Some(desc) = &ws.description {
+            line.push_str(&format!(" - {desc}"));
+        }
+        eprintln!("{line}");
+    }
+    if can_create {
+        eprintln!("{:>index_width$}. Create new workspace script", "n");
+    }
+}
+
+/// Line-based stand-in for the TUI on terminals that can't draw it (`TERM=dumb`, not a TTY).
+///
+/// Lists the scripts and reads a number, or a query narrowing the list down like the
+/// TUI filter; an empty answer or end of input quits.
+pub fn run_plain_picker(
+    workspaces: Vec<WorkspaceEntry>,
+    options: &TuiOptions,
+) -> io::Result<(Vec<WorkspaceEntry>, Option<Action>)> {
+    let can_create = !options.pick && !options.read_only;
+    let verb = if options.pick { "Pick" } else { "Launch" };
+    let matching = |query: &str| -> Vec<usize> {
+        (0..workspaces.len())
+            .filter(|&idx| query.is_empty() || score_workspace(&workspaces[idx], query).is_some())
+            .collect()
+    };
+
+    let mut shown = matching(options.filter.as_deref().unwrap_or_default());
+    if shown.is_empty() {
+        shown = matching("");
+    }
+
+    let action = loop {
+        print_choices(&workspaces, &shown, can_create);
+        let Some(answer) = ask(&format!("{verb} which? (number or name, empty to quit): "))? else {
+            break None;
+        };
+
+        if answer.is_empty() {
+            break None;
+        }
+        if can_create && answer == "n" {
+            break Some(Action::CreateNew(options.dirs[0].clone()));
+        }
+        if let Ok(pos) = answer.parse::<usize>() {
+            match pos.checked_sub(1).and_then(|pos| shown.get(pos)) {
+                Some(&idx) => break Some(Action::Launch(idx)),
+                None => {
+                    eprintln!("No script #{pos}.\n");
+                    continue;
+                }
+            }
+        }
+        if let Some(idx) = shown
+            .iter()
+            .copied()
+            .find(|&idx| workspaces[idx].name_short == answer)
+        {
+            break Some(Action::Launch(idx));
+        }
+
+        match matching(&answer).as_slice() {
+            [] => eprintln!("No script matches '{answer}'.\n"),
+            [idx] => break Some(Action::Launch(*idx)),
+            found => {
+                eprintln!("{} scripts match '{answer}':\n", found.len());
+                shown = found.to_vec();
+            }
+        }
+    };
+
+    // Asked here too, since this picker has no confirmation popup of its own
+    let question = match action {
+        Some(Action::Launch(idx)) => {
+            match current_workspace_to_confirm(options, &workspaces[idx]) {
+                Some(num) => Some(format!(
+                    "{} targets workspace {num}, the one you are on. Launch here anyway? [y/N]: ",
+                    workspaces[idx].name_short
+                )),
+                None if options.config.confirm.launch && !options.pick => {
+                    Some(format!("Launch {}? [y/N]: ", workspaces[idx].name_short))
+                }
+                None => None,
+            }
+        }
+        _ => None,
+    };
+    // `--yes` answers it
+    if let Some(question) = question.filter(|_| !assume_yes()) {
+        let answer = ask(&question)?.unwrap_or_default();
+        if !matches!(answer.to_lowercase().as_str(), "y" | "yes") {
+            return Ok((workspaces, None));
+        }
+    }
+
+    Ok((workspaces, action))
+}
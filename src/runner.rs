@@ -0,0 +1,127 @@
+use std::fmt;
+use std::io::{self, BufRead, BufReader};
+use std::os::unix::process::ExitStatusExt;
+use std::process::{Command, ExitStatus, Output, Stdio};
+
+/// Starts the external commands of a launch (the script, hyprctl, the post-launch hook).
+///
+/// [`SystemRunner`] runs them; [`RecordingRunner`] only notes them down, for dry runs
+/// and tests.
+pub trait CommandRunner {
+    /// Run `command` with inherited stdio and wait for it. With `on_line`, stdout is
+    /// piped instead and each of its lines is handed over as it arrives.
+    fn status(
+        &mut self,
+        command: &mut Command,
+        on_line: Option<&mut dyn FnMut(&str)>,
+    ) -> io::Result<ExitStatus>;
+
+    /// Run `command` and collect its output.
+    fn output(&mut self, command: &mut Command) -> io::Result<Output>;
+}
+
+/// Runs commands for real
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemRunner;
+
+impl CommandRunner for SystemRunner {
+    fn status(
+        &mut self,
+        command: &mut Command,
+        on_line: Option<&mut dyn FnMut(&str)>,
+    ) -> io::Result<ExitStatus> {
+        let Some(on_line) = on_line else {
+            return command.status();
+        };
+
+        let mut child = command.stdout(Stdio::piped()).spawn()?;
+        if let Some(stdout) = child.stdout.take() {
+            for line in BufReader::new(stdout).lines() {
+                on_line(&line?);
+            }
+        }
+        child.wait()
+    }
+
+    fn output(&mut self, command: &mut Command) -> io::Result<Output> {
+        command.output()
+    }
+}
+
+/// A command as it would have been started
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecordedCommand {
+    pub program: String,
+    pub args: Vec<String>,
+    /// Environment variables set for this command only
+    pub envs: Vec<(String, String)>,
+}
+
+impl RecordedCommand {
+    fn of(command: &Command) -> Self {
+        let text = |s: &std::ffi::OsStr| s.to_string_lossy().into_owned();
+        Self {
+            program: text(command.get_program()),
+            args: command.get_args().map(text).collect(),
+            envs: command
+                .get_envs()
+                .filter_map(|(key, value)| Some((text(key), text(value?))))
+                .collect(),
+        }
+    }
+}
+
+/// Single-quote `word` for the shell when it holds anything but plain characters.
+pub(crate) fn shell_quote(word: &str) -> String {
+    let plain = !word.is_empty()
+        && word
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "-_./=:@%+,".contains(c));
+    if plain {
+        word.to_string()
+    } else {
+        format!("'{}'", word.replace('\'', r"'\''"))
+    }
+}
+
+impl fmt::Display for RecordedCommand {
+    /// Shell-like form, e.g. `HYPRLAND_INSTANCE_SIGNATURE=abc hyprctl --batch 'dispatch ...'`
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let words: Vec<String> = self
+            .envs
+            .iter()
+            .map(|(key, value)| format!("{key}={}", shell_quote(value)))
+            .chain([shell_quote(&self.program)])
+            .chain(self.args.iter().map(|arg| shell_quote(arg)))
+            .collect();
+        write!(f, "{}", words.join(" "))
+    }
+}
+
+/// Notes every command down without starting it.
+///
+/// Every command succeeds, and those whose output is read (hyprctl) answer `ok`.
+#[derive(Debug, Clone, Default)]
+pub struct RecordingRunner {
+    pub commands: Vec<RecordedCommand>,
+}
+
+impl CommandRunner for RecordingRunner {
+    fn status(
+        &mut self,
+        command: &mut Command,
+        _on_line: Option<&mut dyn FnMut(&str)>,
+    ) -> io::Result<ExitStatus> {
+        self.commands.push(RecordedCommand::of(command));
+        Ok(ExitStatus::from_raw(0))
+    }
+
+    fn output(&mut self, command: &mut Command) -> io::Result<Output> {
+        self.commands.push(RecordedCommand::of(command));
+        Ok(Output {
+            status: ExitStatus::from_raw(0),
+            stdout: b"ok\n".to_vec(),
+            stderr: Vec::new(),
+        })
+    }
+}
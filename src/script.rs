@@ -0,0 +1,1564 @@
+use std::collections::HashSet;
+use std::env;
+use std::time::Duration;
+
+use crate::workspace::Workspace;
+
+/// A single window spawned by a script through `rule_exec` or `hyprctl dispatch exec`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct WindowSpec {
+    /// Hyprland rules, e.g. "workspace 3 silent; float; size 10% 15%; move 1% 8%"
+    pub rules: String,
+    /// Command launched with those rules
+    pub command: String,
+    /// Directory the command starts in, as written (a leading `~/` is expanded), if any
+    pub cwd: Option<String>,
+    /// Seconds to `sleep` before spawning this window, if any
+    pub delay: Option<String>,
+    /// `hyprctl dispatch layoutmsg` message sent right before spawning this window,
+    /// e.g. `preselect r` so it tiles to the right (dwindle)
+    pub layout_hint: Option<String>,
+    /// Unit of the `size` / `move` values, `None` without any (see [`Unit::of_rules`])
+    pub unit: Option<Unit>,
+    /// Whether hyprspace focuses this window once the launch is done (a [`FOCUS_MARKER`]
+    /// comment right before it)
+    pub focus: bool,
+}
+
+/// How the `size` and `move` values of a window are expressed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Unit {
+    /// Percent of the monitor, e.g. `size 10% 15%`
+    Percent,
+    /// Absolute pixels, e.g. `size 800 600`
+    Pixels,
+    /// Both kinds within the same window
+    Mixed,
+}
+
+impl Unit {
+    /// The unit used by the numeric `size` / `move` values in `rules`.
+    ///
+    /// Expressions such as `100%-w` or `cursor` are not counted.
+    pub fn of_rules(rules: &str) -> Option<Unit> {
+        let (mut percent, mut pixels) = (false, false);
+        for rule in rules.split(';') {
+            let mut words = rule.split_whitespace();
+            if !matches!(words.next(), Some("size" | "move")) || is_cursor_move(rule) {
+                continue;
+            }
+            for value in words {
+                match value.strip_suffix('%') {
+                    Some(number) if number.parse::<f64>().is_ok() => percent = true,
+                    None if value.parse::<i64>().is_ok() => pixels = true,
+                    _ => {}
+                }
+            }
+        }
+        match (percent, pixels) {
+            (true, true) => Some(Unit::Mixed),
+            (true, false) => Some(Unit::Percent),
+            (false, true) => Some(Unit::Pixels),
+            (false, false) => None,
+        }
+    }
+
+    /// Human-readable name, e.g. for the detail view
+    pub fn label(self) -> &'static str {
+        match self {
+            Unit::Percent => "percent of monitor",
+            Unit::Pixels => "pixels",
+            Unit::Mixed => "mixed percent and pixels",
+        }
+    }
+
+    /// Validate a value typed in the wizard and format it for a rule (`10` → `10%` in percent).
+    ///
+    /// Sizes must be positive; positions may be negative in pixels (monitors left of or
+    /// above the primary one).
+    pub fn parse_value(self, input: &str, is_size: bool) -> Result<String, String> {
+        match self {
+            Unit::Percent => {
+                let number = input.trim().trim_end_matches('%');
+                match number.parse::<f64>() {
+                    Ok(n) if is_size && n > 0.0 && n <= 100.0 => Ok(format!("{number}%")),
+                    Ok(n) if !is_size && (0.0..=100.0).contains(&n) => Ok(format!("{number}%")),
+                    _ if is_size => Err("enter a percentage above 0 and up to 100".to_string()),
+                    _ => Err("enter a percentage from 0 to 100".to_string()),
+                }
+            }
+            Unit::Pixels | Unit::Mixed => match input.trim().parse::<i64>() {
+                Ok(n) if is_size && n <= 0 => Err("enter a positive number of pixels".to_string()),
+                Ok(n) => Ok(n.to_string()),
+                Err(_) if input.contains('%') => {
+                    Err("pixels are whole numbers, without %".to_string())
+                }
+                Err(_) => Err("enter a whole number of pixels".to_string()),
+            },
+        }
+    }
+}
+
+impl WindowSpec {
+    /// The program the command runs (its first word), e.g. "kitty"
+    pub fn program(&self) -> Option<&str> {
+        self.command
+            .split_whitespace()
+            .next()
+            .map(|word| word.trim_matches(|c| c == '"' || c == '\''))
+    }
+
+    /// The command handed to `hyprctl dispatch exec`, moving to [`cwd`](Self::cwd) first
+    pub fn exec_command(&self) -> String {
+        match &self.cwd {
+            Some(dir) => format!("cd {} && {}", sh_quote_dir(dir), self.command),
+            None => self.command.clone(),
+        }
+    }
+
+    /// Names the window's class probably matches: the program's file name and any
+    /// `--class` / `--app-id` argument
+    pub fn class_names(&self) -> Vec<&str> {
+        let mut names: Vec<&str> = self
+            .program()
+            .map(|program| program.rsplit('/').next().unwrap_or(program))
+            .into_iter()
+            .collect();
+        let mut words = self.command.split_whitespace();
+        while let Some(word) = words.next() {
+            match word.split_once('=') {
+                Some(("--class" | "--app-id", value)) => names.push(value),
+                _ if word == "--class" || word == "--app-id" => names.extend(words.next()),
+                _ => {}
+            }
+        }
+        names
+    }
+
+    /// Problems with the rule syntax Hyprland would reject (see [`validate_rules`])
+    pub fn rule_problems(&self) -> Vec<String> {
+        validate_rules(&self.rules)
+    }
+
+    /// The number of the `workspace N` rule, if any
+    pub fn workspace_num(&self) -> Option<u32> {
+        self.rules.split(';').find_map(|rule| {
+            let mut words = rule.split_whitespace();
+            if words.next()? != "workspace" {
+                return None;
+            }
+            words.next()?.parse().ok()
+        })
+    }
+
+    /// Keyword of the first rule other than `workspace N`, e.g. `float`
+    pub fn main_rule_keyword(&self) -> Option<&str> {
+        self.rules
+            .split(';')
+            .filter_map(|rule| rule.split_whitespace().next())
+            .find(|keyword| *keyword != "workspace")
+    }
+
+    /// Whether the window floats, so layout hints don't apply to it
+    pub fn is_floating(&self) -> bool {
+        self.rules.split(';').any(|rule| rule.trim() == "float")
+    }
+
+    /// Whether the `workspace N` rule carries `silent`, keeping focus where it was.
+    ///
+    /// Windows without a workspace rule open in place and are reported as not silent.
+    pub fn is_silent(&self) -> bool {
+        self.rules.split(';').map(str::trim).any(|rule| {
+            let mut words = rule.split_whitespace();
+            words.next() == Some("workspace") && words.any(|word| word == "silent")
+        })
+    }
+
+    /// The rules with any `workspace ...` directive removed, so the window opens where you are.
+    pub fn rules_without_workspace(&self) -> String {
+        self.rules
+            .split(';')
+            .map(str::trim)
+            .filter(|rule| !rule.is_empty() && rule.split_whitespace().next() != Some("workspace"))
+            .collect::<Vec<_>>()
+            .join("; ")
+    }
+}
+
+/// One message per window whose `workspace N` rule sends it somewhere other than
+/// `workspace_num`, the workspace the script switches to.
+pub fn workspace_mismatches(workspace_num: u32, windows: &[WindowSpec]) -> Vec<String> {
+    windows
+        .iter()
+        .enumerate()
+        .filter_map(|(idx, window)| {
+            let num = window.workspace_num().filter(|num| *num != workspace_num)?;
+            Some(format!(
+                "window #{} ({}) opens on workspace {num}, but the script switches to workspace {workspace_num}",
+                idx + 1,
+                window.program().unwrap_or("?")
+            ))
+        })
+        .collect()
+}
+
+/// `rules` with every `workspace old` rule (`silent` or not) sent to workspace `new`,
+/// spacing kept as written.
+pub fn retarget_rules(rules: &str, old: u32, new: u32) -> String {
+    let retarget = |rule: &str| -> Option<String> {
+        let trimmed = rule.trim_start();
+        let lead = &rule[..rule.len() - trimmed.len()];
+        let rest = trimmed.strip_prefix("workspace")?;
+        let args = rest.trim_start();
+        if args.len() == rest.len() {
+            return None;
+        }
+        let num = args.split_whitespace().next()?;
+        (num == old.to_string()).then(|| format!("{lead}workspace {new}{}", &args[num.len()..]))
+    };
+
+    rules
+        .split(';')
+        .map(|rule| retarget(rule).unwrap_or_else(|| rule.to_string()))
+        .collect::<Vec<_>>()
+        .join(";")
+}
+
+/// `rules` with the numeric `size` and `move` values expressed in `to` (percent or pixels)
+/// for a `width`×`height` monitor.
+///
+/// Expressions such as `100%-w` or `cursor` are left alone.
+pub fn convert_rules(rules: &str, to: Unit, width: u32, height: u32) -> String {
+    let convert = |value: &str, extent: u32| -> Option<String> {
+        let extent = f64::from(extent);
+        match (to, value.strip_suffix('%')) {
+            (Unit::Pixels, Some(number)) => {
+                let percent = number.parse::<f64>().ok()?;
+                Some(format!("{}", (percent * extent / 100.0).round() as i64))
+            }
+            (Unit::Percent, None) => {
+                let pixels = value.parse::<i64>().ok()?;
+                let percent = format!("{:.2}", pixels as f64 * 100.0 / extent);
+                Some(format!(
+                    "{}%",
+                    percent.trim_end_matches('0').trim_end_matches('.')
+                ))
+            }
+            _ => None,
+        }
+    };
+    let convert_rule = |rule: &str| -> Option<String> {
+        let trimmed = rule.trim_start();
+        let lead = &rule[..rule.len() - trimmed.len()];
+        let mut words = trimmed.split_whitespace();
+        let keyword = words.next().filter(|k| matches!(*k, "size" | "move"))?;
+        if is_cursor_move(rule) {
+            return None;
+        }
+
+        let mut changed = false;
+        let mut axis = 0;
+        let mut out = vec![keyword.to_string()];
+        for word in words {
+            if word == "onscreen" {
+                out.push(word.to_string());
+                continue;
+            }
+            let extent = if axis == 0 { width } else { height };
+            axis += 1;
+            match convert(word, extent) {
+                Some(value) => {
+                    changed = true;
+                    out.push(value);
+                }
+                None => out.push(word.to_string()),
+            }
+        }
+        changed.then(|| format!("{lead}{}", out.join(" ")))
+    };
+
+    rules
+        .split(';')
+        .map(|rule| convert_rule(rule).unwrap_or_else(|| rule.to_string()))
+        .collect::<Vec<_>>()
+        .join(";")
+}
+
+/// Whether `rule` is a `move cursor x y` rule, whose values are offsets from the cursor
+/// (in percent of the window) rather than monitor positions
+fn is_cursor_move(rule: &str) -> bool {
+    let mut words = rule.split_whitespace().filter(|word| *word != "onscreen");
+    words.next() == Some("move") && words.next() == Some("cursor")
+}
+
+/// `rules` with its `monitor` rule replaced by `monitor <monitor>`, or with one added.
+pub fn with_monitor(rules: &str, monitor: &str) -> String {
+    rules
+        .split(';')
+        .map(str::trim)
+        .filter(|rule| !rule.is_empty() && rule.split_whitespace().next() != Some("monitor"))
+        .map(str::to_string)
+        .chain([format!("monitor {monitor}")])
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+/// Check that `size` and `move` rules each have exactly two space-separated arguments.
+///
+/// Catches typos such as `move 1%8%` that Hyprland silently rejects.
+pub fn validate_rules(rules: &str) -> Vec<String> {
+    let mut problems = Vec::new();
+
+    for rule in rules.split(';').map(str::trim).filter(|r| !r.is_empty()) {
+        let mut words = rule.split_whitespace();
+        let Some(keyword) = words.next() else {
+            continue;
+        };
+
+        if keyword == "size" || keyword == "move" {
+            let args: Vec<&str> = words.collect();
+            if args.len() != 2 {
+                problems.push(format!(
+                    "`{rule}`: {keyword} expects 2 arguments (X Y), got {}",
+                    args.len()
+                ));
+            }
+        }
+    }
+
+    problems
+}
+
+/// Everything needed to generate a workspace script
+#[derive(Debug, Clone)]
+pub struct ScriptSpec {
+    /// Workspace the script switches to, or the special workspace (scratchpad) it toggles
+    pub workspace: Workspace,
+    /// Optional `# hyprspace: <description>` header
+    pub description: Option<String>,
+    /// Optional `# hyprspace-icon: <glyph>` header
+    pub icon: Option<String>,
+    /// Optional `# hyprspace-order: <N>` header
+    pub order: Option<i32>,
+    /// Hyprland instance signature the hyprctl calls should target
+    pub instance: Option<String>,
+    /// Close the windows already on the workspace before spawning, so re-running is idempotent
+    pub close_existing: bool,
+    /// Name of the generated helper function (`rule_exec` by default)
+    pub helper: String,
+    /// Windows spawned through `rule_exec`, in order
+    pub windows: Vec<WindowSpec>,
+}
+
+impl ScriptSpec {
+    /// A script for `workspace` with no headers and no windows, using the default helper
+    pub fn new(workspace: Workspace) -> Self {
+        ScriptSpec {
+            workspace,
+            description: None,
+            icon: None,
+            order: None,
+            instance: None,
+            close_existing: false,
+            helper: DEFAULT_HELPER.to_string(),
+            windows: Vec::new(),
+        }
+    }
+}
+
+/// Generate the bash script for the given spec.
+pub fn build_script_content(spec: &ScriptSpec) -> String {
+    let mut content = String::new();
+
+    // Header and workspace dispatch
+    content.push_str("#!/bin/bash\n");
+    if let Some(description) = &spec.description {
+        content.push_str(&format!("# hyprspace: {description}\n"));
+    }
+    if let Some(icon) = &spec.icon {
+        content.push_str(&format!("# hyprspace-icon: {icon}\n"));
+    }
+    if let Some(order) = spec.order {
+        content.push_str(&format!("# hyprspace-order: {order}\n"));
+    }
+    content.push('\n');
+
+    let hyprctl = match &spec.instance {
+        Some(sig) => format!("hyprctl --instance {sig}"),
+        None => "hyprctl".to_string(),
+    };
+    if spec.close_existing {
+        let preamble = match &spec.workspace {
+            Workspace::Numbered(num) => close_existing_text(&hyprctl, *num),
+            Workspace::Special(name) => close_existing_special_text(&hyprctl, name),
+        };
+        content.push_str(&preamble);
+        content.push_str("\n\n");
+    }
+    let [dispatcher, target] = spec.workspace.dispatch();
+    content.push_str(&format!("{hyprctl} dispatch {dispatcher} {target}\n\n"));
+
+    // rule_exec helper
+    content.push_str(&helper_text(&spec.helper, spec.instance.as_deref()));
+    content.push_str("\n\n");
+
+    // One rule_exec block per window, preceded by its delay and layout hint
+    for window in &spec.windows {
+        if let Some(seconds) = &window.delay {
+            content.push_str(&format!("sleep {seconds}\n\n"));
+        }
+        if let Some(message) = &window.layout_hint {
+            content.push_str(&layout_text(&hyprctl, message));
+            content.push('\n');
+        }
+        if window.focus {
+            content.push_str(FOCUS_MARKER);
+            content.push('\n');
+        }
+        content.push_str(&window_text(&spec.helper, window));
+        content.push_str("\n\n");
+    }
+
+    content
+}
+
+/// A rule a script sets up front with `hyprctl keyword windowrulev2 "<rule>, <matchers>"`
+/// (or `windowrule "<rule>, <class regex>"`), for the windows it spawns afterwards
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WindowRule {
+    /// The rule itself, e.g. `size 800 600`
+    pub rule: String,
+    /// Class regex the rule targets (`class:` or `initialClass:`), if any
+    pub class: Option<String>,
+    /// Whether it also matches on something else (title, xwayland...), which can't be
+    /// told from a command
+    pub other_matchers: bool,
+}
+
+impl WindowRule {
+    /// Whether the rule would apply to the window `window` spawns, as far as the command tells.
+    ///
+    /// Only plain class patterns (`^(kitty)$`, `firefox|chromium`) are understood; they are
+    /// compared to the program's file name and to a `--class` / `--app-id` argument.
+    pub fn applies_to(&self, window: &WindowSpec) -> bool {
+        let Some(class) = &self.class else {
+            return false;
+        };
+        if self.other_matchers {
+            return false;
+        }
+
+        let names = window.class_names();
+        let pattern = class.trim_start_matches('^').trim_end_matches('$');
+        let pattern = pattern
+            .strip_prefix('(')
+            .and_then(|p| p.strip_suffix(')'))
+            .unwrap_or(pattern);
+        pattern.split('|').any(|alternative| {
+            let literal = alternative.replace("\\.", ".");
+            let is_plain = literal
+                .chars()
+                .all(|c| c.is_alphanumeric() || "-_.".contains(c));
+            is_plain && names.iter().any(|name| name.eq_ignore_ascii_case(&literal))
+        })
+    }
+}
+
+/// A chunk of a script, kept verbatim so untouched parts survive a rewrite
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Block {
+    /// The `#!` interpreter line
+    Shebang(String),
+    /// The `rule_exec() { ... }` helper definition
+    Helper(String),
+    /// A `rule_exec` call or a direct `hyprctl dispatch exec`, with the text it was parsed from
+    Window { raw: String, spec: WindowSpec },
+    /// A persistent `hyprctl keyword windowrulev2` (or `windowrule`) rule
+    Rule { raw: String, rule: WindowRule },
+    /// A `sleep <seconds>` pause between windows
+    Delay { raw: String, seconds: String },
+    /// A `hyprctl dispatch layoutmsg <message>` hint for the next tiled window
+    Layout { raw: String, message: String },
+    /// A [`FOCUS_MARKER`] comment: the next window gets focus after launch
+    Focus(String),
+    /// Anything else: comments, dispatch lines, custom commands, blank lines
+    Line(String),
+}
+
+impl Block {
+    /// The script text of this block (without a trailing newline)
+    pub fn text(&self) -> &str {
+        match self {
+            Block::Shebang(text) | Block::Helper(text) | Block::Focus(text) | Block::Line(text) => {
+                text
+            }
+            Block::Window { raw, .. }
+            | Block::Rule { raw, .. }
+            | Block::Delay { raw, .. }
+            | Block::Layout { raw, .. } => raw,
+        }
+    }
+}
+
+/// A workspace script as a sequence of blocks.
+///
+/// Rendering an unmodified document gives back the original text byte for byte;
+/// editing a window only regenerates that window's block.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ScriptDocument {
+    pub blocks: Vec<Block>,
+    /// Name of the helper function window blocks call
+    helper: String,
+    /// Whether the original text ended with a newline
+    trailing_newline: bool,
+}
+
+impl ScriptDocument {
+    /// Split script content into blocks, with the helper this script defines or `rule_exec`.
+    pub fn parse(content: &str) -> Self {
+        Self::parse_with_helper(content, DEFAULT_HELPER)
+    }
+
+    /// Split script content into blocks.
+    ///
+    /// Window blocks are calls to the helper function the script defines (any function
+    /// running `dispatch exec`), falling back to `helper` when it defines none.
+    pub fn parse_with_helper(content: &str, helper: &str) -> Self {
+        let helper = detect_helper(content).unwrap_or_else(|| helper.to_string());
+        let mut blocks = Vec::new();
+        // Split on `\n` only, so a `\r` of CRLF endings stays in the raw text
+        let body = content.strip_suffix('\n').unwrap_or(content);
+        let mut lines = body.split('\n').filter(|_| !content.is_empty());
+
+        while let Some(line) = lines.next() {
+            let mut raw = line.to_string();
+
+            if blocks.is_empty() && line.starts_with("#!") {
+                blocks.push(Block::Shebang(raw));
+                continue;
+            }
+
+            if is_helper_start(line, &helper) {
+                // Keep everything up to the closing brace
+                let mut closed = line.trim_end().ends_with('}');
+                while !closed {
+                    let Some(next) = lines.next() else { break };
+                    raw.push('\n');
+                    raw.push_str(next);
+                    closed = is_closing_brace(next);
+                }
+                blocks.push(Block::Helper(raw));
+                continue;
+            }
+
+            // Gather `\` continuation lines into one logical line; a comment ends at its line
+            let mut logical = line.trim().to_string();
+            while !logical.starts_with('#')
+                && let Some(head) = logical.strip_suffix('\\')
+            {
+                let Some(next) = lines.next() else { break };
+                logical = format!("{} {}", head.trim_end(), next.trim());
+                raw.push('\n');
+                raw.push_str(next);
+            }
+
+            if logical == FOCUS_MARKER {
+                blocks.push(Block::Focus(raw));
+            } else if let Some(spec) =
+                parse_rule_exec(&logical, &helper).or_else(|| parse_dispatch_exec(&logical))
+            {
+                blocks.push(Block::Window { raw, spec });
+            } else if let Some(rule) = parse_window_rule(&logical) {
+                blocks.push(Block::Rule { raw, rule });
+            } else if let Some(seconds) = parse_sleep(&logical) {
+                blocks.push(Block::Delay { raw, seconds });
+            } else if let Some(message) = parse_layoutmsg(&logical) {
+                blocks.push(Block::Layout { raw, message });
+            } else {
+                blocks.push(Block::Line(raw));
+            }
+        }
+
+        ScriptDocument {
+            blocks,
+            helper,
+            trailing_newline: content.ends_with('\n'),
+        }
+    }
+
+    /// Render the document back to script text.
+    pub fn render(&self) -> String {
+        let mut out = self
+            .blocks
+            .iter()
+            .map(Block::text)
+            .collect::<Vec<_>>()
+            .join("\n");
+        if self.trailing_newline {
+            out.push('\n');
+        }
+        out
+    }
+
+    /// Whether the script starts by closing the windows already on its workspace
+    pub fn closes_existing(&self) -> bool {
+        self.blocks
+            .iter()
+            .any(|b| matches!(b, Block::Line(line) if line.starts_with(CLOSE_EXISTING_MARKER)))
+    }
+
+    /// Name of the helper function window blocks call
+    pub fn helper(&self) -> &str {
+        &self.helper
+    }
+
+    /// Whether the script defines its helper function
+    pub fn has_helper(&self) -> bool {
+        self.blocks.iter().any(|b| matches!(b, Block::Helper(_)))
+    }
+
+    /// Insert the standard helper before the first window, or at the end of the script.
+    pub fn insert_helper(&mut self, instance: Option<&str>) {
+        let helper = Block::Helper(helper_text(&self.helper, instance));
+        let blank = Block::Line(String::new());
+
+        match self
+            .blocks
+            .iter()
+            .position(|b| matches!(b, Block::Window { .. }))
+        {
+            Some(pos) => {
+                self.blocks.splice(pos..pos, [helper, blank]);
+            }
+            None => {
+                self.blocks.extend([blank, helper]);
+                self.trailing_newline = true;
+            }
+        }
+    }
+
+    /// The windows of the script, in order, each carrying the `sleep`, layout hint and focus
+    /// marker that precede it, and the `windowrulev2` rules set up before it that target it
+    pub fn windows(&self) -> Vec<WindowSpec> {
+        let mut windows = Vec::new();
+        let mut delay = None;
+        let mut layout_hint = None;
+        let mut focus = false;
+        let mut rules: Vec<&WindowRule> = Vec::new();
+
+        for block in &self.blocks {
+            match block {
+                Block::Delay { seconds, .. } => delay = Some(seconds.clone()),
+                Block::Layout { message, .. } => layout_hint = Some(message.clone()),
+                Block::Focus(_) => focus = true,
+                Block::Rule { rule, .. } => rules.push(rule),
+                Block::Window { spec, .. } => {
+                    let mut spec = spec.clone();
+                    let extra: Vec<&str> = rules
+                        .iter()
+                        .filter(|rule| rule.applies_to(&spec))
+                        .map(|rule| rule.rule.as_str())
+                        .collect();
+                    if !extra.is_empty() {
+                        spec.rules = [spec.rules.as_str()]
+                            .into_iter()
+                            .chain(extra)
+                            .filter(|rules| !rules.is_empty())
+                            .collect::<Vec<_>>()
+                            .join("; ");
+                        spec.unit = Unit::of_rules(&spec.rules);
+                    }
+                    spec.delay = delay.take();
+                    spec.layout_hint = layout_hint.take();
+                    spec.focus = std::mem::take(&mut focus);
+                    windows.push(spec);
+                }
+                _ => {}
+            }
+        }
+
+        windows
+    }
+
+    /// Position in `blocks` of the `idx`-th window
+    fn window_block(&self, idx: usize) -> Option<usize> {
+        self.blocks
+            .iter()
+            .enumerate()
+            .filter(|(_, b)| matches!(b, Block::Window { .. }))
+            .nth(idx)
+            .map(|(pos, _)| pos)
+    }
+
+    /// Replace the `idx`-th window, regenerating only its block. Returns false if out of range.
+    ///
+    /// The window's `delay` and `layout_hint` are ignored: they are separate blocks.
+    pub fn set_window(&mut self, idx: usize, spec: WindowSpec) -> bool {
+        let Some(pos) = self.window_block(idx) else {
+            return false;
+        };
+        self.blocks[pos] = Block::Window {
+            raw: window_text(&self.helper, &spec),
+            spec,
+        };
+        true
+    }
+
+    /// Remove the `idx`-th window and return it.
+    pub fn remove_window(&mut self, idx: usize) -> Option<WindowSpec> {
+        let pos = self.window_block(idx)?;
+        match self.blocks.remove(pos) {
+            Block::Window { spec, .. } => Some(spec),
+            _ => None,
+        }
+    }
+
+    /// The `hyprctl` invocation of the script's workspace dispatch, with its `--instance` if any
+    fn hyprctl_prefix(&self) -> String {
+        self.blocks
+            .iter()
+            .find_map(|b| match b {
+                Block::Line(line) if line.starts_with("hyprctl") => line
+                    .split_once(" dispatch workspace ")
+                    .or_else(|| line.split_once(" dispatch togglespecialworkspace "))
+                    .map(|(prefix, _)| prefix.to_string()),
+                _ => None,
+            })
+            .unwrap_or_else(|| "hyprctl".to_string())
+    }
+
+    /// Point the script at workspace `new` instead of `old`: the `dispatch workspace` line,
+    /// the close-existing preamble and every `workspace old` rule of its windows and
+    /// `windowrulev2` lines. Everything else is left as written.
+    pub fn retarget_workspace(&mut self, old: u32, new: u32) {
+        let hyprctl = self.hyprctl_prefix();
+        let dispatch = format!("{hyprctl} dispatch workspace ");
+        let old_close = close_existing_text(&hyprctl, old);
+        let new_close = close_existing_text(&hyprctl, new);
+        let closes_existing = self.closes_existing();
+
+        for block in &mut self.blocks {
+            match block {
+                Block::Line(line) => {
+                    if line
+                        .strip_prefix(&dispatch)
+                        .is_some_and(|num| num.trim() == old.to_string())
+                    {
+                        *line = format!("{dispatch}{new}");
+                    } else if closes_existing
+                        && let Some(pos) = old_close.lines().position(|l| l == line)
+                    {
+                        *line = new_close.lines().nth(pos).unwrap_or_default().to_string();
+                    }
+                }
+                Block::Window { raw, spec } => {
+                    let rules = retarget_rules(&spec.rules, old, new);
+                    if rules != spec.rules {
+                        *raw = raw.replacen(&spec.rules, &rules, 1);
+                        spec.rules = rules;
+                    }
+                }
+                Block::Rule { raw, rule } => {
+                    let text = retarget_rules(&rule.rule, old, new);
+                    if text != rule.rule {
+                        *raw = raw.replacen(&rule.rule, &text, 1);
+                        rule.rule = text;
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Express the `size` and `move` values of every window, and of the `windowrulev2`
+    /// rules, in `to` for a `width`×`height` monitor (see [`convert_rules`]).
+    ///
+    /// Returns how many windows and rules changed.
+    pub fn convert_units(&mut self, to: Unit, width: u32, height: u32) -> usize {
+        let mut changed = 0;
+        for block in &mut self.blocks {
+            match block {
+                Block::Window { raw, spec } => {
+                    let rules = convert_rules(&spec.rules, to, width, height);
+                    if rules != spec.rules {
+                        *raw = raw.replacen(&spec.rules, &rules, 1);
+                        spec.rules = rules;
+                        spec.unit = Unit::of_rules(&spec.rules);
+                        changed += 1;
+                    }
+                }
+                Block::Rule { raw, rule } => {
+                    let text = convert_rules(&rule.rule, to, width, height);
+                    if text != rule.rule {
+                        *raw = raw.replacen(&rule.rule, &text, 1);
+                        rule.rule = text;
+                        changed += 1;
+                    }
+                }
+                _ => {}
+            }
+        }
+        changed
+    }
+
+    /// The `--instance` signature the script's workspace dispatch targets, if any
+    pub fn instance(&self) -> Option<String> {
+        let prefix = self.hyprctl_prefix();
+        let sig = prefix.strip_prefix("hyprctl --instance ")?.trim();
+        (!sig.is_empty()).then(|| sig.to_string())
+    }
+
+    /// What a script regenerated from its [`ScriptSpec`] would lose: the first line that
+    /// doesn't come from the generator (custom commands, comments, a modified helper).
+    /// `None` when the script can be regenerated as is.
+    pub fn hand_edited_content(&self) -> Option<String> {
+        let hyprctl = self.hyprctl_prefix();
+        let dispatch = format!("{hyprctl} dispatch workspace ");
+        let toggle = format!("{hyprctl} dispatch togglespecialworkspace ");
+        let workspace_num = self.blocks.iter().find_map(|b| match b {
+            Block::Line(line) => line.strip_prefix(&dispatch)?.trim().parse::<u32>().ok(),
+            _ => None,
+        });
+        let special = self.blocks.iter().find_map(|b| match b {
+            Block::Line(line) => line.strip_prefix(&toggle).map(str::trim),
+            _ => None,
+        });
+        let close_existing = match (workspace_num, special) {
+            _ if !self.closes_existing() => String::new(),
+            (Some(num), _) => close_existing_text(&hyprctl, num),
+            (None, Some(name)) => close_existing_special_text(&hyprctl, name),
+            (None, None) => String::new(),
+        };
+        let standard_helper = helper_text(&self.helper, self.instance().as_deref());
+
+        let mut headers = HashSet::new();
+        let mut dispatched = false;
+        for block in &self.blocks {
+            match block {
+                Block::Shebang(line) if line != "#!/bin/bash" => {
+                    return Some(format!("interpreter line `{line}`"));
+                }
+                Block::Helper(text) if *text != standard_helper => {
+                    return Some(format!("customized {} definition", self.helper));
+                }
+                // Its rules are folded into the windows it targets, but it also applies to
+                // any other matching window
+                Block::Rule { raw, .. } => return Some(format!("line `{}`", raw.trim())),
+                Block::Line(line) => {
+                    let trimmed = line.trim();
+                    if trimmed.is_empty() || close_existing.lines().any(|l| l == line) {
+                        continue;
+                    }
+                    if (line.starts_with(&dispatch) || line.starts_with(&toggle)) && !dispatched {
+                        dispatched = true;
+                        continue;
+                    }
+                    let header = ["hyprspace-order:", "hyprspace-icon:", "hyprspace:"]
+                        .into_iter()
+                        .find(|key| {
+                            trimmed
+                                .strip_prefix('#')
+                                .is_some_and(|c| c.trim_start().starts_with(key))
+                        });
+                    // A repeated header is ignored when reading, so it would be dropped
+                    if let Some(key) = header
+                        && headers.insert(key)
+                    {
+                        continue;
+                    }
+                    return Some(format!("line `{trimmed}`"));
+                }
+                _ => {}
+            }
+        }
+        None
+    }
+
+    /// Append a window (and its delay and layout hint) after the last existing one,
+    /// or at the end of the script.
+    pub fn push_window(&mut self, spec: WindowSpec) {
+        let mut new_blocks = vec![Block::Line(String::new())];
+        if let Some(seconds) = &spec.delay {
+            new_blocks.push(Block::Delay {
+                raw: format!("sleep {seconds}"),
+                seconds: seconds.clone(),
+            });
+            new_blocks.push(Block::Line(String::new()));
+        }
+        if let Some(message) = &spec.layout_hint {
+            new_blocks.push(Block::Layout {
+                raw: layout_text(&self.hyprctl_prefix(), message),
+                message: message.clone(),
+            });
+        }
+        if spec.focus {
+            new_blocks.push(Block::Focus(FOCUS_MARKER.to_string()));
+        }
+        new_blocks.push(Block::Window {
+            raw: window_text(&self.helper, &spec),
+            spec,
+        });
+
+        let last_window = self
+            .blocks
+            .iter()
+            .rposition(|b| matches!(b, Block::Window { .. }));
+
+        match last_window {
+            Some(pos) => {
+                self.blocks.splice(pos + 1..pos + 1, new_blocks);
+            }
+            None => {
+                self.blocks.extend(new_blocks);
+                self.trailing_newline = true;
+            }
+        }
+    }
+}
+
+/// Name of the helper function generated scripts use unless configured otherwise
+pub const DEFAULT_HELPER: &str = "rule_exec";
+
+/// Default rules of a generated window, see [`fill_template`]
+pub const DEFAULT_RULES_TEMPLATE: &str = "{workspace}; float; size {width} {height}; move {x} {y}";
+
+/// Placeholders a rules template may use
+pub const RULES_PLACEHOLDERS: &[&str] = &["workspace", "width", "height", "x", "y", "command"];
+
+/// Replace every `{name}` of `template` with its value; unknown names are left as written.
+pub fn fill_template(template: &str, values: &[(&str, &str)]) -> String {
+    let mut out = template.to_string();
+    for (name, value) in values {
+        out = out.replace(&format!("{{{name}}}"), value);
+    }
+    out
+}
+
+/// Names of the `{{name}}` launch-time placeholders of `command`, in order of first use.
+///
+/// A name is made of letters, digits, `_` and `-`; anything else between double braces
+/// is left alone.
+pub fn launch_placeholders(command: &str) -> Vec<&str> {
+    let mut names: Vec<&str> = Vec::new();
+    for part in command.split("{{").skip(1) {
+        let Some((name, _)) = part.split_once("}}") else {
+            continue;
+        };
+        let valid = !name.is_empty()
+            && name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+        if valid && !names.contains(&name) {
+            names.push(name);
+        }
+    }
+    names
+}
+
+/// Replace every `{{name}}` of `command` with its value, as typed.
+pub fn fill_launch_placeholders(command: &str, values: &[(String, String)]) -> String {
+    let mut out = command.to_string();
+    for (name, value) in values {
+        out = out.replace(&format!("{{{{{name}}}}}"), value);
+    }
+    out
+}
+
+/// Whether `name` can be used as a shell function name
+pub fn is_valid_helper_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Name of the function a line starts defining (`name() {` or `function name`), if any
+fn function_name(line: &str) -> Option<&str> {
+    let line = line.trim_start();
+    let name = match line.strip_prefix("function ") {
+        Some(rest) => rest
+            .trim_start()
+            .split(|c: char| c == '(' || c.is_whitespace())
+            .next()?,
+        None => line.split_once("()")?.0.trim_end(),
+    };
+    is_valid_helper_name(name).then_some(name)
+}
+
+/// Name of the first function in `content` whose body runs `dispatch exec`
+fn detect_helper(content: &str) -> Option<String> {
+    let mut lines = content.lines();
+    while let Some(line) = lines.next() {
+        let Some(name) = function_name(line) else {
+            continue;
+        };
+        let mut body = line.to_string();
+        // One-line definitions end on their first line
+        let mut closed = line.trim_end().ends_with('}');
+        while !closed {
+            let Some(next) = lines.next() else { break };
+            body.push_str(next);
+            closed = is_closing_brace(next);
+        }
+        if body.contains("dispatch exec") {
+            return Some(name.to_string());
+        }
+    }
+    None
+}
+
+/// Whether `line` opens the definition of the `helper` function
+/// Whether `line` closes a function body: a `}`, possibly followed by a comment.
+fn is_closing_brace(line: &str) -> bool {
+    line.trim()
+        .strip_prefix('}')
+        .is_some_and(|rest| rest.trim().is_empty() || rest.trim_start().starts_with('#'))
+}
+
+fn is_helper_start(line: &str, helper: &str) -> bool {
+    function_name(line) == Some(helper)
+}
+
+/// The standard helper definition (without a trailing newline)
+fn helper_text(helper: &str, instance: Option<&str>) -> String {
+    let hyprctl = match instance {
+        Some(sig) => format!("hyprctl --instance {sig}"),
+        None => "hyprctl".to_string(),
+    };
+
+    format!(
+        r#"{helper}() {{
+  local rules="$1"
+  shift
+  {hyprctl} dispatch exec "[$rules] $*"
+}}"#
+    )
+}
+
+/// Comment marking the window that follows as the one focused after launch
+pub const FOCUS_MARKER: &str = "# hyprspace-focus";
+
+/// First line of the generated close-existing preamble, used to recognize it
+pub const CLOSE_EXISTING_MARKER: &str = "# hyprspace-close-existing";
+
+/// Preamble closing every window already on `workspace_num` (read from `hyprctl clients`).
+fn close_existing_text(hyprctl: &str, workspace_num: u32) -> String {
+    format!(
+        r#"{CLOSE_EXISTING_MARKER}: start from an empty workspace {workspace_num} on every run
+for address in $({hyprctl} clients | awk '/^Window /{{addr=$2}} $1 == "workspace:" && $2 == {workspace_num} {{print addr}}'); do
+  {hyprctl} dispatch closewindow "address:0x$address" > /dev/null
+done"#
+    )
+}
+
+/// Preamble closing every window already on the special workspace `name`.
+fn close_existing_special_text(hyprctl: &str, name: &str) -> String {
+    format!(
+        r#"{CLOSE_EXISTING_MARKER}: start from an empty special:{name} on every run
+for address in $({hyprctl} clients | awk '/^Window /{{addr=$2}} $1 == "workspace:" && $3 == "(special:{name})" {{print addr}}'); do
+  {hyprctl} dispatch closewindow "address:0x$address" > /dev/null
+done"#
+    )
+}
+
+/// Generated text for a window block calling `helper`
+fn window_text(helper: &str, window: &WindowSpec) -> String {
+    // `rule_exec` joins its arguments with `$*`, so the `cd` goes in one double-quoted word
+    // to reach Hyprland's `sh -c` with its quoting intact
+    let cmd = match &window.cwd {
+        Some(dir) => format!(
+            "\"cd {} &&\" {}",
+            escape_double_quoted(&sh_quote_dir(dir)),
+            window.command
+        ),
+        None => window.command.clone(),
+    };
+    format!("{helper} \"{rules}\" \\\n  {cmd}", rules = window.rules)
+}
+
+/// `dir` quoted for `sh`, leaving a leading `~/` outside the quotes so it still expands.
+fn sh_quote_dir(dir: &str) -> String {
+    let (home, rest) = match dir.strip_prefix("~/") {
+        Some(rest) => ("~/", rest),
+        None if dir == "~" => return dir.to_string(),
+        None => ("", dir),
+    };
+    format!("{home}'{}'", rest.replace('\'', r"'\''"))
+}
+
+/// `text` escaped to sit inside a bash double-quoted string.
+fn escape_double_quoted(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        if matches!(c, '\\' | '"' | '$' | '`') {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+    out
+}
+
+/// Read the shell word `text` starts with, resolving its quotes, and return it with the rest.
+///
+/// Fails on an unterminated quote.
+fn take_shell_word(text: &str) -> Option<(String, &str)> {
+    let mut word = String::new();
+    let mut chars = text.char_indices();
+
+    while let Some((idx, c)) = chars.next() {
+        match c {
+            c if c.is_whitespace() => return Some((word, &text[idx..])),
+            '\'' => loop {
+                match chars.next()? {
+                    (_, '\'') => break,
+                    (_, c) => word.push(c),
+                }
+            },
+            '"' => loop {
+                match chars.next()? {
+                    (_, '"') => break,
+                    (_, '\\') => {
+                        let (_, next) = chars.next()?;
+                        if !matches!(next, '\\' | '"' | '$' | '`') {
+                            word.push('\\');
+                        }
+                        word.push(next);
+                    }
+                    (_, c) => word.push(c),
+                }
+            },
+            '\\' => word.push(chars.next()?.1),
+            c => word.push(c),
+        }
+    }
+
+    Some((word, ""))
+}
+
+/// Split a leading `cd <dir> &&` off a `sh` command line.
+fn split_sh_cwd(command: &str) -> Option<(String, String)> {
+    let rest = command.strip_prefix("cd ")?.trim_start();
+    let (dir, rest) = take_shell_word(rest)?;
+    let command = rest.trim_start().strip_prefix("&&")?.trim();
+    (!dir.is_empty() && !command.is_empty()).then(|| (dir, command.to_string()))
+}
+
+/// Split the `"cd <dir> &&"` word written by [`window_text`] off the arguments of a helper call.
+fn split_helper_cwd(command: &str) -> Option<(String, String)> {
+    if !command.starts_with("\"cd ") {
+        return None;
+    }
+    let (inner, rest) = take_shell_word(command)?;
+    split_sh_cwd(&format!("{inner} {}", rest.trim()))
+}
+
+/// Generated `layoutmsg` line sending `message` through `hyprctl`
+fn layout_text(hyprctl: &str, message: &str) -> String {
+    format!("{hyprctl} dispatch layoutmsg {message}")
+}
+
+/// Parse a `hyprctl [flags] dispatch layoutmsg <message>` line into its message.
+fn parse_layoutmsg(line: &str) -> Option<String> {
+    let message = strip_dispatcher(line, "layoutmsg")?.trim();
+    (!message.is_empty()).then(|| message.to_string())
+}
+
+/// Parse a `sleep <seconds>` line into its argument.
+fn parse_sleep(line: &str) -> Option<String> {
+    let mut words = line.split_whitespace();
+    if words.next()? != "sleep" {
+        return None;
+    }
+    let seconds = words.next()?;
+    words.next().is_none().then(|| seconds.to_string())
+}
+
+/// Whether `value` is a valid `sleep` duration such as `1`, `0.5` or `2s`
+pub fn is_valid_delay(value: &str) -> bool {
+    delay_duration(value).is_some()
+}
+
+/// The duration of a `sleep` argument such as `1`, `0.5` or `2s`
+pub fn delay_duration(value: &str) -> Option<Duration> {
+    let number = value.strip_suffix('s').unwrap_or(value);
+    Duration::try_from_secs_f64(number.parse().ok()?).ok()
+}
+
+/// Parse a `<helper> "<rules>" <command>` line, e.g. `rule_exec "float" kitty`.
+fn parse_rule_exec(line: &str, helper: &str) -> Option<WindowSpec> {
+    let rest = line.strip_prefix(helper)?;
+
+    // Skip the helper definition itself (`rule_exec() {`)
+    if !rest.starts_with(char::is_whitespace) {
+        return None;
+    }
+
+    let rest = rest.trim_start();
+    let quote = rest.chars().next().filter(|c| *c == '"' || *c == '\'')?;
+    let rest = &rest[1..];
+    let end = rest.find(quote)?;
+
+    let rules = rest[..end].trim();
+    let command = rest[end + 1..].trim();
+    let (cwd, command) = match split_helper_cwd(command) {
+        Some((dir, command)) => (Some(dir), command),
+        None => (None, command.to_string()),
+    };
+    Some(WindowSpec {
+        unit: Unit::of_rules(rules),
+        rules: rules.to_string(),
+        command,
+        cwd,
+        ..Default::default()
+    })
+}
+
+/// Why `line`, which looks like it spawns a window, was not recognized as one.
+pub fn window_parse_problem(line: &str, helper: &str) -> Option<String> {
+    if let Some(rest) = line.strip_prefix(helper)
+        && rest.starts_with(char::is_whitespace)
+    {
+        return Some(match rest.trim_start().chars().next() {
+            Some('"' | '\'') => "the quote around the rules is never closed".to_string(),
+            _ => format!(
+                "the rules must come quoted right after `{helper}`, e.g. `{helper} \"float\" kitty`"
+            ),
+        });
+    }
+    line.contains("dispatch exec").then(|| {
+        "only `hyprctl [flags] dispatch exec \"[rules] command\"` is recognized, with a closed `[...]`".to_string()
+    })
+}
+
+/// Substitute `$VAR` and `${VAR}` with values from the current environment.
+///
+/// Purely informational: variables that are not set are left as written, and shell
+/// quoting rules are not applied.
+pub fn expand_env_vars(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(pos) = rest.find('$') {
+        out.push_str(&rest[..pos]);
+        let after = &rest[pos + 1..];
+
+        let (name, written_len) = match after.strip_prefix('{') {
+            Some(braced) => match braced.find('}') {
+                Some(end) => (&braced[..end], end + 2),
+                None => ("", 0),
+            },
+            None => {
+                let end = after
+                    .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+                    .unwrap_or(after.len());
+                (&after[..end], end)
+            }
+        };
+
+        let is_name = name.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_');
+        match env::var(name) {
+            Ok(value) if is_name => out.push_str(&value),
+            _ => out.push_str(&rest[pos..pos + 1 + written_len]),
+        }
+        rest = &after[written_len..];
+    }
+    out.push_str(rest);
+
+    out
+}
+
+/// Split `[rules] command` into its rules (empty when absent) and command.
+fn split_exec_target(target: &str) -> Option<WindowSpec> {
+    let target = target.trim();
+    let (rules, command) = match target.strip_prefix('[') {
+        Some(rest) => {
+            let end = rest.find(']')?;
+            (rest[..end].trim(), rest[end + 1..].trim())
+        }
+        None => ("", target),
+    };
+
+    let (cwd, command) = match split_sh_cwd(command) {
+        Some((dir, command)) => (Some(dir), command),
+        None => (None, command.to_string()),
+    };
+    (!command.is_empty()).then(|| WindowSpec {
+        rules: rules.to_string(),
+        command,
+        cwd,
+        unit: Unit::of_rules(rules),
+        ..Default::default()
+    })
+}
+
+/// What follows `hyprctl [flags] dispatch <dispatcher>` on `line`, if it calls that dispatcher.
+fn strip_dispatcher<'a>(line: &'a str, dispatcher: &str) -> Option<&'a str> {
+    strip_hyprctl(line, ["dispatch", dispatcher])
+}
+
+/// What follows `hyprctl [flags] <command> <name>` on `line`, if it runs that command.
+fn strip_hyprctl<'a>(line: &'a str, words: [&str; 2]) -> Option<&'a str> {
+    let mut rest = line.trim().strip_prefix("hyprctl")?;
+    let mut expected = words.into_iter().peekable();
+
+    while let Some(&word_expected) = expected.peek() {
+        rest = rest.trim_start();
+        let end = rest.find(char::is_whitespace).unwrap_or(rest.len());
+        let word = &rest[..end];
+        rest = &rest[end..];
+
+        if word == "--instance" || word == "-i" {
+            // Skip the flag's value too
+            rest = rest.trim_start();
+            rest = &rest[rest.find(char::is_whitespace).unwrap_or(rest.len())..];
+        } else if !word.starts_with('-') {
+            if word != word_expected {
+                return None;
+            }
+            expected.next();
+        }
+    }
+
+    Some(rest)
+}
+
+/// Parse a `hyprctl [flags] dispatch exec "[rules] command"` line, quoted or not.
+fn parse_dispatch_exec(line: &str) -> Option<WindowSpec> {
+    let target = strip_dispatcher(line, "exec")?.trim();
+    let unquoted = ['"', '\'']
+        .into_iter()
+        .find_map(|q| target.strip_prefix(q)?.strip_suffix(q))
+        .unwrap_or(target);
+    split_exec_target(unquoted)
+}
+
+/// Parse a `hyprctl [flags] keyword windowrulev2 "<rule>, <matchers>"` line, or its
+/// `windowrule "<rule>, <class regex>"` form.
+fn parse_window_rule(line: &str) -> Option<WindowRule> {
+    let (value, v2) = match strip_hyprctl(line, ["keyword", "windowrulev2"]) {
+        Some(value) => (value, true),
+        None => (strip_hyprctl(line, ["keyword", "windowrule"])?, false),
+    };
+    let value = value.trim();
+    let value = ['"', '\'']
+        .into_iter()
+        .find_map(|q| value.strip_prefix(q)?.strip_suffix(q))
+        .unwrap_or(value);
+    let (rule, matchers) = value.split_once(',')?;
+    let rule = rule.trim();
+    if rule.is_empty() {
+        return None;
+    }
+
+    let mut class = None;
+    let mut other_matchers = false;
+    for matcher in matchers.split(',').map(str::trim).filter(|m| !m.is_empty()) {
+        match matcher.split_once(':') {
+            Some(("class" | "initialClass", pattern)) if v2 => {
+                class = Some(pattern.trim().to_string())
+            }
+            Some((key, _)) if v2 || key == "title" => other_matchers = true,
+            // windowrule takes a bare class regex
+            _ if !v2 => class = Some(matcher.to_string()),
+            _ => other_matchers = true,
+        }
+    }
+
+    Some(WindowRule {
+        rule: rule.to_string(),
+        class,
+        other_matchers,
+    })
+}
+
+/// Parse a Hyprland config `exec-once = [rules] command` (or `exec = ...`) line.
+fn parse_exec_keyword(line: &str) -> Option<WindowSpec> {
+    let (key, value) = line.split_once('=')?;
+    matches!(key.trim(), "exec" | "exec-once").then(|| split_exec_target(value))?
+}
+
+/// Extract windows from pasted text: `hyprctl dispatch exec` calls, `rule_exec` calls
+/// or `exec-once =` config lines, one per line (`\` continuations are joined).
+pub fn parse_pasted_windows(text: &str) -> Vec<WindowSpec> {
+    let mut windows = Vec::new();
+    let mut lines = text.lines();
+
+    while let Some(line) = lines.next() {
+        let mut logical = line.trim().to_string();
+        while let Some(head) = logical.strip_suffix('\\') {
+            let Some(next) = lines.next() else { break };
+            logical = format!("{} {}", head.trim_end(), next.trim());
+        }
+
+        let window = parse_rule_exec(&logical, DEFAULT_HELPER)
+            .or_else(|| parse_dispatch_exec(&logical))
+            .or_else(|| parse_exec_keyword(&logical));
+        windows.extend(window);
+    }
+
+    windows
+}
+
+/// Extract every window the script spawns, through its helper (`rule_exec` by default)
+/// or `hyprctl dispatch exec`.
+pub fn parse_windows(content: &str) -> Vec<WindowSpec> {
+    ScriptDocument::parse(content).windows()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HELPER: &str =
+        "rule_exec() {\n  local rules=\"$1\"\n  shift\n  hyprctl dispatch exec \"[$rules] $*\"\n}";
+
+    /// Parse `content`, check it renders back byte for byte and return its windows' commands.
+    fn round_trip(content: &str) -> Vec<String> {
+        let doc = ScriptDocument::parse(content);
+        assert_eq!(doc.render(), content);
+        doc.windows()
+            .into_iter()
+            .map(|window| window.command)
+            .collect()
+    }
+
+    #[test]
+    fn documents_render_back_unchanged() {
+        let script = format!(
+            "#!/bin/bash\nhyprctl dispatch workspace 2\n\n{HELPER}\n\nrule_exec \"workspace 2\" \\\n  kitty\n"
+        );
+        assert_eq!(round_trip(&script), ["kitty"]);
+
+        // CRLF line endings, including inside a continued window
+        assert_eq!(round_trip(&script.replace('\n', "\r\n")), ["kitty"]);
+
+        // Without a trailing newline
+        assert_eq!(round_trip(script.trim_end()), ["kitty"]);
+        assert_eq!(round_trip(""), Vec::<String>::new());
+        assert_eq!(round_trip("\n\n"), Vec::<String>::new());
+    }
+
+    #[test]
+    fn comments_ending_in_a_backslash_stand_alone() {
+        let script = format!("{HELPER}\n# windows below \\\nrule_exec \"float\" kitty\n");
+        assert_eq!(round_trip(&script), ["kitty"]);
+    }
+
+    #[test]
+    fn helper_ends_at_a_brace_followed_by_a_comment() {
+        let helper = HELPER.replace("\n}", "\n}  # end of helper");
+        let script = format!("{helper}\nrule_exec \"float\" kitty\nrule_exec \"float\" firefox\n");
+        assert_eq!(round_trip(&script), ["kitty", "firefox"]);
+        assert_eq!(ScriptDocument::parse(&script).helper(), "rule_exec");
+    }
+
+    #[test]
+    fn unit_conversion_keeps_expressions_and_round_trips() {
+        let rules = "workspace 2 silent; float; size 50% 25%; move onscreen 100%-w 10%";
+        let pixels = convert_rules(rules, Unit::Pixels, 1920, 1080);
+        assert_eq!(
+            pixels,
+            "workspace 2 silent; float; size 960 270; move onscreen 100%-w 108"
+        );
+        assert_eq!(Unit::of_rules(&pixels), Some(Unit::Pixels));
+        assert_eq!(convert_rules(&pixels, Unit::Percent, 1920, 1080), rules);
+
+        // Cursor-relative moves are offsets within the window, not monitor positions
+        let rules = "float; size 50% 50%; move cursor -50% -50%";
+        assert_eq!(
+            convert_rules(rules, Unit::Pixels, 1920, 1080),
+            "float; size 960 540; move cursor -50% -50%"
+        );
+        assert_eq!(Unit::of_rules("float; move onscreen cursor 10 10"), None);
+    }
+
+    #[test]
+    fn launch_placeholders_are_listed_once_and_filled() {
+        assert_eq!(
+            launch_placeholders("nvim {{file}} {{ x }} {{file}} {{dir}}"),
+            ["file", "dir"]
+        );
+        assert!(launch_placeholders("echo {{}} {{a b}}").is_empty());
+
+        let values = [("project".to_string(), "hyprspace".to_string())];
+        assert_eq!(
+            fill_launch_placeholders("cd {{project}} && nvim", &values),
+            "cd hyprspace && nvim"
+        );
+        assert_eq!(
+            fill_launch_placeholders("nvim {{file}}", &values),
+            "nvim {{file}}"
+        );
+    }
+
+    #[test]
+    fn windowrulev2_rules_follow_the_windows_they_target() {
+        let windows = parse_windows(
+            "#!/bin/bash\nhyprctl dispatch workspace 2\nhyprctl keyword windowrulev2 \"float, class:^(kitty)$\"\nhyprctl keyword windowrulev2 \"size 50% 50%, class:^(kitty)$, title:^(logs)$\"\nhyprctl dispatch exec kitty\nhyprctl dispatch exec firefox\n",
+        );
+        let rules: Vec<&str> = windows.iter().map(|window| window.rules.as_str()).collect();
+        assert_eq!(rules, ["float", ""]);
+
+        let rule =
+            parse_window_rule("hyprctl -i 0 keyword windowrule 'float, firefox|chromium'").unwrap();
+        assert_eq!(rule.class.as_deref(), Some("firefox|chromium"));
+        assert!(!rule.other_matchers);
+        assert!(rule.applies_to(&parse_windows("hyprctl dispatch exec chromium --incognito\n")[0]));
+        assert!(!rule.applies_to(&parse_windows("hyprctl dispatch exec kitty\n")[0]));
+
+        let rule = parse_window_rule(
+            "hyprctl keyword windowrulev2 \"opacity 0.9, initialClass:^(notes)$\"",
+        )
+        .unwrap();
+        assert!(rule.applies_to(&parse_windows("hyprctl dispatch exec kitty --class notes\n")[0]));
+        assert_eq!(
+            parse_window_rule("hyprctl keyword windowrulev2 \", class:^(kitty)$\""),
+            None
+        );
+        assert_eq!(parse_window_rule("hyprctl keyword general:gaps_in 5"), None);
+    }
+
+    #[test]
+    fn window_details_survive_generation_and_parsing() {
+        let window = WindowSpec {
+            rules: "workspace 2".to_string(),
+            command: "nvim .".to_string(),
+            cwd: Some("~/my code/it's $HOME".to_string()),
+            focus: true,
+            ..Default::default()
+        };
+        let content = build_script_content(&ScriptSpec {
+            windows: vec![window.clone()],
+            ..ScriptSpec::new(Workspace::Numbered(2))
+        });
+
+        let windows = parse_windows(&content);
+        assert_eq!(windows, [window]);
+        assert_eq!(
+            windows[0].exec_command(),
+            r"cd ~/'my code/it'\''s $HOME' && nvim ."
+        );
+    }
+
+    #[test]
+    fn special_workspace_scripts_toggle_and_regenerate() {
+        let window = WindowSpec {
+            rules: "workspace special:magic silent; float".to_string(),
+            command: "kitty".to_string(),
+            ..Default::default()
+        };
+        let content = build_script_content(&ScriptSpec {
+            close_existing: true,
+            windows: vec![window.clone()],
+            ..ScriptSpec::new(Workspace::Special("magic".to_string()))
+        });
+
+        assert!(content.contains("\nhyprctl dispatch togglespecialworkspace magic\n"));
+        assert!(!content.contains("dispatch workspace"));
+        assert!(content.contains(r#"$3 == "(special:magic)""#));
+        let doc = ScriptDocument::parse(&content);
+        assert_eq!(doc.hand_edited_content(), None);
+        assert_eq!(doc.windows(), [window]);
+    }
+}
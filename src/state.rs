@@ -0,0 +1,115 @@
+use std::collections::BTreeSet;
+use std::env;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Returns the directory of the state kept between runs: `$XDG_STATE_HOME/hyprspace`,
+/// else `~/.local/state/hyprspace`. An empty variable counts as unset.
+pub fn state_dir() -> PathBuf {
+    if let Some(state_home) = env::var_os("XDG_STATE_HOME").filter(|v| !v.is_empty()) {
+        return PathBuf::from(state_home).join("hyprspace");
+    }
+    let home = env::var("HOME").expect("HOME environment variable not set");
+    PathBuf::from(home)
+        .join(".local")
+        .join("state")
+        .join("hyprspace")
+}
+
+/// Write `content` to `path`, creating the state directory first.
+fn write_state(path: &Path, content: String) -> io::Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, content)
+}
+
+/// Returns the path to the pinned-workspaces list (~/.local/state/hyprspace/pinned)
+pub fn pinned_path() -> PathBuf {
+    state_dir().join("pinned")
+}
+
+/// Short names of the pinned workspaces (empty if none were pinned yet)
+pub fn load_pinned() -> BTreeSet<String> {
+    fs::read_to_string(pinned_path())
+        .map(|content| {
+            content
+                .lines()
+                .map(str::trim)
+                .filter(|line| !line.is_empty() && !line.starts_with('#'))
+                .map(str::to_string)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Persist the pinned short names, one per line.
+pub fn save_pinned(pinned: &BTreeSet<String>) -> io::Result<()> {
+    let mut content = String::new();
+    for name in pinned {
+        content.push_str(name);
+        content.push('\n');
+    }
+    write_state(&pinned_path(), content)
+}
+
+/// TUI preferences remembered across runs
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct UiPrefs {
+    /// Dense rows (`v`)
+    pub compact: bool,
+    /// Filter query left in place (`/`, then `Enter`)
+    pub filter: String,
+}
+
+/// Returns the path to the TUI preferences (~/.local/state/hyprspace/ui)
+pub fn ui_prefs_path() -> PathBuf {
+    state_dir().join("ui")
+}
+
+/// The remembered TUI preferences, defaults for anything missing or unreadable
+pub fn load_ui_prefs() -> UiPrefs {
+    let mut prefs = UiPrefs::default();
+    let Ok(content) = fs::read_to_string(ui_prefs_path()) else {
+        return prefs;
+    };
+
+    for (key, value) in content.lines().filter_map(|line| line.split_once('=')) {
+        match key.trim() {
+            "compact" => prefs.compact = value.trim() == "true",
+            // Kept verbatim: the query may start or end with spaces
+            "filter" => prefs.filter = value.to_string(),
+            _ => {}
+        }
+    }
+    prefs
+}
+
+/// Persist the TUI preferences as `key=value` lines.
+pub fn save_ui_prefs(prefs: &UiPrefs) -> io::Result<()> {
+    write_state(
+        &ui_prefs_path(),
+        format!("compact={}\nfilter={}\n", prefs.compact, prefs.filter),
+    )
+}
+
+/// Returns the path to the last-launched record (~/.local/state/hyprspace/last)
+pub fn last_launched_path() -> PathBuf {
+    state_dir().join("last")
+}
+
+/// Full path of the most recently launched script, if one was recorded
+pub fn load_last_launched() -> Option<PathBuf> {
+    let content = fs::read_to_string(last_launched_path()).ok()?;
+    let line = content.lines().next()?.trim();
+    (!line.is_empty()).then(|| PathBuf::from(line))
+}
+
+/// Remember `path` as the most recently launched script.
+pub fn save_last_launched(path: &Path) -> io::Result<()> {
+    write_state(
+        &last_launched_path(),
+        format!("{}\n", path.to_string_lossy()),
+    )
+}
@@ -0,0 +1,118 @@
+use std::env;
+use std::fs::OpenOptions;
+use std::io::{self, IsTerminal, Write};
+use std::process::{Child, Command, Stdio};
+
+use crossterm::cursor::Show;
+use crossterm::event::{DisableBracketedPaste, DisableMouseCapture, PopKeyboardEnhancementFlags};
+use crossterm::execute;
+use crossterm::style::ResetColor;
+use crossterm::terminal::LeaveAlternateScreen;
+
+/// Whether `$TERM` is unset, empty or `dumb`: no cursor movement nor colors
+fn is_dumb_term() -> bool {
+    env::var("TERM").map_or(true, |term| term.is_empty() || term == "dumb")
+}
+
+/// Whether the full-screen TUI can be drawn: stdin and stderr (where it draws) are
+/// terminals understanding escape sequences.
+pub fn supports_tui() -> bool {
+    io::stdin().is_terminal() && io::stderr().is_terminal() && !is_dumb_term()
+}
+
+/// Whether stdout output may be styled with ANSI escapes (not with `NO_COLOR` set).
+pub fn supports_color() -> bool {
+    io::stdout().is_terminal()
+        && !is_dumb_term()
+        && env::var_os("NO_COLOR").is_none_or(|v| v.is_empty())
+}
+
+/// The pager from `$PAGER` (`less` when unset), `None` when it is empty or `cat`.
+fn pager_command() -> Option<String> {
+    let pager = env::var("PAGER").unwrap_or_else(|_| "less".to_string());
+    let pager = pager.trim();
+    (!pager.is_empty() && pager != "cat").then(|| pager.to_string())
+}
+
+/// Start `pager` through the shell, reading from a pipe.
+fn spawn_pager(pager: &str) -> io::Result<Child> {
+    let mut command = Command::new("sh");
+    command.arg("-c").arg(pager).stdin(Stdio::piped());
+    if env::var_os("LESS").is_none() {
+        command.env("LESS", "FRX");
+    }
+    command.spawn()
+}
+
+/// Print `text` on stdout, through the pager when stdout is a terminal the text
+/// doesn't fit in, like git does.
+///
+/// `less` gets `LESS=FRX` unless set, so the text stays on screen once quit. A pager
+/// that fails to start falls back to printing.
+pub fn page(text: &str) -> io::Result<()> {
+    let rows = crossterm::terminal::size().map_or(usize::MAX, |(_, rows)| usize::from(rows));
+    let fits = text.lines().count() < rows;
+    let child = pager_command()
+        .filter(|_| io::stdout().is_terminal() && !is_dumb_term() && !fits)
+        .and_then(|pager| spawn_pager(&pager).ok());
+
+    let Some(mut child) = child else {
+        print!("{text}");
+        return io::stdout().flush();
+    };
+
+    if let Some(mut stdin) = child.stdin.take() {
+        // Quitting the pager early closes the pipe, which is not an error
+        match stdin.write_all(text.as_bytes()) {
+            Err(err) if err.kind() != io::ErrorKind::BrokenPipe => return Err(err),
+            _ => {}
+        }
+    }
+    child.wait()?;
+    Ok(())
+}
+
+/// Undo whatever a crashed TUI left behind (`hyprspace reset-term`): cooked mode back
+/// through `stty sane`, the alternate screen left, mouse reporting off, the cursor shown
+/// and colors reset.
+///
+/// Works on `/dev/tty`, so it helps even with stdout or stderr redirected.
+pub fn reset_terminal() -> io::Result<()> {
+    let mut tty = OpenOptions::new()
+        .read(true)
+        .write(true)
+        .open("/dev/tty")
+        .map_err(|err| {
+            io::Error::new(
+                err.kind(),
+                format!("cannot open the terminal (/dev/tty): {err}"),
+            )
+        })?;
+
+    // crossterm only undoes raw mode it enabled itself, in this very process
+    let status = Command::new("stty")
+        .arg("sane")
+        .stdin(tty.try_clone()?)
+        .status()
+        .map_err(|err| {
+            if err.kind() == io::ErrorKind::NotFound {
+                io::Error::new(err.kind(), "stty not found, run `reset` instead")
+            } else {
+                err
+            }
+        })?;
+    if !status.success() {
+        return Err(io::Error::other("stty sane failed, run `reset` instead"));
+    }
+
+    execute!(
+        tty,
+        PopKeyboardEnhancementFlags,
+        DisableMouseCapture,
+        DisableBracketedPaste,
+        LeaveAlternateScreen,
+        ResetColor,
+        Show
+    )?;
+    tty.flush()
+}
@@ -0,0 +1,215 @@
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+use crate::cli::usage_error;
+use crate::workspace::{confirm, is_workspace_file_name, move_file, trash_dir};
+
+/// A deleted script waiting in a trash folder
+#[derive(Debug)]
+pub struct TrashedScript {
+    /// Where the script sits in the trash
+    pub path: PathBuf,
+    /// File name it had before deletion (e.g. "workspace-backend.sh")
+    pub base_name: String,
+    /// Deletion time as written in the file name, `YYYYMMDD-HHMMSS` (UTC)
+    pub deleted_at: String,
+    /// Directory the script is restored to
+    pub home: PathBuf,
+}
+
+impl TrashedScript {
+    /// Short name it had before deletion (e.g. "backend")
+    pub fn name_short(&self) -> &str {
+        let no_ext = self.base_name.trim_end_matches(".sh");
+        no_ext.strip_prefix("workspace-").unwrap_or(no_ext)
+    }
+
+    /// The deletion time as `YYYY-MM-DD HH:MM:SS UTC`
+    pub fn deleted_at_display(&self) -> String {
+        let t = &self.deleted_at;
+        format!(
+            "{}-{}-{} {}:{}:{} UTC",
+            &t[..4],
+            &t[4..6],
+            &t[6..8],
+            &t[9..11],
+            &t[11..13],
+            &t[13..15]
+        )
+    }
+}
+
+/// Split a trashed file name into its deletion timestamp and original file name.
+fn split_trashed_name(name: &str) -> Option<(&str, &str)> {
+    let (stamp, base_name) = (name.get(..15)?, name.get(16..)?);
+    let well_formed = name.as_bytes()[15] == b'-'
+        && stamp.bytes().enumerate().all(|(i, b)| {
+            if i == 8 {
+                b == b'-'
+            } else {
+                b.is_ascii_digit()
+            }
+        });
+    (well_formed && is_workspace_file_name(base_name)).then_some((stamp, base_name))
+}
+
+/// Every script in the trash of `dirs`, newest first.
+pub fn list_trash(dirs: &[PathBuf]) -> io::Result<Vec<TrashedScript>> {
+    let mut trashed = Vec::new();
+    let mut seen = Vec::new();
+    for dir in dirs {
+        let trash = trash_dir(dir);
+        if seen.contains(&trash) || !trash.is_dir() {
+            continue;
+        }
+        for entry in fs::read_dir(&trash)? {
+            let path = entry?.path();
+            let name = path
+                .file_name()
+                .unwrap_or_default()
+                .to_string_lossy()
+                .to_string();
+            if let Some((stamp, base_name)) = split_trashed_name(&name) {
+                trashed.push(TrashedScript {
+                    base_name: base_name.to_string(),
+                    deleted_at: stamp.to_string(),
+                    home: trash.parent().unwrap_or(dir).to_path_buf(),
+                    path,
+                });
+            }
+        }
+        seen.push(trash);
+    }
+    trashed.sort_by(|a, b| {
+        b.deleted_at
+            .cmp(&a.deleted_at)
+            .then_with(|| a.base_name.cmp(&b.base_name))
+    });
+    Ok(trashed)
+}
+
+/// Print the trashed scripts (`hyprspace trash list`).
+fn print_trash(trashed: &[TrashedScript], multiple_homes: bool) {
+    if trashed.is_empty() {
+        println!("The trash is empty.");
+        return;
+    }
+    let width = trashed
+        .iter()
+        .map(|t| t.name_short().len())
+        .max()
+        .unwrap_or(0);
+    for script in trashed {
+        let home = if multiple_homes {
+            format!("  ({})", script.home.to_string_lossy())
+        } else {
+            String::new()
+        };
+        println!(
+            "{:<width$}  deleted {}{home}",
+            script.name_short(),
+            script.deleted_at_display()
+        );
+    }
+    println!(
+        "\nRestore one with `hyprspace trash restore <name>`, purge them with `hyprspace trash empty`."
+    );
+}
+
+/// Put the newest trashed copy of `name` (short name, file name or trashed file name)
+/// back in its directory.
+fn restore(trashed: &[TrashedScript], name: &str) -> io::Result<()> {
+    let matches = |t: &&TrashedScript| {
+        t.name_short() == name
+            || t.base_name == name
+            || t.path.file_name().is_some_and(|f| f == name)
+    };
+    let Some(script) = trashed.iter().find(matches) else {
+        return Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            format!("no trashed script named '{name}'"),
+        ));
+    };
+
+    let dest = script.home.join(&script.base_name);
+    move_file(&script.path, &dest)?;
+    println!(
+        "Restored {} (deleted {})",
+        dest.to_string_lossy(),
+        script.deleted_at_display()
+    );
+    Ok(())
+}
+
+/// Remove every trashed script for good, after confirmation (`hyprspace trash empty`).
+fn empty(trashed: &[TrashedScript]) -> io::Result<()> {
+    if trashed.is_empty() {
+        println!("The trash is already empty.");
+        return Ok(());
+    }
+    if !confirm(&format!(
+        "Permanently delete {} trashed script(s)?",
+        trashed.len()
+    ))? {
+        println!("Aborted, the trash was kept.");
+        return Ok(());
+    }
+
+    for script in trashed {
+        fs::remove_file(&script.path)?;
+    }
+    // Leave no empty trash folders behind; one still holding other files stays
+    for trash in trashed.iter().filter_map(|t| t.path.parent()) {
+        let _ = fs::remove_dir(trash);
+    }
+    println!("Deleted {} script(s) for good.", trashed.len());
+    Ok(())
+}
+
+/// `hyprspace trash [list | restore <name> | empty]` over the trash of `dirs`.
+pub fn run_trash(dirs: &[PathBuf], subcommand: Option<&str>, name: Option<&str>) -> io::Result<()> {
+    let trashed = list_trash(dirs)?;
+    match (subcommand, name) {
+        (None | Some("list"), None) => {
+            let first_home = trashed.first().map(|t| t.home.as_path());
+            let multiple_homes = trashed.iter().any(|t| Some(t.home.as_path()) != first_home);
+            print_trash(&trashed, multiple_homes);
+            Ok(())
+        }
+        (Some("restore"), Some(name)) => restore(&trashed, name),
+        (Some("empty"), None) => empty(&trashed),
+        _ => Err(usage_error()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn trashed_names_split_into_stamp_and_script() {
+        assert_eq!(
+            split_trashed_name("20240229-123456-workspace-dev.sh"),
+            Some(("20240229-123456", "workspace-dev.sh"))
+        );
+        assert_eq!(
+            split_trashed_name("19700101-000000-workspace-a-b.sh"),
+            Some(("19700101-000000", "workspace-a-b.sh"))
+        );
+
+        for name in [
+            "20240229-123456",
+            "20240229-123456-",
+            "20240229-123456-notes.txt",
+            "2024022-1234567-workspace-dev.sh",
+            "20240229_123456-workspace-dev.sh",
+            "2024O229-123456-workspace-dev.sh",
+            "20240229-123456_workspace-dev.sh",
+            "workspace-dev.sh",
+            "2024é229-123456-workspace-dev.sh",
+        ] {
+            assert_eq!(split_trashed_name(name), None, "{name}");
+        }
+    }
+}
@@ -1,47 +1,479 @@
+use std::collections::{BTreeSet, HashSet};
+use std::fs;
 use std::io;
-use std::time::Duration;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime};
 
 use crossterm::{
-    event::{self, Event, KeyCode},
+    cursor::Show,
+    event::{
+        self, Event, KeyCode, KeyEvent, KeyModifiers, KeyboardEnhancementFlags,
+        PopKeyboardEnhancementFlags, PushKeyboardEnhancementFlags,
+    },
     execute,
-    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
+    terminal::{EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode},
 };
 use ratatui::{
+    Frame, Terminal, TerminalOptions, Viewport,
     backend::CrosstermBackend,
-    layout::{Constraint, Direction, Layout},
+    layout::{Constraint, Direction, Layout, Rect},
     style::{Color, Modifier, Style},
-    widgets::{Block, Borders, List, ListItem, ListState, Paragraph},
-    Frame, Terminal,
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph, Widget, Wrap},
 };
+use unicode_width::UnicodeWidthChar;
 
-use crate::workspace::WorkspaceEntry;
+use crate::clipboard;
+use crate::config::{Config, CreateRow, DigitKeys};
+use crate::diff::{DiffLine, diff_lines};
+use crate::docs;
+use crate::fuzzy::{fuzzy_match, score_workspace};
+use crate::grid::{layout_grid, placement};
+use crate::hyprctl;
+use crate::launcher::{LaunchOptions, open_debug_terminal, targets_current_workspace};
+use crate::list::{badge, badge_width, column_widths, display_width, icon_cell, programs_summary};
+use crate::picker::run_plain_picker;
+use crate::script::{ScriptDocument, WindowSpec, expand_env_vars, fill_template};
+use crate::state::{UiPrefs, load_pinned, load_ui_prefs, save_pinned, save_ui_prefs};
+use crate::term;
+use crate::workspace::{
+    ParseNote, WorkspaceEntry, archive_dirs, archive_script, assume_yes, delete_script,
+    has_multiple_sources, is_executable, list_all_workspaces, list_profiles, move_script,
+    name_collisions, parse_report, profile_dir, restore_script, set_executable, workspace_dir,
+};
+
+/// How long a first `g` waits for the second one to complete `gg`
+const PENDING_G_TIMEOUT: Duration = Duration::from_millis(800);
+
+/// What `Enter` does with each modifier, for the footer (see [`EnterMode`])
+const ENTER_MATRIX: &str =
+    "Enter: launch  • Shift+Enter: launch & stay  • Ctrl+Enter: switch only  • Alt+Enter: dry run";
+
+/// How long a toast message stays in the footer
+const TOAST_DURATION: Duration = Duration::from_secs(3);
+
+/// How often `--watch` looks for changes in the script directories
+const WATCH_INTERVAL: Duration = Duration::from_secs(1);
 
 /// What the user chose in the TUI
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone)]
 pub enum Action {
     Launch(usize),
-    CreateNew,
+    /// Launch only these windows (positions in the script) of the script at that index
+    LaunchWindows(usize, Vec<usize>),
+    /// Only switch to the script's workspace, spawning nothing
+    Switch(usize),
+    /// Copy the script under a new name and workspace number
+    Duplicate(usize),
+    /// Run the creation wizard, saving into this directory
+    CreateNew(PathBuf),
+    /// Convert the script's size and move values between percent and pixels
+    ConvertUnits(usize),
+    /// Launch the script, then come back to the TUI (`Shift+Enter`)
+    LaunchAndStay(usize),
+    /// Print what launching the script would run, without running it (`Alt+Enter`)
+    DryRun(usize),
+}
+
+/// Settings the TUI is started with
+#[derive(Debug, Clone, Default)]
+pub struct TuiOptions {
+    /// Hyprland instance signature for direct hyprctl calls
+    pub instance: Option<String>,
+    /// Only pick a script (`hyprspace pick`): `Enter` selects instead of launching
+    pub pick: bool,
+    /// Start with dense rows (`--compact` / `--no-compact`), `None` for the remembered density
+    pub compact: Option<bool>,
+    /// Browse and launch only: no create or delete (`--read-only`)
+    pub read_only: bool,
+    /// Draw below the prompt instead of on the alternate screen (`--inline`)
+    pub inline: bool,
+    /// Filter query the list starts with, `None` for the remembered one
+    pub filter: Option<String>,
+    /// Short name of the script highlighted at start (`--select`), if it exists
+    pub select: Option<String>,
+    /// Ask before launching a script onto the focused workspace (off for dry and scratch launches)
+    pub check_current_workspace: bool,
+    /// Profile the scripts come from (`--profile`), `None` for the workspace directory
+    pub profile: Option<String>,
+    /// Script directories, offered as destinations when moving a script; the first one
+    /// is the workspace (or profile) directory
+    pub dirs: Vec<PathBuf>,
+    /// Directories to poll for added, removed or modified scripts (`--watch`), empty to disable
+    pub watch: Vec<PathBuf>,
+    /// Message shown in the footer when the TUI opens, e.g. how the last launch went
+    pub notice: Option<String>,
+    /// Settings from the config file
+    pub config: Config,
+}
+
+/// The workspace number of `ws` when it is the focused one and launching it should
+/// ask first (`[confirm] current_workspace`), see [`targets_current_workspace`].
+pub(crate) fn current_workspace_to_confirm(
+    options: &TuiOptions,
+    ws: &WorkspaceEntry,
+) -> Option<u32> {
+    if options.pick || !options.check_current_workspace || !options.config.confirm.current_workspace
+    {
+        return None;
+    }
+    let launch_options = LaunchOptions {
+        instance: options.instance.clone(),
+        ..LaunchOptions::default()
+    };
+    targets_current_workspace(ws, &launch_options)
+}
+
+/// Detail view of a single script and its windows
+struct Detail {
+    /// Index into `App::workspaces`
+    ws_idx: usize,
+    /// Windows parsed from the script
+    windows: Vec<WindowSpec>,
+    /// Selected window
+    selected: usize,
+    /// Show commands with `$VAR`s replaced by their current values
+    expand_env: bool,
+    /// Windows toggled off with `Space`, left out when launching from here
+    disabled: BTreeSet<usize>,
+    /// Show the layout grid under the windows (`g`)
+    grid: bool,
+    /// Logical size of the focused monitor, asked when the grid is first shown
+    monitor: Option<(u32, u32)>,
+}
+
+/// Name, size and modification time of every file in the watched directories
+type Fingerprint = Vec<(PathBuf, u64, Option<SystemTime>)>;
+
+fn fingerprint(dirs: &[PathBuf]) -> Fingerprint {
+    let mut files: Fingerprint = dirs
+        .iter()
+        .filter_map(|dir| fs::read_dir(dir).ok())
+        .flatten()
+        .filter_map(Result::ok)
+        .map(|entry| {
+            let meta = entry.metadata().ok();
+            let len = meta.as_ref().map_or(0, |m| m.len());
+            (entry.path(), len, meta.and_then(|m| m.modified().ok()))
+        })
+        .collect();
+    files.sort();
+    files
+}
+
+/// Polling state for `--watch`
+struct Watch {
+    dirs: Vec<PathBuf>,
+    last: Fingerprint,
+    next_check: Instant,
+}
+
+/// Popup comparing two scripts line by line
+struct DiffView {
+    /// File names of the compared scripts, old then new
+    names: (String, String),
+    old: String,
+    new: String,
+    /// First diff line shown
+    scroll: u16,
+}
+
+/// Popup showing how the parser reads a script (`Ctrl+D`)
+struct ParseView {
+    /// File name of the script
+    name: String,
+    /// Where the workspace number comes from and the window count
+    summary: String,
+    notes: Vec<ParseNote>,
+    /// First line shown
+    scroll: u16,
+}
+
+/// Popup choosing the directory a script is moved to
+struct MoveView {
+    /// Index into `App::workspaces` of the script being moved
+    ws_idx: usize,
+    /// Configured directories other than the script's own
+    choices: Vec<PathBuf>,
+    selected: usize,
+}
+
+/// What `Enter` does on a script, picked by the modifier held with it.
+///
+/// Terminals that don't report modifiers with `Enter` always give [`EnterMode::Launch`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum EnterMode {
+    /// `Enter`: launch and leave the TUI
+    Launch,
+    /// `Shift+Enter`: launch and come back to the TUI
+    Stay,
+    /// `Ctrl+Enter`: only switch to the script's workspace
+    SwitchOnly,
+    /// `Alt+Enter`: print the commands a launch would run
+    DryRun,
+}
+
+impl EnterMode {
+    fn from_modifiers(modifiers: KeyModifiers) -> Self {
+        if modifiers.contains(KeyModifiers::CONTROL) {
+            EnterMode::SwitchOnly
+        } else if modifiers.contains(KeyModifiers::ALT) {
+            EnterMode::DryRun
+        } else if modifiers.contains(KeyModifiers::SHIFT) {
+            EnterMode::Stay
+        } else {
+            EnterMode::Launch
+        }
+    }
+}
+
+/// The action launching the script at `idx`, coming back to the TUI afterwards when `stay`
+fn launch_action(idx: usize, stay: bool) -> Action {
+    if stay {
+        Action::LaunchAndStay(idx)
+    } else {
+        Action::Launch(idx)
+    }
+}
+
+/// A yes/no question shown in the footer, answered with `y`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Confirm {
+    DeleteChecked,
+    /// Launch the script at this index (`[confirm] launch = true`), returning to the TUI
+    /// afterwards when set
+    Launch(usize, bool),
+    /// Launch the script at this index onto the focused workspace, whose number is kept
+    LaunchCurrent(usize, u32, bool),
+    /// Quit although [`App::is_dirty`]
+    Quit,
 }
 
 /// Application state for the TUI
 struct App {
+    options: TuiOptions,
     workspaces: Vec<WorkspaceEntry>,
     selected: usize,
     action: Option<Action>,
+    /// Set once the user asked to leave the TUI
+    quit: bool,
+    /// Set when a first `g` was pressed, waiting for a second one
+    pending_g: Option<Instant>,
+    /// Current filter query (empty = show everything)
+    filter: String,
+    /// Listing the archived scripts (`A`) instead of the active ones
+    archived: bool,
+    /// Whether keystrokes currently edit the filter query
+    filter_active: bool,
+    /// Indices into `workspaces` that match the filter, in display order
+    visible: Vec<usize>,
+    /// Whether `Space` toggles checkmarks for batch actions
+    select_mode: bool,
+    /// Indices into `workspaces` checked in selection mode
+    checked: HashSet<usize>,
+    /// Question awaiting a `y` answer, if any
+    confirm: Option<Confirm>,
+    /// Toast shown in the footer after an action, with the time it appeared
+    status: Option<(String, Instant)>,
+    /// Open detail view, if any
+    detail: Option<Detail>,
+    /// Short names of pinned workspaces, shown in their own section at the top
+    pinned: BTreeSet<String>,
+    /// Dense rows: only the workspace badge and short name
+    compact: bool,
+    /// Script marked with `m`, to compare with the next one marked
+    marked: Option<usize>,
+    /// Open diff popup, if any
+    diff: Option<DiffView>,
+    /// Open parser report, if any
+    parse_view: Option<ParseView>,
+    /// Open move popup, if any
+    move_view: Option<MoveView>,
+    /// Workspace ids that already hold windows, read from hyprctl at startup
+    occupied: HashSet<u32>,
+    /// Set with `--watch`
+    watch: Option<Watch>,
+    /// Density and filter the TUI started with; only changes made in this run are saved,
+    /// so flags don't stick
+    start_prefs: UiPrefs,
 }
 
 impl App {
-    fn new(workspaces: Vec<WorkspaceEntry>) -> Self {
-        Self {
+    fn new(workspaces: Vec<WorkspaceEntry>, options: TuiOptions) -> Self {
+        // Flags win over what the last run left
+        let saved = load_ui_prefs();
+        let compact = options.compact.unwrap_or(saved.compact);
+        let filter = options.filter.clone().unwrap_or(saved.filter);
+        let start_prefs = UiPrefs {
+            compact,
+            filter: filter.clone(),
+        };
+        // Without Hyprland (or on a hyprctl error) nothing is marked
+        let occupied =
+            hyprctl::occupied_workspaces(options.instance.as_deref()).unwrap_or_default();
+        let watch = (!options.watch.is_empty()).then(|| Watch {
+            last: fingerprint(&options.watch),
+            dirs: options.watch.clone(),
+            next_check: Instant::now() + WATCH_INTERVAL,
+        });
+        let mut app = Self {
+            options,
             workspaces,
             selected: 0,
             action: None,
+            quit: false,
+            pending_g: None,
+            filter,
+            archived: false,
+            filter_active: false,
+            visible: Vec::new(),
+            select_mode: false,
+            checked: HashSet::new(),
+            confirm: None,
+            status: None,
+            detail: None,
+            pinned: load_pinned(),
+            compact,
+            marked: None,
+            diff: None,
+            parse_view: None,
+            move_view: None,
+            occupied,
+            watch,
+            start_prefs,
+        };
+        app.apply_filter();
+        if let Some(name) = app.options.select.clone() {
+            app.select_name(&name);
+        }
+        if let Some(notice) = app.options.notice.clone() {
+            app.toast(notice);
+        }
+        app
+    }
+
+    /// Highlight the script called `name`, clearing a filter that hides it.
+    fn select_name(&mut self, name: &str) {
+        let Some(idx) = self.workspaces.iter().position(|ws| ws.name_short == name) else {
+            return;
+        };
+        if !self.visible.contains(&idx) {
+            self.filter.clear();
+            self.refresh_visible();
+        }
+        if let Some(pos) = self.visible.iter().position(|&v| v == idx) {
+            self.selected = pos + self.row_offset();
         }
     }
 
     fn total_items(&self) -> usize {
-        // all workspaces + 1 extra item for "Create new..."
-        self.workspaces.len() + 1
+        // visible workspaces + 1 extra item for "Create new..."
+        self.visible.len() + usize::from(self.has_create_row())
+    }
+
+    /// Whether the "Create new..." row is shown (hidden in read-only mode and among archived scripts)
+    fn has_create_row(&self) -> bool {
+        !self.options.read_only && !self.archived
+    }
+
+    /// Number of rows shown above the first script (the "Create new..." row when on top)
+    fn row_offset(&self) -> usize {
+        usize::from(self.has_create_row() && self.options.config.create_row == CreateRow::Top)
+    }
+
+    /// Position in `visible` of the selected row, `None` for "Create new..."
+    fn selected_position(&self) -> Option<usize> {
+        let pos = self.selected.checked_sub(self.row_offset())?;
+        (pos < self.visible.len()).then_some(pos)
+    }
+
+    /// Index into `workspaces` of the selected row, `None` for "Create new..."
+    fn selected_workspace(&self) -> Option<usize> {
+        self.selected_position().map(|pos| self.visible[pos])
+    }
+
+    /// Recompute the visible rows after the filter query changed.
+    fn apply_filter(&mut self) {
+        self.refresh_visible();
+        // Land on the best match rather than on a "Create new..." row at the top
+        let best = (0..self.visible.len()).max_by_key(|&pos| {
+            // Ties go to the earliest row
+            (self.match_score(self.visible[pos]), std::cmp::Reverse(pos))
+        });
+        self.selected = best.map_or(0, |pos| pos + self.row_offset());
+    }
+
+    /// Best fuzzy score of the filter against the entry's names (`None` = no match).
+    fn match_score(&self, idx: usize) -> Option<i64> {
+        score_workspace(&self.workspaces[idx], &self.filter)
+    }
+
+    /// Recompute the visible rows, keeping the selection in range.
+    fn refresh_visible(&mut self) {
+        if self.filter.is_empty() {
+            self.visible = (0..self.workspaces.len()).collect();
+        } else {
+            let mut scored: Vec<(usize, i64)> = (0..self.workspaces.len())
+                .filter_map(|idx| Some((idx, self.match_score(idx)?)))
+                .collect();
+            // Best matches first; the sort is stable so equal scores keep the list order
+            scored.sort_by_key(|&(_, score)| std::cmp::Reverse(score));
+            self.visible = scored.into_iter().map(|(idx, _)| idx).collect();
+        }
+
+        // Pinned entries first; the sort is stable so each section keeps its order
+        let pinned = &self.pinned;
+        let workspaces = &self.workspaces;
+        self.visible
+            .sort_by_key(|&idx| !pinned.contains(&workspaces[idx].name_short));
+
+        self.selected = self.selected.min(self.total_items().saturating_sub(1));
+    }
+
+    fn is_pinned(&self, idx: usize) -> bool {
+        self.pinned.contains(&self.workspaces[idx].name_short)
+    }
+
+    /// Number of visible rows in the pinned section
+    fn pinned_visible(&self) -> usize {
+        self.visible
+            .iter()
+            .filter(|&&idx| self.is_pinned(idx))
+            .count()
+    }
+
+    /// Pin or unpin the selected workspace and persist the change.
+    fn toggle_pinned(&mut self) {
+        let Some(idx) = self.selected_workspace() else {
+            return;
+        };
+        let name = self.workspaces[idx].name_short.clone();
+
+        let pinned = if self.pinned.remove(&name) {
+            false
+        } else {
+            self.pinned.insert(name.clone());
+            true
+        };
+
+        self.refresh_visible();
+        // Keep the cursor on the entry that just moved between sections
+        if let Some(pos) = self.visible.iter().position(|&i| i == idx) {
+            self.selected = pos + self.row_offset();
+        }
+
+        let message = match save_pinned(&self.pinned) {
+            Ok(()) if pinned => format!("Pinned {name}"),
+            Ok(()) => format!("Unpinned {name}"),
+            Err(err) => format!("Failed to save pinned list: {err}"),
+        };
+        self.toast(message);
+    }
+
+    fn clear_filter(&mut self) {
+        self.filter.clear();
+        self.filter_active = false;
+        self.apply_filter();
     }
 
     fn next(&mut self) {
@@ -63,129 +495,1771 @@ impl App {
             self.selected -= 1;
         }
     }
-}
 
-/// Draw the UI for the current app state
-fn ui(f: &mut Frame, app: &App) {
-    let area = f.area();
+    fn first(&mut self) {
+        self.selected = 0;
+    }
 
-    // Split screen: main list + footer
-    let chunks = Layout::default()
-        .direction(Direction::Vertical)
-        .constraints(
-            [
-                Constraint::Min(3),   // main area
-                Constraint::Length(2), // footer
-            ]
-            .as_ref(),
-        )
-        .split(area);
+    fn last(&mut self) {
+        let total = self.total_items();
+        if total == 0 {
+            return;
+        }
+        self.selected = total - 1;
+    }
 
-    let title = format!(
-        "Hyprspace • {} configuration(s) found",
-        app.workspaces.len()
-    );
+    /// Move to the next visible script (after the cursor, wrapping around) whose
+    /// workspace number starts with `digit`.
+    fn jump_to_number(&mut self, digit: char) {
+        let count = self.visible.len();
+        let start = self.selected_position().map_or(0, |pos| pos + 1);
+        let found = (0..count).map(|step| (start + step) % count).find(|&pos| {
+            self.workspaces[self.visible[pos]]
+                .workspace_num
+                .is_some_and(|num| num.to_string().starts_with(digit))
+        });
 
-    let block = Block::default()
-        .title(title)
-        .borders(Borders::ALL);
+        match found {
+            Some(pos) => self.selected = pos + self.row_offset(),
+            None => self.toast(format!("No workspace number starting with {digit}")),
+        }
+    }
 
-    // Build list items: all workspaces + one "Create new" entry
-    let mut items: Vec<ListItem> = app
-        .workspaces
-        .iter()
-        .enumerate()
-        .map(|(idx, ws)| {
-            let ws_info = match ws.workspace_num {
-                Some(num) => format!("[ws {}]", num),
-                None => "[ws ?]".to_string(),
-            };
+    /// Launch the `n`-th visible script (1-based), through the launch confirmation if enabled.
+    fn launch_nth(&mut self, n: usize) {
+        if let Some(&idx) = n.checked_sub(1).and_then(|pos| self.visible.get(pos)) {
+            self.request_launch(idx, false);
+        }
+    }
 
-            let text = format!(
-                "{}. {} {} ({})",
-                idx + 1,
-                ws_info,
-                ws.name_short,
-                ws.base_name
+    /// Launch (or pick) the script at `idx`, asking first when `[confirm] launch` is set
+    /// (and not answered by `--yes`).
+    fn request_launch(&mut self, idx: usize, stay: bool) {
+        // `--yes` answers every confirmation in advance
+        if assume_yes() {
+            self.action = Some(launch_action(idx, stay));
+            self.quit = true;
+        } else if let Some(num) = current_workspace_to_confirm(&self.options, &self.workspaces[idx])
+        {
+            self.confirm = Some(Confirm::LaunchCurrent(idx, num, stay));
+        } else if self.options.config.confirm.launch && !self.options.pick {
+            // Picking only prints a name, so only a real launch asks
+            self.confirm = Some(Confirm::Launch(idx, stay));
+        } else {
+            self.action = Some(launch_action(idx, stay));
+            self.quit = true;
+        }
+    }
+
+    /// Only switch to the workspace of the script at `idx` (`s`, `Ctrl+Enter`).
+    fn request_switch(&mut self, idx: usize) {
+        if self.workspaces[idx].workspace().is_some() {
+            self.action = Some(Action::Switch(idx));
+            self.quit = true;
+        } else {
+            let message = format!(
+                "{} has no workspace line to switch to",
+                self.workspaces[idx].base_name
             );
-            ListItem::new(text)
-        })
-        .collect();
+            self.toast(message);
+        }
+    }
 
-    let create_label = "Create new workspace script…";
-    items.push(ListItem::new(create_label));
+    /// Run what `Enter` does on the script at `idx`, given the modifiers held (see [`EnterMode`]).
+    fn enter_on(&mut self, idx: usize, modifiers: KeyModifiers) {
+        // Picking only returns the script
+        if self.options.pick {
+            self.request_launch(idx, false);
+            return;
+        }
+        match EnterMode::from_modifiers(modifiers) {
+            EnterMode::Launch => self.request_launch(idx, false),
+            EnterMode::Stay => self.request_launch(idx, true),
+            EnterMode::SwitchOnly => self.request_switch(idx),
+            EnterMode::DryRun => {
+                self.action = Some(Action::DryRun(idx));
+                self.quit = true;
+            }
+        }
+    }
 
-    let list = List::new(items)
-        .block(block)
-        .highlight_style(
-            Style::default()
-                .fg(Color::Cyan)
-                .add_modifier(Modifier::BOLD),
-        )
-        .highlight_symbol("➤ ");
+    /// Whether quitting would lose work in progress: checked scripts, or windows
+    /// toggled off in the detail view.
+    fn is_dirty(&self) -> bool {
+        !self.checked.is_empty()
+            || self
+                .detail
+                .as_ref()
+                .is_some_and(|detail| !detail.disabled.is_empty())
+    }
 
-    let mut state = ListState::default();
-    if app.total_items() > 0 {
-        state.select(Some(app.selected));
+    /// Quit, asking first when [`is_dirty`](Self::is_dirty) (unless `--yes`).
+    fn request_quit(&mut self) {
+        if self.is_dirty() && !assume_yes() {
+            self.confirm = Some(Confirm::Quit);
+        } else {
+            self.action = None;
+            self.quit = true;
+        }
     }
 
-    // Render main list in the upper chunk
-    f.render_stateful_widget(list, chunks[0], &mut state);
+    /// Handle a `g` press: the second one within the timeout jumps to the top.
+    fn press_g(&mut self) {
+        match self.pending_g.take() {
+            Some(at) if at.elapsed() <= PENDING_G_TIMEOUT => self.first(),
+            _ => self.pending_g = Some(Instant::now()),
+        }
+    }
 
-    // Footer with shortcuts
-    let footer_text = "↑/↓ or j/k: navigate  • Enter: launch  • q or Esc: quit";
-    let footer = Paragraph::new(footer_text)
-        .style(Style::default().fg(Color::DarkGray));
+    /// Toggle the checkmark on the selected row, entering selection mode.
+    fn toggle_checked(&mut self) {
+        let Some(idx) = self.selected_workspace() else {
+            return;
+        };
+        self.select_mode = true;
+        if !self.checked.remove(&idx) {
+            self.checked.insert(idx);
+        }
+    }
 
-    f.render_widget(footer, chunks[1]);
-}
+    fn exit_select_mode(&mut self) {
+        self.select_mode = false;
+        self.checked.clear();
+    }
 
-/// Run the TUI and return the selected action (launch or create).
-pub fn run_tui(
-    workspaces: Vec<WorkspaceEntry>,
-) -> io::Result<(Vec<WorkspaceEntry>, Option<Action>)> {
-    let mut app = App::new(workspaces);
+    /// Move every checked script to the trash and out of the list.
+    fn delete_checked(&mut self) {
+        let mut indices: Vec<usize> = self.checked.drain().collect();
+        // Remove from the back so the remaining indices stay valid
+        indices.sort_unstable_by(|a, b| b.cmp(a));
 
-    enable_raw_mode()?;
-    let mut stdout = io::stdout();
-    execute!(stdout, EnterAlternateScreen)?;
-    let backend = CrosstermBackend::new(stdout);
-    let mut terminal = Terminal::new(backend)?;
+        let mut deleted = 0usize;
+        let mut failures = Vec::new();
 
-    loop {
-        terminal.draw(|f| ui(f, &app))?;
-
-        if event::poll(Duration::from_millis(250))? {
-            if let Event::Key(key_event) = event::read()? {
-                match key_event.code {
-                    KeyCode::Char('q') | KeyCode::Esc => {
-                        app.action = None;
-                        break;
-                    }
-                    KeyCode::Down | KeyCode::Char('j') => {
-                        app.next();
-                    }
-                    KeyCode::Up | KeyCode::Char('k') => {
-                        app.previous();
-                    }
-                    KeyCode::Enter => {
-                        let ws_len = app.workspaces.len();
-                        if app.selected < ws_len {
-                            app.action = Some(Action::Launch(app.selected));
-                        } else {
-                            app.action = Some(Action::CreateNew);
-                        }
-                        break;
-                    }
-                    _ => {}
+        for idx in indices {
+            match delete_script(&self.workspaces[idx]) {
+                Ok(_) => {
+                    self.workspaces.remove(idx);
+                    deleted += 1;
+                }
+                Err(err) => failures.push(format!("{}: {err}", self.workspaces[idx].base_name)),
+            }
+        }
+
+        self.select_mode = false;
+        // Indices shifted, the mark may now point at another script
+        self.marked = None;
+        self.refresh_visible();
+
+        self.toast(if failures.is_empty() {
+            format!("Moved {deleted} script(s) to the trash, `hyprspace trash restore <name>` brings one back")
+        } else {
+            format!("Moved {deleted} script(s) to the trash, failed: {}", failures.join(", "))
+        });
+    }
+
+    /// The script directories currently listed: their `disabled/` folders in the archived view
+    fn listed_dirs(&self) -> Vec<PathBuf> {
+        if self.archived {
+            archive_dirs(&self.options.dirs)
+        } else {
+            self.options.dirs.clone()
+        }
+    }
+
+    /// Switch between the active and the archived scripts (`A`).
+    fn toggle_archived_view(&mut self) {
+        self.archived = !self.archived;
+        match list_all_workspaces(&self.listed_dirs()) {
+            Ok(workspaces) => {
+                self.exit_select_mode();
+                self.replace_workspaces(workspaces);
+                self.selected = self.row_offset();
+                if self.archived {
+                    self.toast(format!(
+                        "{} archived script(s) • a: restore • A: back",
+                        self.workspaces.len()
+                    ));
+                }
+            }
+            Err(err) => {
+                self.archived = !self.archived;
+                self.toast(format!("Could not list archived scripts: {err}"));
+            }
+        }
+    }
+
+    /// Switch to the next profile (`P`): the workspace directory, then every profile in
+    /// name order, then back.
+    fn next_profile(&mut self) {
+        let profiles = list_profiles();
+        let next = match &self.options.profile {
+            None => profiles.first().cloned(),
+            Some(current) => profiles
+                .iter()
+                .skip_while(|name| *name != current)
+                .nth(1)
+                .cloned(),
+        };
+        if next.is_none() && self.options.profile.is_none() {
+            self.toast(format!(
+                "No profiles yet, add directories to {}",
+                profile_dir("").to_string_lossy()
+            ));
+            return;
+        }
+
+        let dir = next.as_deref().map_or_else(workspace_dir, profile_dir);
+        let previous = std::mem::replace(&mut self.options.dirs[0], dir.clone());
+        if let Some(watch) = &mut self.watch
+            && let Some(first) = watch.dirs.first_mut()
+        {
+            *first = dir;
+            watch.last = fingerprint(&watch.dirs);
+        }
+
+        match list_all_workspaces(&self.listed_dirs()) {
+            Ok(workspaces) => {
+                self.exit_select_mode();
+                self.detail = None;
+                self.marked = None;
+                self.replace_workspaces(workspaces);
+                self.selected = self.row_offset();
+                let name = next.as_deref().unwrap_or("default");
+                self.toast(format!(
+                    "Profile {name}: {} script(s)",
+                    self.workspaces.len()
+                ));
+                self.options.profile = next;
+            }
+            Err(err) => {
+                self.options.dirs[0] = previous;
+                self.toast(format!("Could not list the scripts of that profile: {err}"));
+            }
+        }
+    }
+
+    /// Archive a script out of the list, or restore it in the archived view (`a`).
+    fn toggle_archive(&mut self, idx: usize) {
+        let name = self.workspaces[idx].base_name.clone();
+        let (result, done) = if self.archived {
+            (restore_script(&self.workspaces[idx]), "Restored")
+        } else {
+            (archive_script(&self.workspaces[idx]), "Archived")
+        };
+        if let Err(err) = result {
+            self.toast(format!(
+                "Could not {} {name}: {err}",
+                if self.archived { "restore" } else { "archive" }
+            ));
+            return;
+        }
+
+        match list_all_workspaces(&self.listed_dirs()) {
+            Ok(workspaces) => {
+                self.replace_workspaces(workspaces);
+                let hint = if self.archived {
+                    ""
+                } else {
+                    ", A shows archived scripts"
+                };
+                self.toast(format!("{done} {name}{hint}"));
+            }
+            Err(err) => self.toast(format!(
+                "{done} {name}, but reloading the list failed: {err}"
+            )),
+        }
+    }
+
+    /// With `--watch`, reload the scripts when their directories changed on disk.
+    fn check_watch(&mut self) {
+        let Some(watch) = &mut self.watch else {
+            return;
+        };
+        watch.next_check = Instant::now() + WATCH_INTERVAL;
+
+        let current = fingerprint(&watch.dirs);
+        if current == watch.last {
+            return;
+        }
+        watch.last = current;
+
+        match list_all_workspaces(&self.listed_dirs()) {
+            Ok(workspaces) => {
+                self.replace_workspaces(workspaces);
+                self.toast("Scripts changed on disk, list refreshed".to_string());
+            }
+            Err(err) => self.toast(format!("Scripts changed on disk, reload failed: {err}")),
+        }
+    }
+
+    /// Swap in a freshly listed set of scripts, keeping the selection, checks, mark
+    /// and open detail view on the same files where they still exist.
+    fn replace_workspaces(&mut self, workspaces: Vec<WorkspaceEntry>) {
+        let path_of = |idx: usize| self.workspaces[idx].full_path.clone();
+        let selected = self.selected_workspace().map(path_of);
+        let checked: Vec<PathBuf> = self.checked.iter().map(|&idx| path_of(idx)).collect();
+        let marked = self.marked.map(path_of);
+        let detail = self.detail.as_ref().map(|d| path_of(d.ws_idx));
+
+        self.workspaces = workspaces;
+        let index_of = |path: &PathBuf| self.workspaces.iter().position(|ws| ws.full_path == *path);
+
+        let selected_idx = selected.as_ref().and_then(index_of);
+        self.checked = checked.iter().filter_map(index_of).collect();
+        self.marked = marked.as_ref().and_then(index_of);
+        match detail.as_ref().map(index_of) {
+            Some(Some(ws_idx)) => {
+                let windows = fs::read_to_string(&self.workspaces[ws_idx].full_path)
+                    .map(|content| self.script_windows(&content))
+                    .unwrap_or_default();
+                if let Some(detail) = &mut self.detail {
+                    detail.selected = detail.selected.min(windows.len().saturating_sub(1));
+                    detail.disabled.retain(|&idx| idx < windows.len());
+                    detail.ws_idx = ws_idx;
+                    detail.windows = windows;
+                }
+            }
+            // The script shown in the detail view is gone
+            Some(None) => self.detail = None,
+            None => {}
+        }
+
+        self.refresh_visible();
+        let position = selected_idx.and_then(|idx| self.visible.iter().position(|&v| v == idx));
+        self.selected = match position {
+            Some(pos) => pos + self.row_offset(),
+            // Stay on "Create new...", or on the first script if the selected one vanished
+            None if selected.is_none() && self.row_offset() == 0 => {
+                self.total_items().saturating_sub(1)
+            }
+            None => self.row_offset().min(self.total_items().saturating_sub(1)),
+        };
+    }
+
+    /// Open the move popup for a script, listing the other configured directories.
+    fn open_move(&mut self, ws_idx: usize) {
+        let source = &self.workspaces[ws_idx].source_dir;
+        let choices: Vec<PathBuf> = self
+            .options
+            .dirs
+            .iter()
+            .filter(|d| *d != source)
+            .cloned()
+            .collect();
+        if choices.is_empty() {
+            self.toast(
+                "No other script directory configured (add one with --dir or HYPRSPACE_PATH)"
+                    .to_string(),
+            );
+            return;
+        }
+        self.move_view = Some(MoveView {
+            ws_idx,
+            choices,
+            selected: 0,
+        });
+    }
+
+    fn handle_move_key(&mut self, key: KeyEvent) {
+        let Some(view) = &mut self.move_view else {
+            return;
+        };
+        let count = view.choices.len();
+        match key.code {
+            KeyCode::Esc | KeyCode::Char('q') => self.move_view = None,
+            KeyCode::Down | KeyCode::Char('j') => view.selected = (view.selected + 1) % count,
+            KeyCode::Up | KeyCode::Char('k') => view.selected = (view.selected + count - 1) % count,
+            KeyCode::Enter => {
+                let dest = view.choices[view.selected].clone();
+                let ws_idx = view.ws_idx;
+                self.move_view = None;
+                self.move_to(ws_idx, &dest);
+            }
+            _ => {}
+        }
+    }
+
+    /// Move a script to `dest`, reload the list and keep the cursor on it.
+    fn move_to(&mut self, ws_idx: usize, dest: &Path) {
+        let name = self.workspaces[ws_idx].base_name.clone();
+        let new_path = match move_script(&self.workspaces[ws_idx], dest) {
+            Ok(path) => path,
+            Err(err) => {
+                self.toast(format!("Could not move {name}: {err}"));
+                return;
+            }
+        };
+
+        match list_all_workspaces(&self.options.dirs) {
+            Ok(workspaces) => {
+                self.replace_workspaces(workspaces);
+                let idx = self
+                    .workspaces
+                    .iter()
+                    .position(|ws| ws.full_path == new_path);
+                if let Some(pos) = idx.and_then(|idx| self.visible.iter().position(|&v| v == idx)) {
+                    self.selected = pos + self.row_offset();
                 }
+                self.toast(format!("Moved {name} to {}", dest.to_string_lossy()));
+            }
+            Err(err) => self.toast(format!(
+                "Moved {name}, but reloading the list failed: {err}"
+            )),
+        }
+    }
+
+    /// Windows of a script, falling back to the configured helper name when it defines none
+    fn script_windows(&self, content: &str) -> Vec<WindowSpec> {
+        ScriptDocument::parse_with_helper(content, &self.options.config.generator.helper).windows()
+    }
+
+    /// Show a message in the footer for a few seconds.
+    fn toast(&mut self, message: String) {
+        self.status = Some((message, Instant::now()));
+    }
+
+    /// Flip the executable bit of a script.
+    fn toggle_executable(&mut self, idx: usize) {
+        let ws = &self.workspaces[idx];
+        let result = is_executable(&ws.full_path)
+            .and_then(|was| set_executable(&ws.full_path, !was).map(|_| !was));
+
+        let message = match result {
+            Ok(true) => format!("{} is now executable", ws.base_name),
+            Ok(false) => format!("{} is no longer executable", ws.base_name),
+            Err(err) => format!("Could not change permissions of {}: {err}", ws.base_name),
+        };
+        self.toast(message);
+    }
+
+    /// Mark the selected script, or compare it with the one already marked.
+    fn mark_or_compare(&mut self, idx: usize) {
+        let Some(marked) = self.marked else {
+            self.marked = Some(idx);
+            self.toast(format!(
+                "Marked {}, select another script and press m to compare",
+                self.workspaces[idx].base_name
+            ));
+            return;
+        };
+
+        self.marked = None;
+        if marked == idx {
+            self.toast("Mark cleared".to_string());
+            return;
+        }
+
+        let (old, new) = (&self.workspaces[marked], &self.workspaces[idx]);
+        match (
+            fs::read_to_string(&old.full_path),
+            fs::read_to_string(&new.full_path),
+        ) {
+            (Ok(old_content), Ok(new_content)) => {
+                self.diff = Some(DiffView {
+                    names: (old.base_name.clone(), new.base_name.clone()),
+                    old: old_content,
+                    new: new_content,
+                    scroll: 0,
+                });
+            }
+            (Err(err), _) | (_, Err(err)) => self.toast(format!("Cannot read script: {err}")),
+        }
+    }
+
+    /// Handle a key press while the diff popup is open.
+    fn handle_diff_key(&mut self, key: KeyEvent) {
+        let Some(diff) = self.diff.as_mut() else {
+            return;
+        };
+
+        match key.code {
+            KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('m') => self.diff = None,
+            KeyCode::Down | KeyCode::Char('j') => diff.scroll = diff.scroll.saturating_add(1),
+            KeyCode::Up | KeyCode::Char('k') => diff.scroll = diff.scroll.saturating_sub(1),
+            KeyCode::Char('g') => diff.scroll = 0,
+            _ => {}
+        }
+    }
+
+    /// Open the parser report of the selected script.
+    fn show_parse_report(&mut self, idx: usize) {
+        let ws = &self.workspaces[idx];
+        match fs::read_to_string(&ws.full_path) {
+            Ok(content) => {
+                let (summary, notes) = parse_report(
+                    &content,
+                    &ws.name_short,
+                    &self.options.config.generator.helper,
+                );
+                self.parse_view = Some(ParseView {
+                    name: ws.base_name.clone(),
+                    summary,
+                    notes,
+                    scroll: 0,
+                });
+            }
+            Err(err) => self.toast(format!("Cannot read script: {err}")),
+        }
+    }
+
+    /// Handle a key press while the parser report is open.
+    fn handle_parse_key(&mut self, key: KeyEvent) {
+        let Some(view) = self.parse_view.as_mut() else {
+            return;
+        };
+
+        match key.code {
+            KeyCode::Esc | KeyCode::Char('q') => self.parse_view = None,
+            KeyCode::Char('d') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                self.parse_view = None
+            }
+            KeyCode::Down | KeyCode::Char('j') => view.scroll = view.scroll.saturating_add(1),
+            KeyCode::Up | KeyCode::Char('k') => view.scroll = view.scroll.saturating_sub(1),
+            KeyCode::Char('g') => view.scroll = 0,
+            _ => {}
+        }
+    }
+
+    /// Copy the selected script's path to the clipboard.
+    fn copy_path(&mut self, ws_idx: usize) {
+        let path = self.workspaces[ws_idx]
+            .full_path
+            .to_string_lossy()
+            .to_string();
+        let message = match clipboard::copy(&path) {
+            Ok(tool) => format!("Copied {path} ({tool})"),
+            Err(err) => format!("Copy failed: {err}"),
+        };
+        self.toast(message);
+    }
+
+    /// Open the detail view for the selected script.
+    fn open_detail(&mut self) {
+        let Some(ws_idx) = self.selected_workspace() else {
+            return;
+        };
+
+        match fs::read_to_string(&self.workspaces[ws_idx].full_path) {
+            Ok(content) => {
+                self.detail = Some(Detail {
+                    ws_idx,
+                    windows: self.script_windows(&content),
+                    selected: 0,
+                    expand_env: false,
+                    disabled: BTreeSet::new(),
+                    grid: false,
+                    monitor: None,
+                });
             }
+            Err(err) => self.toast(format!("Cannot read script: {err}")),
         }
     }
 
-    disable_raw_mode()?;
-    execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
-    terminal.show_cursor()?;
+    /// Open the selected window of the detail view in the current workspace.
+    fn test_window(&mut self) {
+        let Some(window) = self
+            .detail
+            .as_ref()
+            .and_then(|detail| detail.windows.get(detail.selected))
+        else {
+            return;
+        };
+
+        let result = hyprctl::dispatch_exec(
+            self.options.instance.as_deref(),
+            &window.rules_without_workspace(),
+            &window.exec_command(),
+        );
+
+        let message = match result {
+            Ok(()) => format!(
+                "Opened {} in the current workspace",
+                window.program().unwrap_or("window")
+            ),
+            Err(err) => format!("Failed to open window: {err}"),
+        };
+        self.toast(message);
+    }
+
+    /// Trace the script at `idx` in a terminal of its own (`bash -x`), the TUI staying open.
+    fn debug_script(&mut self, idx: usize) {
+        let ws = &self.workspaces[idx];
+        let message = match open_debug_terminal(ws, self.options.instance.as_deref()) {
+            Ok(terminal) => format!("Tracing {} in {terminal}", ws.base_name),
+            Err(err) => format!("Could not open a terminal: {err}"),
+        };
+        self.toast(message);
+    }
+
+    /// Handle a key press while the detail view is open.
+    fn handle_detail_key(&mut self, key: KeyEvent) {
+        let Some(detail) = self.detail.as_mut() else {
+            return;
+        };
+        let count = detail.windows.len();
+
+        match key.code {
+            KeyCode::Esc | KeyCode::Left | KeyCode::Char('h') => self.detail = None,
+            KeyCode::Char('q') => self.request_quit(),
+            KeyCode::Down | KeyCode::Char('j') if count > 0 => {
+                detail.selected = (detail.selected + 1) % count;
+            }
+            KeyCode::Up | KeyCode::Char('k') if count > 0 => {
+                detail.selected = (detail.selected + count - 1) % count;
+            }
+            KeyCode::Char('t') => self.test_window(),
+            KeyCode::Char('w') | KeyCode::Char('W') => {
+                let Some(window) = detail.windows.get(detail.selected) else {
+                    return;
+                };
+                let url = if key.code == KeyCode::Char('w') {
+                    window
+                        .main_rule_keyword()
+                        .map_or(docs::WINDOW_RULES_URL.to_string(), docs::rule_doc_url)
+                } else if let Some(message) = &window.layout_hint {
+                    docs::layoutmsg_doc_url(message).to_string()
+                } else {
+                    docs::DISPATCHERS_URL.to_string()
+                };
+                let message = match docs::open_url(&url) {
+                    Ok(()) => format!("Opened {url}"),
+                    Err(err) => format!("Could not open {url}: {err}"),
+                };
+                self.toast(message);
+            }
+            KeyCode::Char('e') => detail.expand_env = !detail.expand_env,
+            KeyCode::Char('g') => {
+                detail.grid = !detail.grid;
+                if detail.grid && detail.monitor.is_none() {
+                    detail.monitor =
+                        hyprctl::focused_monitor_size(self.options.instance.as_deref())
+                            .ok()
+                            .flatten();
+                }
+            }
+            KeyCode::Char(' ') if count > 0 => {
+                if detail.disabled.contains(&detail.selected) {
+                    detail.disabled.remove(&detail.selected);
+                } else {
+                    detail.disabled.insert(detail.selected);
+                }
+            }
+            KeyCode::Enter => {
+                let ws_idx = detail.ws_idx;
+                let enabled: Vec<usize> = (0..count)
+                    .filter(|idx| !detail.disabled.contains(idx))
+                    .collect();
+                if enabled.len() == count {
+                    self.enter_on(ws_idx, key.modifiers);
+                } else if self.options.pick {
+                    self.toast(
+                        "Picking returns the whole script, enable every window first".to_string(),
+                    );
+                } else if enabled.is_empty() {
+                    self.toast("Every window is disabled, nothing to launch".to_string());
+                } else {
+                    self.action = Some(Action::LaunchWindows(ws_idx, enabled));
+                    self.quit = true;
+                }
+            }
+            KeyCode::Char('c') => {
+                let ws_idx = detail.ws_idx;
+                self.copy_path(ws_idx);
+            }
+            KeyCode::Char('u') if self.options.read_only => {
+                self.toast("Read-only mode: converting units is unavailable".to_string());
+            }
+            // The prompts talk on stdout, which a picker must keep clean
+            KeyCode::Char('u') if self.options.pick => {
+                self.toast("Converting units is unavailable while picking".to_string());
+            }
+            KeyCode::Char('u') if !detail.windows.iter().any(|w| w.unit.is_some()) => {
+                self.toast("No numeric size or move values to convert".to_string());
+            }
+            KeyCode::Char('u') => {
+                self.action = Some(Action::ConvertUnits(detail.ws_idx));
+                self.quit = true;
+            }
+            _ => {}
+        }
+    }
+
+    /// Handle a key press in the current mode.
+    fn handle_key(&mut self, key: KeyEvent) {
+        // Raw mode turns Ctrl-C into a key press instead of SIGINT: quit from any mode
+        if key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('c') {
+            self.action = None;
+            self.quit = true;
+            return;
+        }
+
+        // Asked from the detail view too, so answered before any view gets the key
+        if let Some(confirm) = self.confirm.take() {
+            if key.code == KeyCode::Char('y') {
+                match confirm {
+                    Confirm::DeleteChecked => self.delete_checked(),
+                    Confirm::Launch(idx, stay) | Confirm::LaunchCurrent(idx, _, stay) => {
+                        self.action = Some(launch_action(idx, stay));
+                        self.quit = true;
+                    }
+                    Confirm::Quit => {
+                        self.action = None;
+                        self.quit = true;
+                    }
+                }
+            }
+            return;
+        }
+
+        if self.diff.is_some() {
+            self.handle_diff_key(key);
+            return;
+        }
+
+        if self.parse_view.is_some() {
+            self.handle_parse_key(key);
+            return;
+        }
+
+        if self.move_view.is_some() {
+            self.handle_move_key(key);
+            return;
+        }
+
+        if self.detail.is_some() {
+            self.handle_detail_key(key);
+            return;
+        }
+
+        if self.filter_active {
+            match key.code {
+                KeyCode::Esc => self.clear_filter(),
+                KeyCode::Enter => self.filter_active = false,
+                KeyCode::Backspace => {
+                    self.filter.pop();
+                    self.apply_filter();
+                }
+                KeyCode::Down => self.next(),
+                KeyCode::Up => self.previous(),
+                KeyCode::Char(c) => {
+                    self.filter.push(c);
+                    self.apply_filter();
+                }
+                _ => {}
+            }
+            return;
+        }
+
+        // Before the plain keys, which ignore modifiers
+        if key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('d') {
+            if let Some(idx) = self.selected_workspace() {
+                self.show_parse_report(idx);
+            }
+            return;
+        }
+
+        // Any key other than `g` cancels a pending `gg`
+        if key.code != KeyCode::Char('g') {
+            self.pending_g = None;
+        }
+
+        match key.code {
+            KeyCode::Esc if self.select_mode => {
+                self.exit_select_mode();
+            }
+            KeyCode::Esc if !self.filter.is_empty() => {
+                self.clear_filter();
+            }
+            KeyCode::Char('q') | KeyCode::Esc => self.request_quit(),
+            KeyCode::Char('/') => {
+                self.filter_active = true;
+            }
+            KeyCode::Char(' ') if self.options.read_only => {
+                self.toast(
+                    "Read-only mode: selecting scripts to delete is unavailable".to_string(),
+                );
+            }
+            KeyCode::Char(' ') => {
+                self.toggle_checked();
+            }
+            KeyCode::Right | KeyCode::Char('l') => {
+                self.open_detail();
+            }
+            KeyCode::Char('c') => {
+                if let Some(idx) = self.selected_workspace() {
+                    self.copy_path(idx);
+                }
+            }
+            KeyCode::Char('p') => {
+                self.toggle_pinned();
+            }
+            KeyCode::Char('m') => {
+                if let Some(idx) = self.selected_workspace() {
+                    self.mark_or_compare(idx);
+                }
+            }
+            KeyCode::Char('M') if self.options.read_only => {
+                self.toast("Read-only mode: moving scripts is unavailable".to_string());
+            }
+            KeyCode::Char('M') => {
+                if let Some(idx) = self.selected_workspace() {
+                    self.open_move(idx);
+                }
+            }
+            KeyCode::Char('s') if self.options.pick => {
+                self.toast("Switching is unavailable while picking".to_string());
+            }
+            KeyCode::Char('s') => {
+                if let Some(idx) = self.selected_workspace() {
+                    self.request_switch(idx);
+                }
+            }
+            KeyCode::Char('a') if self.options.read_only => {
+                self.toast("Read-only mode: archiving scripts is unavailable".to_string());
+            }
+            KeyCode::Char('a') => {
+                if let Some(idx) = self.selected_workspace() {
+                    self.toggle_archive(idx);
+                }
+            }
+            KeyCode::Char('A') => {
+                self.toggle_archived_view();
+            }
+            KeyCode::Char('P') => self.next_profile(),
+            KeyCode::Char('D') if self.options.read_only => {
+                self.toast("Read-only mode: duplicating scripts is unavailable".to_string());
+            }
+            // The prompts talk on stdout, which a picker must keep clean
+            KeyCode::Char('D') if self.options.pick => {
+                self.toast("Duplicating is unavailable while picking".to_string());
+            }
+            KeyCode::Char('D') => {
+                if let Some(idx) = self.selected_workspace() {
+                    self.action = Some(Action::Duplicate(idx));
+                    self.quit = true;
+                }
+            }
+            KeyCode::Char('T') if self.options.pick => {
+                self.toast("Debugging is unavailable while picking".to_string());
+            }
+            KeyCode::Char('T') => {
+                if let Some(idx) = self.selected_workspace() {
+                    self.debug_script(idx);
+                }
+            }
+            KeyCode::Char('x') if self.options.read_only => {
+                self.toast("Read-only mode: changing permissions is unavailable".to_string());
+            }
+            KeyCode::Char('x') => {
+                if let Some(idx) = self.selected_workspace() {
+                    self.toggle_executable(idx);
+                }
+            }
+            KeyCode::Char('d') if self.select_mode && !self.checked.is_empty() => {
+                if self.options.config.confirm.delete && !assume_yes() {
+                    self.confirm = Some(Confirm::DeleteChecked);
+                } else {
+                    self.delete_checked();
+                }
+            }
+            KeyCode::Down | KeyCode::Char('j') => {
+                self.next();
+            }
+            KeyCode::Up | KeyCode::Char('k') => {
+                self.previous();
+            }
+            KeyCode::Char('v') => {
+                self.compact = !self.compact;
+            }
+            KeyCode::Char('g') => {
+                self.press_g();
+            }
+            KeyCode::Char('G') => {
+                self.last();
+            }
+            KeyCode::Char(c @ '0'..='9') => match self.options.config.digit_keys {
+                DigitKeys::Jump => self.jump_to_number(c),
+                DigitKeys::Launch => self.launch_nth(c as usize - '0' as usize),
+            },
+            KeyCode::Enter => match self.selected_workspace() {
+                Some(idx) => self.enter_on(idx, key.modifiers),
+                // The wizard talks on stdout, which a picker must keep clean
+                None if self.options.pick => {
+                    self.toast("Pick an existing script, or q to cancel".to_string())
+                }
+                None if !self.has_create_row() => {}
+                None => {
+                    self.action = Some(Action::CreateNew(self.options.dirs[0].clone()));
+                    self.quit = true;
+                }
+            },
+            _ => {}
+        }
+    }
+
+    /// Earliest instant at which a pending timer needs attention, if any.
+    fn next_deadline(&self) -> Option<Instant> {
+        let pending_g = self.pending_g.map(|at| at + PENDING_G_TIMEOUT);
+        let toast = self.status.as_ref().map(|(_, at)| *at + TOAST_DURATION);
+        let watch = self.watch.as_ref().map(|watch| watch.next_check);
+        pending_g.into_iter().chain(toast).chain(watch).min()
+    }
+
+    /// Drop timed state (a stray `g`, an old toast) once it has waited too long.
+    fn expire_timers(&mut self) {
+        if let Some(at) = self.pending_g
+            && at.elapsed() > PENDING_G_TIMEOUT
+        {
+            self.pending_g = None;
+        }
+        if let Some((_, at)) = &self.status
+            && at.elapsed() >= TOAST_DURATION
+        {
+            self.status = None;
+        }
+        if self
+            .watch
+            .as_ref()
+            .is_some_and(|watch| Instant::now() >= watch.next_check)
+        {
+            self.check_watch();
+        }
+    }
+}
+
+/// Split `text` into spans, emphasizing the characters that fuzzy-match `query`.
+fn highlight<'a>(text: &'a str, query: &str) -> Vec<Span<'a>> {
+    let Some(found) = fuzzy_match(text, query) else {
+        return vec![Span::raw(text)];
+    };
+    let style = Style::default()
+        .fg(Color::Yellow)
+        .add_modifier(Modifier::BOLD);
+
+    // Merge adjacent matched characters into a single styled span
+    let mut spans = Vec::new();
+    let mut plain_from = 0;
+    let mut positions = found.positions.iter().peekable();
+    while let Some(&start) = positions.next() {
+        let mut end = start + text[start..].chars().next().map_or(1, char::len_utf8);
+        while let Some(&&next) = positions.peek() {
+            if next != end {
+                break;
+            }
+            end += text[next..].chars().next().map_or(1, char::len_utf8);
+            positions.next();
+        }
+        if plain_from < start {
+            spans.push(Span::raw(&text[plain_from..start]));
+        }
+        spans.push(Span::styled(&text[start..end], style));
+        plain_from = end;
+    }
+    if plain_from < text.len() {
+        spans.push(Span::raw(&text[plain_from..]));
+    }
+    spans
+}
+
+/// Split `text` into chunks of at most `width` columns, preferring to break at spaces.
+fn wrap_text(text: &str, width: usize) -> Vec<String> {
+    let width = width.max(1);
+    let mut lines = Vec::new();
+    let mut rest = text;
+
+    while display_width(rest) > width {
+        // Byte offset of the first character that would overflow, keeping at least one
+        let mut used = 0;
+        let limit = rest
+            .char_indices()
+            .find(|&(i, c)| {
+                used += c.width().unwrap_or(0);
+                used > width && i > 0
+            })
+            .map_or(rest.len(), |(i, _)| i);
+        let cut = rest[..limit].rfind(' ').filter(|&i| i > 0).unwrap_or(limit);
+        lines.push(rest[..cut].to_string());
+        rest = rest[cut..].trim_start();
+    }
+    lines.push(rest.to_string());
+
+    lines
+}
+
+/// Draw the detail view of a script: its metadata and windows
+fn render_detail(f: &mut Frame, app: &App, detail: &Detail, area: Rect) {
+    let ws = &app.workspaces[detail.ws_idx];
+    let dim = Style::default().fg(Color::DarkGray);
+
+    let mut lines = vec![
+        Line::from(vec![
+            Span::styled("Path: ", dim),
+            Span::raw(ws.full_path.to_string_lossy()),
+        ]),
+        Line::from(vec![
+            Span::styled("Workspace: ", dim),
+            Span::raw(
+                ws.workspace()
+                    .map(|w| w.rule_target())
+                    .unwrap_or_else(|| "?".to_string()),
+            ),
+        ]),
+    ];
+    if let Some(desc) = &ws.description {
+        lines.push(Line::from(vec![
+            Span::styled("Description: ", dim),
+            Span::raw(desc.as_str()),
+        ]));
+    }
+    lines.push(Line::raw(""));
+    let expanded = if detail.expand_env {
+        ", variables expanded"
+    } else {
+        ""
+    };
+    let count = detail.windows.len();
+    let windows = if detail.disabled.is_empty() {
+        format!("{count} window(s){expanded}")
+    } else {
+        format!(
+            "{} of {count} window(s) enabled{expanded} • Enter launches only those",
+            count - detail.disabled.len()
+        )
+    };
+    lines.push(Line::styled(
+        windows,
+        Style::default().add_modifier(Modifier::BOLD),
+    ));
+
+    let header_height = lines.len() as u16 + 1;
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(header_height), Constraint::Min(1)])
+        .split(area);
+
+    let title = format!("Hyprspace • {}", ws.base_name);
+    let header = Paragraph::new(lines).block(
+        Block::default()
+            .title(title)
+            .borders(Borders::TOP | Borders::LEFT | Borders::RIGHT),
+    );
+    f.render_widget(header, chunks[0]);
+
+    // Long commands wrap onto indented continuation lines instead of being cut off;
+    // the width leaves room for the borders, the highlight symbol and the indent
+    let text_width = usize::from(chunks[1].width.saturating_sub(7));
+    let items: Vec<ListItem> = detail
+        .windows
+        .iter()
+        .enumerate()
+        .map(|(idx, window)| {
+            let mut lines = Vec::new();
+            if let Some(seconds) = &window.delay {
+                lines.push(Line::styled(format!("   ⏱ sleep {seconds}"), dim));
+            }
+            if let Some(message) = &window.layout_hint {
+                lines.push(Line::styled(format!("   ⊞ layoutmsg {message}"), dim));
+            }
+            if let Some(dir) = &window.cwd {
+                lines.push(Line::styled(format!("   ⌂ in {dir}"), dim));
+            }
+            if window.focus {
+                lines.push(Line::styled("   ◎ focused after launch", dim));
+            }
+            let command = if detail.expand_env {
+                expand_env_vars(&window.command)
+            } else {
+                window.command.clone()
+            };
+            // Disabled windows are struck through
+            let style = if detail.disabled.contains(&idx) {
+                dim.add_modifier(Modifier::CROSSED_OUT)
+            } else {
+                Style::default()
+            };
+            for (line_idx, part) in wrap_text(&command, text_width).iter().enumerate() {
+                if line_idx == 0 {
+                    lines.push(Line::styled(format!("{}. {part}", idx + 1), style));
+                } else {
+                    lines.push(Line::styled(format!("   {part}"), style));
+                }
+            }
+            let focus = if window.is_silent() {
+                ""
+            } else {
+                " · takes focus"
+            };
+            let unit = window
+                .unit
+                .map(|unit| format!(" · {}", unit.label()))
+                .unwrap_or_default();
+            let rules = format!("[{}]{unit}{focus}", window.rules);
+            for part in wrap_text(&rules, text_width) {
+                lines.push(Line::styled(format!("   {part}"), dim));
+            }
+            ListItem::new(lines)
+        })
+        .collect();
+
+    let list = List::new(items)
+        .block(Block::default().borders(Borders::BOTTOM | Borders::LEFT | Borders::RIGHT))
+        .highlight_style(
+            Style::default()
+                .fg(Color::Cyan)
+                .add_modifier(Modifier::BOLD),
+        )
+        .highlight_symbol("➤ ");
+
+    let mut state = ListState::default();
+    if !detail.windows.is_empty() {
+        state.select(Some(detail.selected));
+    }
+
+    if !detail.grid {
+        f.render_stateful_widget(list, chunks[1], &mut state);
+        return;
+    }
+
+    // Terminal cells are about twice as tall as wide: halve the rows to keep the
+    // monitor's aspect ratio, leaving some room for the windows above
+    let (width, height) = detail.monitor.unwrap_or((16, 9));
+    let cols = chunks[1].width.saturating_sub(2);
+    let max_rows = (chunks[1].height / 2).saturating_sub(2).max(2);
+    let rows = ((u32::from(cols) * height / width.max(1) / 2) as u16).clamp(2, max_rows);
+    let parts = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(1), Constraint::Length(rows + 2)])
+        .split(chunks[1]);
+    f.render_stateful_widget(list, parts[0], &mut state);
+
+    let grid = layout_grid(
+        &detail.windows,
+        detail.monitor,
+        usize::from(cols),
+        usize::from(rows),
+        Some(detail.selected),
+    );
+    let title = match detail.monitor {
+        Some((width, height)) => format!("Layout • {width}×{height}"),
+        None => "Layout • 16:9, monitor size unknown".to_string(),
+    };
+    let mut block = Block::default()
+        .title(title)
+        .borders(Borders::ALL)
+        .border_style(dim);
+    let missing: Vec<String> = detail
+        .windows
+        .iter()
+        .enumerate()
+        .filter(|(_, window)| placement(window, detail.monitor).is_none())
+        .map(|(idx, _)| (idx + 1).to_string())
+        .collect();
+    if !missing.is_empty() {
+        block = block.title_bottom(format!(
+            "not drawn (tiled or no size): {}",
+            missing.join(", ")
+        ));
+    }
+    let lines: Vec<Line> = grid.into_iter().map(Line::raw).collect();
+    f.render_widget(Paragraph::new(lines).block(block), parts[1]);
+}
+
+/// Draw the list of workspace scripts
+fn render_list(f: &mut Frame, app: &App, area: Rect) {
+    let settings = &app.options.config.tui;
+    let count = app.workspaces.len().to_string();
+    let mut title = if app.archived {
+        format!(
+            "Hyprspace • {} archived configuration(s)",
+            app.workspaces.len()
+        )
+    } else if let Some(template) = &settings.title {
+        let mut title = fill_template(template, &[("count", &count)]);
+        if !app.filter.is_empty() {
+            title.push_str(&format!(" • {} match", app.visible.len()));
+        }
+        title
+    } else if app.filter.is_empty() {
+        format!(
+            "Hyprspace • {} configuration(s) found",
+            app.workspaces.len()
+        )
+    } else {
+        format!(
+            "Hyprspace • {} of {} configuration(s) match",
+            app.visible.len(),
+            app.workspaces.len()
+        )
+    };
+    if let Some(profile) = &app.options.profile {
+        title.push_str(&format!(" • profile {profile}"));
+    }
+    if app.options.read_only {
+        title.push_str(" • read-only");
+    }
+
+    let mut block = Block::default().title(title).borders(Borders::ALL);
+    if let Some(template) = &settings.subtitle {
+        block = block.title_bottom(fill_template(template, &[("count", &count)]));
+    }
+
+    // Origin is only worth showing once scripts come from several directories
+    let collisions = name_collisions(&app.workspaces);
+    let multiple_sources = has_multiple_sources(&app.workspaces);
+
+    // Pad badges and row numbers so the names line up in one column, measured over
+    // every script so the column doesn't shift while filtering
+    let width = badge_width(&app.workspaces);
+    let (icon_width, name_width) = column_widths(&app.workspaces);
+    let index_width = app.visible.len().to_string().len();
+
+    // Build list items: visible workspaces + one "Create new" entry
+    let mut items: Vec<ListItem> = app
+        .visible
+        .iter()
+        .enumerate()
+        .map(|(pos, &idx)| {
+            let ws = &app.workspaces[idx];
+            let ws_info = if ws.read_error.is_some() {
+                Span::styled(badge(ws, width), Style::default().fg(Color::Red))
+            } else {
+                Span::raw(badge(ws, width))
+            };
+
+            let mut spans = Vec::new();
+            if app.select_mode {
+                let mark = if app.checked.contains(&idx) {
+                    "[x] "
+                } else {
+                    "[ ] "
+                };
+                spans.push(Span::raw(mark));
+            }
+            if app.marked == Some(idx) {
+                spans.push(Span::styled("● ", Style::default().fg(Color::Magenta)));
+            } else if app.marked.is_some() {
+                spans.push(Span::raw("  "));
+            }
+            let occupied = ws
+                .workspace_num
+                .filter(|num| app.occupied.contains(num))
+                .map(|_| Span::styled(" (occupied)", Style::default().fg(Color::Yellow)));
+            if app.compact {
+                spans.extend([ws_info, Span::raw(" ")]);
+                spans.extend(highlight(&ws.name_short, &app.filter));
+                spans.extend(occupied);
+                return ListItem::new(Line::from(spans));
+            }
+
+            spans.extend([
+                Span::raw(format!("{:>index_width$}. ", pos + 1)),
+                ws_info,
+                Span::raw(" "),
+            ]);
+            spans.push(Span::raw(icon_cell(ws, icon_width)));
+            spans.extend(highlight(&ws.name_short, &app.filter));
+            spans.push(Span::raw(
+                " ".repeat(name_width.saturating_sub(display_width(&ws.name_short))),
+            ));
+            spans.push(Span::raw(" ("));
+            spans.extend(highlight(&ws.base_name, &app.filter));
+            spans.push(Span::raw(")"));
+            spans.extend(occupied);
+
+            if multiple_sources {
+                spans.push(Span::styled(
+                    format!(" @ {}", ws.source_dir.to_string_lossy()),
+                    Style::default().fg(Color::DarkGray),
+                ));
+            }
+            if collisions.contains(&ws.name_short) {
+                spans.push(Span::styled(
+                    " ⚠ name used in several directories",
+                    Style::default().fg(Color::Yellow),
+                ));
+            }
+
+            if let Some(err) = &ws.read_error {
+                spans.push(Span::styled(
+                    format!(" unreadable: {err}"),
+                    Style::default().fg(Color::Red),
+                ));
+            }
+
+            if let Some(summary) = programs_summary(ws) {
+                spans.push(Span::styled(
+                    format!(" {summary}"),
+                    Style::default().fg(Color::DarkGray),
+                ));
+            }
+            if let Some(desc) = &ws.description {
+                spans.push(Span::styled(
+                    format!(" — {desc}"),
+                    Style::default().fg(Color::DarkGray),
+                ));
+            }
+            ListItem::new(Line::from(spans))
+        })
+        .collect();
+
+    // Section headers are display-only rows, shift the selection past them
+    let pinned_count = app.pinned_visible();
+    let mut display_selected = app.selected;
+    if pinned_count > 0 {
+        let header = |label: &str| {
+            ListItem::new(Line::styled(
+                format!("── {label} ──"),
+                Style::default().fg(Color::DarkGray),
+            ))
+        };
+        items.insert(pinned_count, header("All"));
+        items.insert(0, header("★ Pinned"));
+        display_selected += match app.selected_position() {
+            Some(pos) if pos < pinned_count => 1,
+            Some(_) => 2,
+            // "Create new..." is above both headers on top, below them at the bottom
+            None if app.row_offset() > 0 => 0,
+            None => 2,
+        };
+    }
+
+    if app.has_create_row() {
+        let create_label = "Create new workspace script…";
+        if app.row_offset() > 0 {
+            items.insert(0, ListItem::new(create_label));
+        } else {
+            items.push(ListItem::new(create_label));
+        }
+    }
+
+    let list = List::new(items)
+        .block(block)
+        .highlight_style(
+            Style::default()
+                .fg(Color::Cyan)
+                .add_modifier(Modifier::BOLD),
+        )
+        .highlight_symbol("➤ ");
+
+    let mut state = ListState::default();
+    if app.total_items() > 0 {
+        state.select(Some(display_selected));
+    }
+
+    f.render_stateful_widget(list, area, &mut state);
+}
+
+/// Rectangle of `percent_x` by `percent_y` of `area`, centered in it
+fn centered_rect(percent_x: u16, percent_y: u16, area: Rect) -> Rect {
+    let vertical = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Percentage((100 - percent_y) / 2),
+            Constraint::Percentage(percent_y),
+            Constraint::Percentage((100 - percent_y) / 2),
+        ])
+        .split(area);
+
+    Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([
+            Constraint::Percentage((100 - percent_x) / 2),
+            Constraint::Percentage(percent_x),
+            Constraint::Percentage((100 - percent_x) / 2),
+        ])
+        .split(vertical[1])[1]
+}
+
+/// Draw the diff popup over the rest of the interface
+fn render_diff(f: &mut Frame, diff: &DiffView, area: Rect) {
+    let popup = centered_rect(90, 90, area);
+
+    let lines: Vec<Line> = diff_lines(&diff.old, &diff.new)
+        .into_iter()
+        .map(|line| match line {
+            DiffLine::Same(text) => Line::raw(format!("  {text}")),
+            DiffLine::Removed(text) => {
+                Line::styled(format!("- {text}"), Style::default().fg(Color::Red))
+            }
+            DiffLine::Added(text) => {
+                Line::styled(format!("+ {text}"), Style::default().fg(Color::Green))
+            }
+        })
+        .collect();
+
+    let title = format!("- {}  + {}", diff.names.0, diff.names.1);
+    let paragraph = Paragraph::new(lines)
+        .block(Block::default().title(title).borders(Borders::ALL))
+        .scroll((diff.scroll, 0));
+
+    f.render_widget(Clear, popup);
+    f.render_widget(paragraph, popup);
+}
+
+/// Draw the parser report popup over the rest of the interface
+fn render_parse_view(f: &mut Frame, view: &ParseView, area: Rect) {
+    let popup = centered_rect(90, 90, area);
+
+    let mut lines = vec![
+        Line::styled(
+            view.summary.clone(),
+            Style::default().add_modifier(Modifier::BOLD),
+        ),
+        Line::raw(""),
+    ];
+    let width = view
+        .notes
+        .iter()
+        .map(|note| note.lines.len())
+        .max()
+        .unwrap_or(0);
+    lines.extend(view.notes.iter().map(|note| {
+        let text = format!("{:>width$}  {}", note.lines, note.text);
+        if note.skipped {
+            Line::styled(text, Style::default().fg(Color::Yellow))
+        } else {
+            Line::raw(text)
+        }
+    }));
+
+    let title = format!("How {} is parsed", view.name);
+    let paragraph = Paragraph::new(lines)
+        .block(Block::default().title(title).borders(Borders::ALL))
+        .wrap(Wrap { trim: false })
+        .scroll((view.scroll, 0));
+
+    f.render_widget(Clear, popup);
+    f.render_widget(paragraph, popup);
+}
+
+/// The footer text and its style: shortcuts, or the current prompt / status
+fn footer(app: &App) -> (String, Style) {
+    let hint = Style::default().fg(Color::DarkGray);
+    if let Some(confirm) = app.confirm {
+        let question = match confirm {
+            Confirm::DeleteChecked => {
+                format!(
+                    "Move {} checked script(s) to the trash? [y/N]",
+                    app.checked.len()
+                )
+            }
+            Confirm::Launch(idx, _) => format!("Launch {}? [y/N]", app.workspaces[idx].base_name),
+            Confirm::Quit => "Discard unsaved changes? [y/N]".to_string(),
+            Confirm::LaunchCurrent(idx, num, _) => format!(
+                "{} targets workspace {num}, the one you are on. Launch here anyway? [y/N]",
+                app.workspaces[idx].base_name
+            ),
+        };
+        (question, Style::default().fg(Color::Yellow))
+    } else if app.filter_active {
+        (format!("/{}█", app.filter), Style::default())
+    } else if let Some((status, _)) = &app.status {
+        (status.clone(), Style::default())
+    } else if app.move_view.is_some() {
+        let footer_text = "↑/↓ or j/k: choose directory  • Enter: move  • Esc/q: cancel";
+        (footer_text.to_string(), hint)
+    } else if app.diff.is_some() || app.parse_view.is_some() {
+        let footer_text = "↑/↓ or j/k: scroll  • g: top  • Esc/q: close";
+        (footer_text.to_string(), hint)
+    } else if app.detail.is_some() {
+        let enter = if app.options.pick {
+            "Enter: pick"
+        } else {
+            ENTER_MATRIX
+        };
+        let footer_text = format!(
+            "{enter}  • ↑/↓ or j/k: select window  • t: open in current workspace  • w/W: rule/dispatcher docs  • Space: toggle window  • e: expand $VARS  • g: layout grid  • u: percent ⇄ pixels  • c: copy path  • Esc/h: back  • q: quit"
+        );
+        (footer_text, hint)
+    } else if app.select_mode {
+        let footer_text = "Space: toggle  • d: trash checked  • Esc: cancel selection";
+        (footer_text.to_string(), hint)
+    } else {
+        // The modifier matrix only once a script is selected
+        let enter = match app.selected_workspace() {
+            _ if app.options.pick => "Enter: pick",
+            Some(_) => ENTER_MATRIX,
+            None => "Enter: create",
+        };
+        let select = if app.options.read_only {
+            "Space: select (unavailable)"
+        } else {
+            "Space: select"
+        };
+        let digits = match app.options.config.digit_keys {
+            DigitKeys::Jump => "0-9: jump to ws",
+            DigitKeys::Launch => "1-9: launch Nth",
+        };
+        let footer_text = format!(
+            "{enter}  • ↑/↓ or j/k: navigate  • gg/G: top/bottom  • {digits}  • /: filter  • {select}  • l: details  • c: copy path  • p: pin  • x: toggle exec  • m: mark/compare  • M: move  • a/A: archive/show archived  • P: next profile  • D: duplicate  • T: trace in a terminal  • Ctrl+D: parser report  • v: compact  • s: switch only  • q or Esc: quit"
+        );
+        (footer_text, hint)
+    }
+}
+
+/// Lay out a footer on lines of at most `width` columns, breaking between its `  • `
+/// separated shortcuts rather than inside one.
+fn footer_lines(text: &str, width: usize) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    for item in text.split("  • ") {
+        match lines.last_mut() {
+            Some(line) if display_width(line) + 4 + display_width(item) <= width => {
+                line.push_str("  • ");
+                line.push_str(item);
+            }
+            _ => lines.extend(wrap_text(item, width)),
+        }
+    }
+    lines
+}
+
+/// Draw the popup listing the directories a script can be moved to
+fn render_move(f: &mut Frame, app: &App, view: &MoveView, area: Rect) {
+    let popup = centered_rect(60, 40, area);
+
+    let items: Vec<ListItem> = view
+        .choices
+        .iter()
+        .map(|dir| ListItem::new(dir.to_string_lossy().into_owned()))
+        .collect();
+
+    let title = format!("Move {} to", app.workspaces[view.ws_idx].base_name);
+    let list = List::new(items)
+        .block(Block::default().title(title).borders(Borders::ALL))
+        .highlight_style(
+            Style::default()
+                .fg(Color::Cyan)
+                .add_modifier(Modifier::BOLD),
+        )
+        .highlight_symbol("➤ ");
+
+    let mut state = ListState::default();
+    state.select(Some(view.selected));
+
+    f.render_widget(Clear, popup);
+    f.render_stateful_widget(list, popup, &mut state);
+}
+
+/// Draw the UI for the current app state
+fn ui(f: &mut Frame, app: &App) {
+    let area = f.area();
+
+    // Split screen: main area + footer, as tall as its shortcuts need (at least two
+    // lines, at most half the screen)
+    let (footer_text, footer_style) = footer(app);
+    let footer_lines = footer_lines(&footer_text, usize::from(area.width));
+    let footer_height = u16::try_from(footer_lines.len())
+        .unwrap_or(u16::MAX)
+        .clamp(2, (area.height / 2).max(2));
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(
+            [
+                Constraint::Min(3),                // main area
+                Constraint::Length(footer_height), // footer
+            ]
+            .as_ref(),
+        )
+        .split(area);
+
+    match &app.detail {
+        Some(detail) => render_detail(f, app, detail, chunks[0]),
+        None => render_list(f, app, chunks[0]),
+    }
+
+    if let Some(diff) = &app.diff {
+        render_diff(f, diff, chunks[0]);
+    }
+
+    if let Some(view) = &app.parse_view {
+        render_parse_view(f, view, chunks[0]);
+    }
+
+    if let Some(view) = &app.move_view {
+        render_move(f, app, view, chunks[0]);
+    }
+
+    let lines: Vec<Line> = footer_lines.into_iter().map(Line::from).collect();
+    f.render_widget(Paragraph::new(lines).style(footer_style), chunks[1]);
+}
+
+/// Lines taken below the prompt by the `--inline` picker
+const INLINE_HEIGHT: u16 = 14;
+
+/// Puts the terminal in TUI mode and restores it when dropped, on every exit path.
+///
+/// The interface is drawn on stderr so stdout stays free for `hyprspace pick` output.
+struct TerminalGuard {
+    /// Inline mode leaves the main screen in place
+    inline: bool,
+}
+
+impl TerminalGuard {
+    fn enter(inline: bool) -> io::Result<Self> {
+        enable_raw_mode()?;
+        // From here on, dropping the guard undoes whatever was set up
+        let guard = TerminalGuard { inline };
+        if !inline {
+            execute!(io::stderr(), EnterAlternateScreen)?;
+        }
+        // Lets terminals speaking the kitty keyboard protocol report Shift/Ctrl/Alt+Enter;
+        // the others ignore the sequence and keep sending a plain Enter
+        execute!(
+            io::stderr(),
+            PushKeyboardEnhancementFlags(KeyboardEnhancementFlags::DISAMBIGUATE_ESCAPE_CODES)
+        )?;
+        Ok(guard)
+    }
+}
+
+impl Drop for TerminalGuard {
+    fn drop(&mut self) {
+        let _ = execute!(io::stderr(), PopKeyboardEnhancementFlags);
+        let _ = disable_raw_mode();
+        if self.inline {
+            let _ = execute!(io::stderr(), Show);
+        } else {
+            let _ = execute!(io::stderr(), LeaveAlternateScreen, Show);
+        }
+    }
+}
+
+/// Replace the inline picker with a one-line summary of the choice, left in scrollback.
+fn leave_inline(
+    terminal: &mut Terminal<CrosstermBackend<io::Stderr>>,
+    app: &App,
+) -> io::Result<()> {
+    let chosen = match &app.action {
+        Some(Action::Launch(idx)) => app.workspaces.get(*idx).map(|ws| ws.base_name.clone()),
+        Some(Action::LaunchWindows(idx, windows)) => app
+            .workspaces
+            .get(*idx)
+            .map(|ws| format!("{} ({} window(s))", ws.base_name, windows.len())),
+        Some(Action::Switch(idx)) => app
+            .workspaces
+            .get(*idx)
+            .map(|ws| format!("switch to {}", ws.base_name)),
+        Some(Action::Duplicate(idx)) => app
+            .workspaces
+            .get(*idx)
+            .map(|ws| format!("duplicate {}", ws.base_name)),
+        Some(Action::CreateNew(_)) => Some("Create new workspace script…".to_string()),
+        Some(Action::ConvertUnits(idx)) => app
+            .workspaces
+            .get(*idx)
+            .map(|ws| format!("convert units of {}", ws.base_name)),
+        Some(Action::LaunchAndStay(idx)) => app
+            .workspaces
+            .get(*idx)
+            .map(|ws| format!("{} (back to the list)", ws.base_name)),
+        Some(Action::DryRun(idx)) => app
+            .workspaces
+            .get(*idx)
+            .map(|ws| format!("dry run of {}", ws.base_name)),
+        None => None,
+    };
+
+    terminal.clear()?;
+    if let Some(chosen) = chosen {
+        terminal.insert_before(1, |buf| {
+            Line::from(vec![
+                Span::styled("hyprspace › ", Style::default().fg(Color::Cyan)),
+                Span::raw(chosen),
+            ])
+            .render(buf.area, buf);
+        })?;
+    }
+    Ok(())
+}
+
+/// Enter TUI mode and set up the terminal drawing on stderr, restoring everything on failure.
+fn init_terminal(
+    inline: bool,
+) -> io::Result<(TerminalGuard, Terminal<CrosstermBackend<io::Stderr>>)> {
+    let guard = TerminalGuard::enter(inline)?;
+    let backend = CrosstermBackend::new(io::stderr());
+    let terminal = if inline {
+        Terminal::with_options(
+            backend,
+            TerminalOptions {
+                viewport: Viewport::Inline(INLINE_HEIGHT),
+            },
+        )?
+    } else {
+        Terminal::new(backend)?
+    };
+    Ok((guard, terminal))
+}
+
+/// Run the TUI and return the selected action (launch or create).
+pub fn run_tui(
+    workspaces: Vec<WorkspaceEntry>,
+    options: TuiOptions,
+) -> io::Result<(Vec<WorkspaceEntry>, Option<Action>)> {
+    if !term::supports_tui() {
+        return run_plain_picker(workspaces, &options);
+    }
+
+    let inline = options.inline;
+    // Detection can be wrong (no controlling terminal, odd emulators), the plain picker
+    // still lets the user choose
+    let (guard, mut terminal) = match init_terminal(inline) {
+        Ok(initialized) => initialized,
+        Err(err) => {
+            eprintln!(
+                "hyprspace: could not start the TUI ({err}), falling back to the plain picker"
+            );
+            return run_plain_picker(workspaces, &options);
+        }
+    };
+    let mut app = App::new(workspaces, options);
+
+    let mut redraw = true;
+
+    loop {
+        if redraw {
+            terminal.draw(|f| ui(f, &app))?;
+        }
+
+        // Block until input arrives; only wake up early when a timer is pending
+        let event = match app.next_deadline() {
+            Some(deadline) => {
+                let timeout = deadline.saturating_duration_since(Instant::now());
+                if !event::poll(timeout)? {
+                    app.expire_timers();
+                    redraw = true;
+                    continue;
+                }
+                event::read()?
+            }
+            None => event::read()?,
+        };
+
+        redraw = matches!(event, Event::Key(_) | Event::Resize(_, _));
+
+        if let Event::Key(key_event) = event {
+            app.handle_key(key_event);
+            if app.quit {
+                break;
+            }
+        }
+    }
+
+    if inline {
+        leave_inline(&mut terminal, &app)?;
+    }
+    drop(guard);
+
+    let prefs = UiPrefs {
+        compact: app.compact,
+        filter: app.filter.clone(),
+    };
+    if prefs != app.start_prefs
+        && let Err(err) = save_ui_prefs(&prefs)
+    {
+        eprintln!("Could not save TUI preferences: {err}");
+    }
 
     Ok((app.workspaces, app.action))
 }
@@ -1,10 +1,25 @@
+use std::collections::HashSet;
 use std::env;
+use std::fmt;
 use std::fs;
 use std::io::{self, Write};
 use std::os::unix::fs::PermissionsExt;
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
 
-/// Represents a workspace script found in ~/.config/hyprspace
+use crate::clipboard;
+use crate::config::{Config, GeneratorSettings};
+use crate::docs;
+use crate::hyprctl::{self, HyprlandVersion};
+use crate::script::{
+    Block, ScriptDocument, ScriptSpec, Unit, WindowSpec, build_script_content, fill_template,
+    is_valid_delay, parse_pasted_windows, parse_windows, window_parse_problem,
+    workspace_mismatches,
+};
+use crate::term;
+
+/// Represents a workspace script found in one of the script directories
 #[derive(Debug)]
 pub struct WorkspaceEntry {
     /// Short name (e.g. "backend" for "workspace-backend.sh")
@@ -13,23 +28,277 @@ pub struct WorkspaceEntry {
     pub base_name: String,
     /// Full path to the script
     pub full_path: PathBuf,
+    /// Directory the script was found in
+    pub source_dir: PathBuf,
     /// Parsed workspace number from the script (if found)
     pub workspace_num: Option<u32>,
+    /// Special workspace (scratchpad) the script toggles instead, e.g. "magic" for `special:magic`
+    pub special: Option<String>,
+    /// Human description from a `# hyprspace: <description>` comment (if any)
+    pub description: Option<String>,
+    /// Icon/emoji from a `# hyprspace-icon: <glyph>` comment (if any)
+    pub icon: Option<String>,
+    /// Sort weight from a `# hyprspace-order: <N>` comment (if any)
+    pub order: Option<i32>,
+    /// Why the script could not be read, if it couldn't
+    pub read_error: Option<String>,
+    /// What could not be understood while reading the script (missing workspace line,
+    /// malformed rules, invalid headers)
+    pub warnings: Vec<String>,
+    /// Program of each window the script spawns, in order (e.g. `kitty`); windows whose
+    /// program can't be told are left out
+    pub programs: Vec<String>,
+    /// Number of windows the script spawns
+    pub window_count: usize,
+    /// Oldest Hyprland the script runs on, from a `# hyprspace-min-hyprland: <version>` comment
+    pub min_hyprland: Option<HyprlandVersion>,
 }
 
-/// Returns the path to the ~/.config/hyprspace directory
-pub fn workspace_dir() -> PathBuf {
+/// Metadata parsed out of a script's content
+#[derive(Debug, Default)]
+struct ScriptMeta {
+    workspace_num: Option<u32>,
+    special: Option<String>,
+    description: Option<String>,
+    icon: Option<String>,
+    order: Option<i32>,
+    warnings: Vec<String>,
+    programs: Vec<String>,
+    window_count: usize,
+    min_hyprland: Option<HyprlandVersion>,
+}
+
+impl WorkspaceEntry {
+    /// The script at `path`, nothing read from it yet: its names come from the file name
+    /// (`workspace-<name>.sh`) and its directory from the path, everything else is empty.
+    pub fn new(path: PathBuf) -> Self {
+        let base_name = path
+            .file_name()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .into_owned();
+        let no_ext = base_name.strip_suffix(".sh").unwrap_or(&base_name);
+        let name_short = no_ext
+            .strip_prefix("workspace-")
+            .unwrap_or(no_ext)
+            .to_string();
+
+        WorkspaceEntry {
+            name_short,
+            base_name,
+            source_dir: path.parent().map(Path::to_path_buf).unwrap_or_default(),
+            full_path: path,
+            workspace_num: None,
+            special: None,
+            description: None,
+            icon: None,
+            order: None,
+            read_error: None,
+            warnings: Vec::new(),
+            programs: Vec::new(),
+            window_count: 0,
+            min_hyprland: None,
+        }
+    }
+
+    /// The workspace the script targets: its number, else its special workspace
+    pub fn workspace(&self) -> Option<Workspace> {
+        match (self.workspace_num, &self.special) {
+            (Some(num), _) => Some(Workspace::Numbered(num)),
+            (None, Some(name)) => Some(Workspace::Special(name.clone())),
+            (None, None) => None,
+        }
+    }
+}
+
+/// A workspace a script can target
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Workspace {
+    /// A regular workspace, switched to with `dispatch workspace N`
+    Numbered(u32),
+    /// A special workspace (scratchpad) such as `special:magic`, shown and hidden with
+    /// `dispatch togglespecialworkspace magic`
+    Special(String),
+}
+
+impl Workspace {
+    /// Parse `3`, `special:magic` or a bare `special` (Hyprland's default special workspace).
+    pub fn parse(text: &str) -> Option<Workspace> {
+        let text = text.trim();
+        if text == "special" {
+            return Some(Workspace::Special("special".to_string()));
+        }
+        if let Some(name) = text.strip_prefix("special:") {
+            return is_valid_short_name(name).then(|| Workspace::Special(name.to_string()));
+        }
+        text.parse()
+            .ok()
+            .filter(|&n| n > 0)
+            .map(Workspace::Numbered)
+    }
+
+    /// Dispatcher and argument bringing the workspace up: `workspace 3`, or
+    /// `togglespecialworkspace magic`
+    pub fn dispatch(&self) -> [String; 2] {
+        match self {
+            Workspace::Numbered(num) => ["workspace".to_string(), num.to_string()],
+            Workspace::Special(name) => ["togglespecialworkspace".to_string(), name.clone()],
+        }
+    }
+
+    /// The workspace as Hyprland rules write it, e.g. `3` or `special:magic`
+    pub fn rule_target(&self) -> String {
+        match self {
+            Workspace::Numbered(num) => num.to_string(),
+            Workspace::Special(name) => format!("special:{name}"),
+        }
+    }
+}
+
+impl fmt::Display for Workspace {
+    /// `workspace 3` or `special:magic`, for messages
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Workspace::Numbered(num) => write!(f, "workspace {num}"),
+            Workspace::Special(name) => write!(f, "special:{name}"),
+        }
+    }
+}
+
+/// Which environment variable decided the workspace directory
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DirSource {
+    /// `$HYPRSPACE_DIR`, used as is
+    HyprspaceDir,
+    /// `$XDG_CONFIG_HOME/hyprspace`
+    XdgConfigHome,
+    /// `$HOME/.config/hyprspace`
+    Home,
+}
+
+impl DirSource {
+    /// Name of the variable, for diagnostics
+    pub fn variable(self) -> &'static str {
+        match self {
+            DirSource::HyprspaceDir => "HYPRSPACE_DIR",
+            DirSource::XdgConfigHome => "XDG_CONFIG_HOME",
+            DirSource::Home => "HOME",
+        }
+    }
+}
+
+/// Resolve the workspace directory: `$HYPRSPACE_DIR`, else `$XDG_CONFIG_HOME/hyprspace`,
+/// else `~/.config/hyprspace`. Empty variables count as unset.
+pub fn resolve_workspace_dir() -> (PathBuf, DirSource) {
+    let var = |name| {
+        env::var_os(name)
+            .filter(|v| !v.is_empty())
+            .map(PathBuf::from)
+    };
+
+    if let Some(dir) = var("HYPRSPACE_DIR") {
+        return (dir, DirSource::HyprspaceDir);
+    }
+    if let Some(config_home) = var("XDG_CONFIG_HOME") {
+        return (config_home.join("hyprspace"), DirSource::XdgConfigHome);
+    }
     let home = env::var("HOME").expect("HOME environment variable not set");
-    let mut p = PathBuf::from(home);
-    p.push(".config");
-    p.push("hyprspace");
-    p
+    (
+        PathBuf::from(home).join(".config").join("hyprspace"),
+        DirSource::Home,
+    )
+}
+
+/// Returns the path to the workspace directory (~/.config/hyprspace by default)
+pub fn workspace_dir() -> PathBuf {
+    resolve_workspace_dir().0
+}
+
+/// Folder of the workspace directory holding one directory per profile
+pub const PROFILES_DIR: &str = "profiles";
+
+/// Directory of the profile `name` (~/.config/hyprspace/profiles/<name>)
+pub fn profile_dir(name: &str) -> PathBuf {
+    workspace_dir().join(PROFILES_DIR).join(name)
+}
+
+/// Names of the existing profiles, sorted
+pub fn list_profiles() -> Vec<String> {
+    let Ok(entries) = fs::read_dir(workspace_dir().join(PROFILES_DIR)) else {
+        return Vec::new();
+    };
+    let mut names: Vec<String> = entries
+        .flatten()
+        .filter(|entry| entry.path().is_dir())
+        .filter_map(|entry| entry.file_name().into_string().ok())
+        .filter(|name| !name.starts_with('.'))
+        .collect();
+    names.sort();
+    names
+}
+
+/// Directory of the profile `name`, which must exist: profiles are made with `mkdir`,
+/// so a typo doesn't silently start an empty one.
+pub fn find_profile(name: &str) -> io::Result<PathBuf> {
+    let dir = profile_dir(name);
+    if dir.is_dir() {
+        return Ok(dir);
+    }
+    let known = list_profiles();
+    let known = if known.is_empty() {
+        "none yet".to_string()
+    } else {
+        known.join(", ")
+    };
+    Err(io::Error::new(
+        io::ErrorKind::NotFound,
+        format!(
+            "no profile '{name}' (existing: {known}), create it with mkdir -p {}",
+            dir.to_string_lossy()
+        ),
+    ))
+}
+
+/// All directories to read scripts from: ~/.config/hyprspace (or the directory of
+/// `profile`) first, then every entry of the colon-separated `HYPRSPACE_PATH`, then
+/// `extra` (from `--dir`).
+///
+/// New scripts and imports always go to the first one.
+pub fn workspace_dirs(profile: Option<&str>, extra: &[PathBuf]) -> Vec<PathBuf> {
+    let mut dirs = vec![profile.map_or_else(workspace_dir, profile_dir)];
+    let from_env = env::var_os("HYPRSPACE_PATH")
+        .map(|value| env::split_paths(&value).collect::<Vec<_>>())
+        .unwrap_or_default();
+
+    for dir in from_env.into_iter().chain(extra.iter().cloned()) {
+        if !dir.as_os_str().is_empty() && !dirs.contains(&dir) {
+            dirs.push(dir);
+        }
+    }
+
+    dirs
 }
 
 /// Ensures that ~/.config/hyprspace exists, creating it if needed.
 pub fn ensure_workspace_dir() -> io::Result<PathBuf> {
     let dir = workspace_dir();
 
+    match fs::metadata(&dir) {
+        Ok(meta) if !meta.is_dir() => {
+            return Err(io::Error::other(format!(
+                "{} exists but is not a directory, move it away so hyprspace can create its workspace directory",
+                dir.to_string_lossy()
+            )));
+        }
+        Err(_) if dir.is_symlink() => {
+            return Err(io::Error::other(format!(
+                "{} is a broken symlink, fix or remove it so hyprspace can use it",
+                dir.to_string_lossy()
+            )));
+        }
+        _ => {}
+    }
+
     if !dir.exists() {
         println!(
             "Workspace directory {:?} does not exist, creating it...",
@@ -41,26 +310,105 @@ pub fn ensure_workspace_dir() -> io::Result<PathBuf> {
     Ok(dir)
 }
 
-/// Try to parse `hyprctl dispatch workspace N` in the given script file.
-fn parse_workspace_num(path: &Path) -> Option<u32> {
-    let content = fs::read_to_string(path).ok()?;
+/// The dispatcher and its first argument of a `hyprctl [--instance <sig>] dispatch <dispatcher> [arg]` line.
+fn parse_dispatch(line: &str) -> Option<(&str, Option<&str>)> {
+    let mut tokens = line.split_whitespace();
 
-    for line in content.lines() {
-        let trimmed = line.trim();
+    if tokens.next()? != "hyprctl" {
+        return None;
+    }
+
+    // Skip global hyprctl flags such as `--instance <sig>` or `-j`
+    let mut token = tokens.next()?;
+    while token.starts_with('-') {
+        if token == "--instance" || token == "-i" {
+            tokens.next()?;
+        }
+        token = tokens.next()?;
+    }
+
+    if token != "dispatch" {
+        return None;
+    }
+    Some((tokens.next()?, tokens.next()))
+}
 
+/// Parse a `hyprctl [--instance <sig>] dispatch workspace N` line.
+fn parse_dispatch_line(line: &str) -> Option<u32> {
+    match parse_dispatch(line)? {
+        ("workspace", Some(num)) => num.parse::<u32>().ok(),
+        _ => None,
+    }
+}
+
+/// Try to parse `hyprctl dispatch workspace N` in the given script content.
+///
+/// Commented-out lines are skipped, and named workspaces (`name:dev`) give `None`.
+pub fn parse_workspace_num(content: &str) -> Option<u32> {
+    content
+        .lines()
+        .map(str::trim)
         // Skip comments and empty lines
-        if trimmed.is_empty() || trimmed.starts_with('#') {
-            continue;
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .find_map(parse_dispatch_line)
+}
+
+/// Try to parse `hyprctl dispatch togglespecialworkspace [name]` in the given script
+/// content, giving the special workspace's name (`special` when the line has none).
+pub fn parse_special_workspace(content: &str) -> Option<String> {
+    content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .find_map(|line| match parse_dispatch(line)? {
+            ("togglespecialworkspace", name) => Some(name.unwrap_or("special").to_string()),
+            _ => None,
+        })
+}
+
+/// Comment line declaring that a script places windows on several workspaces on purpose
+pub const MULTI_WORKSPACE_MARKER: &str = "# hyprspace-multi-workspace";
+
+/// Whether the script spans several workspaces on purpose: it carries
+/// [`MULTI_WORKSPACE_MARKER`] or switches to more than one workspace.
+pub fn spans_multiple_workspaces(content: &str) -> bool {
+    let mut dispatched = HashSet::new();
+    for line in content.lines().map(str::trim) {
+        if line == MULTI_WORKSPACE_MARKER {
+            return true;
+        }
+        if !line.starts_with('#')
+            && let Some(num) = parse_dispatch_line(line)
+        {
+            dispatched.insert(num);
         }
+    }
+    dispatched.len() > 1
+}
 
-        // We look for a line that starts with "hyprctl dispatch workspace"
-        if trimmed.starts_with("hyprctl dispatch workspace") {
-            // Split by whitespace and parse the last token as a number
-            let parts: Vec<&str> = trimmed.split_whitespace().collect();
-            if let Some(last) = parts.last() {
-                if let Ok(num) = last.parse::<u32>() {
-                    return Some(num);
-                }
+/// The leading number segment of a short name, e.g. 2 for `2-backend`.
+fn workspace_num_from_name(name_short: &str) -> Option<u32> {
+    name_short
+        .split('-')
+        .next()?
+        .parse()
+        .ok()
+        .filter(|&n| n > 0)
+}
+
+/// Returns the value of the first `# <key>: <value>` comment in the script content.
+fn parse_header(content: &str, key: &str) -> Option<String> {
+    for line in content.lines() {
+        let Some(comment) = line.trim().strip_prefix('#') else {
+            continue;
+        };
+
+        if let Some(value) = comment.trim_start().strip_prefix(key)
+            && let Some(value) = value.strip_prefix(':')
+        {
+            let value = value.trim();
+            if !value.is_empty() {
+                return Some(value.to_string());
             }
         }
     }
@@ -68,6 +416,184 @@ fn parse_workspace_num(path: &Path) -> Option<u32> {
     None
 }
 
+/// One part of a script as the parser saw it, for [`parse_report`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseNote {
+    /// Line number, or range for continued lines, e.g. `4-5`
+    pub lines: String,
+    /// What the parser made of it
+    pub text: String,
+    /// Whether the part looks meaningful but was left out
+    pub skipped: bool,
+}
+
+/// How the parser reads `content`: where the workspace number comes from, then every
+/// part of the script it recognized or skipped. Blank lines and plain comments are left out.
+///
+/// Windows are recognized with `helper` (`[generator] helper`), as when launching.
+pub fn parse_report(content: &str, name_short: &str, helper: &str) -> (String, Vec<ParseNote>) {
+    let document = ScriptDocument::parse_with_helper(content, helper);
+    let helper = document.helper().to_string();
+    let mut notes = Vec::new();
+    let mut dispatch: Option<(u32, usize)> = None;
+    let mut windows = 0;
+    let mut line_no = 1;
+
+    for block in &document.blocks {
+        let text = block.text();
+        let count = text.lines().count().max(1);
+        let lines = if count == 1 {
+            line_no.to_string()
+        } else {
+            format!("{line_no}-{}", line_no + count - 1)
+        };
+        let logical = text
+            .lines()
+            .map(|line| line.trim().trim_end_matches('\\').trim_end())
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        let mut skipped = false;
+        let description = match block {
+            Block::Shebang(_) => Some(format!("interpreter: {logical}")),
+            Block::Helper(_) => Some(format!("helper `{helper}` definition")),
+            Block::Window { spec, .. } => {
+                windows += 1;
+                Some(format!(
+                    "window #{windows}: [{}] {}",
+                    spec.rules,
+                    spec.exec_command()
+                ))
+            }
+            Block::Rule { rule, .. } => Some(match &rule.class {
+                Some(class) => format!("window rule `{}` for class {class}", rule.rule),
+                None => format!("window rule `{}`, not tied to a class", rule.rule),
+            }),
+            Block::Delay { seconds, .. } => Some(format!("delay: sleep {seconds}")),
+            Block::Layout { message, .. } => Some(format!("layout hint: {message}")),
+            Block::Focus(_) => {
+                Some("focus marker: the next window gets focus after launch".to_string())
+            }
+            Block::Line(_) if logical.is_empty() => None,
+            Block::Line(_) if logical.starts_with('#') => logical
+                .trim_start_matches('#')
+                .trim_start()
+                .starts_with("hyprspace")
+                .then(|| format!("header: {logical}")),
+            Block::Line(_) => Some(match parse_dispatch_line(&logical) {
+                Some(num) if dispatch.is_none() => {
+                    dispatch = Some((num, line_no));
+                    format!("workspace dispatch: workspace {num}")
+                }
+                Some(num) => format!(
+                    "workspace dispatch: workspace {num} (only the first one sets the number)"
+                ),
+                None => {
+                    skipped = true;
+                    match window_parse_problem(&logical, &helper) {
+                        Some(problem) => format!("not a window, {problem}: {logical}"),
+                        None => format!("run as is: {logical}"),
+                    }
+                }
+            }),
+        };
+        if let Some(text) = description {
+            notes.push(ParseNote {
+                lines,
+                text,
+                skipped,
+            });
+        }
+        line_no += count;
+    }
+
+    let summary = match (dispatch, workspace_num_from_name(name_short)) {
+        (Some((num, line)), _) => format!("workspace {num}, from the dispatch line on line {line}"),
+        (None, Some(num)) => format!("workspace {num}, from the file name (no dispatch line)"),
+        (None, None) => {
+            "no workspace: no `hyprctl dispatch workspace N` line, no number leading the name"
+                .to_string()
+        }
+    };
+    (format!("{summary} • {windows} window(s)"), notes)
+}
+
+/// Read a script and parse its metadata, keeping read failures distinct from missing data.
+///
+/// Without a dispatch line (nor a `togglespecialworkspace` one), the workspace number
+/// comes from a leading number in `name_short`, if any (`2-backend`).
+fn read_script_meta(path: &Path, name_short: &str) -> io::Result<ScriptMeta> {
+    let content = fs::read_to_string(path)?;
+    let mut warnings = Vec::new();
+
+    let mut workspace_num = parse_workspace_num(&content);
+    // A script switching to a regular workspace only shows a special one on the side
+    let special = parse_special_workspace(&content).filter(|_| workspace_num.is_none());
+    if workspace_num.is_none() && special.is_none() {
+        workspace_num = workspace_num_from_name(name_short);
+        if workspace_num.is_none() {
+            warnings.push("no `hyprctl dispatch workspace N` line found".to_string());
+        }
+    }
+
+    let order = parse_header(&content, "hyprspace-order").and_then(|value| match value.parse() {
+        Ok(order) => Some(order),
+        Err(_) => {
+            warnings.push(format!(
+                "`# hyprspace-order: {value}` is not a whole number"
+            ));
+            None
+        }
+    });
+
+    let min_hyprland = parse_header(&content, "hyprspace-min-hyprland").and_then(|value| {
+        let version = HyprlandVersion::parse(&value);
+        if version.is_none() {
+            warnings.push(format!(
+                "`# hyprspace-min-hyprland: {value}` is not a version such as 0.40"
+            ));
+        }
+        version
+    });
+
+    let windows = parse_windows(&content);
+    for (idx, window) in windows.iter().enumerate() {
+        for problem in window.rule_problems() {
+            warnings.push(format!("window #{}: malformed rule {problem}", idx + 1));
+        }
+    }
+    let programs = windows
+        .iter()
+        .filter_map(WindowSpec::program)
+        .map(|program| program.rsplit('/').next().unwrap_or(program).to_string())
+        .collect();
+
+    Ok(ScriptMeta {
+        workspace_num,
+        special,
+        description: parse_header(&content, "hyprspace"),
+        icon: parse_header(&content, "hyprspace-icon"),
+        order,
+        warnings,
+        programs,
+        window_count: windows.len(),
+        min_hyprland,
+    })
+}
+
+/// Whether a file name follows the workspace-<name>.sh convention.
+pub fn is_workspace_file_name(name: &str) -> bool {
+    name.starts_with("workspace-") && name.ends_with(".sh")
+}
+
+/// Whether `name` is safe to use in `workspace-<name>.sh`: only ASCII letters, digits, `_` and `-`.
+pub fn is_valid_short_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+}
+
 /// Lists all workspace-*.sh files in the given directory.
 pub fn list_workspaces(dir: &Path) -> io::Result<Vec<WorkspaceEntry>> {
     let mut entries = Vec::new();
@@ -91,36 +617,290 @@ pub fn list_workspaces(dir: &Path) -> io::Result<Vec<WorkspaceEntry>> {
 
         let file_name = file_name_os.to_string_lossy();
 
-        if !file_name.starts_with("workspace-") || !file_name.ends_with(".sh") {
+        if !is_workspace_file_name(&file_name) {
             continue;
         }
 
-        let no_ext = file_name.trim_end_matches(".sh");
-        let short = no_ext.strip_prefix("workspace-").unwrap_or(no_ext);
-
-        let workspace_num = parse_workspace_num(&path);
+        let ws = WorkspaceEntry::new(path);
+        let (meta, read_error) = match read_script_meta(&ws.full_path, &ws.name_short) {
+            Ok(meta) => (meta, None),
+            Err(err) => (ScriptMeta::default(), Some(err.to_string())),
+        };
 
         entries.push(WorkspaceEntry {
-            name_short: short.to_string(),
-            base_name: file_name.to_string(),
-            full_path: path,
-            workspace_num,
+            workspace_num: meta.workspace_num,
+            special: meta.special,
+            description: meta.description,
+            icon: meta.icon,
+            order: meta.order,
+            read_error,
+            warnings: meta.warnings,
+            programs: meta.programs,
+            window_count: meta.window_count,
+            min_hyprland: meta.min_hyprland,
+            ..ws
         });
     }
 
-    entries.sort_by(|a, b| a.base_name.cmp(&b.base_name));
+    sort_entries(&mut entries);
+
+    Ok(entries)
+}
+
+/// Lists the workspace scripts of every directory in `dirs`, merged and sorted like [`list_workspaces`].
+///
+/// Scripts sharing a name across directories are all kept, see [`name_collisions`]. A
+/// directory that can't be read is skipped with a warning, the others are still listed.
+pub fn list_all_workspaces(dirs: &[PathBuf]) -> io::Result<Vec<WorkspaceEntry>> {
+    let mut entries = Vec::new();
+    for dir in dirs {
+        match list_workspaces(dir) {
+            Ok(found) => entries.extend(found),
+            Err(err) => eprintln!("Warning: skipping {}: {err}", dir.to_string_lossy()),
+        }
+    }
+
+    sort_entries(&mut entries);
 
     Ok(entries)
 }
 
+/// Sort by `# hyprspace-order` (ascending, unordered scripts last), then by file name.
+///
+/// The sort is stable, so same-named scripts stay in directory order.
+fn sort_entries(entries: &mut [WorkspaceEntry]) {
+    entries.sort_by(|a, b| {
+        let order = |ws: &WorkspaceEntry| (ws.order.is_none(), ws.order);
+        order(a)
+            .cmp(&order(b))
+            .then_with(|| a.base_name.cmp(&b.base_name))
+    });
+}
+
+/// Short names used by more than one script.
+pub fn name_collisions(entries: &[WorkspaceEntry]) -> HashSet<String> {
+    let mut seen = HashSet::new();
+    entries
+        .iter()
+        .filter(|ws| !seen.insert(ws.name_short.as_str()))
+        .map(|ws| ws.name_short.clone())
+        .collect()
+}
+
+/// Whether the entries come from more than one directory.
+pub fn has_multiple_sources(entries: &[WorkspaceEntry]) -> bool {
+    entries
+        .windows(2)
+        .any(|pair| pair[0].source_dir != pair[1].source_dir)
+}
+
+/// How messages name `ws`: its file name, or its full path when the entries come from
+/// several directories, where file names alone are ambiguous.
+pub fn display_name(ws: &WorkspaceEntry, multiple_sources: bool) -> String {
+    if multiple_sources {
+        ws.full_path.to_string_lossy().into_owned()
+    } else {
+        ws.base_name.clone()
+    }
+}
+
+/// Find the script called `name`, refusing to guess when several directories provide one.
+pub fn find_workspace<'a>(
+    entries: &'a [WorkspaceEntry],
+    name: &str,
+) -> io::Result<&'a WorkspaceEntry> {
+    let matches: Vec<&WorkspaceEntry> = entries.iter().filter(|ws| ws.name_short == name).collect();
+
+    match matches.as_slice() {
+        [] => Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            format!("no workspace named '{name}'"),
+        )),
+        [ws] => Ok(ws),
+        _ => {
+            let paths: Vec<String> = matches
+                .iter()
+                .map(|ws| ws.full_path.to_string_lossy().into_owned())
+                .collect();
+            Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("workspace name '{name}' is ambiguous: {}", paths.join(", ")),
+            ))
+        }
+    }
+}
+
+/// The single script targeting workspace `num`; NotFound without one, InvalidInput
+/// when several share it.
+pub fn find_workspace_by_num(entries: &[WorkspaceEntry], num: u32) -> io::Result<&WorkspaceEntry> {
+    let matches: Vec<&WorkspaceEntry> = entries
+        .iter()
+        .filter(|ws| ws.workspace_num == Some(num))
+        .collect();
+
+    match matches.as_slice() {
+        [] => Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            format!("no script targets workspace {num}"),
+        )),
+        [ws] => Ok(ws),
+        _ => {
+            let names: Vec<&str> = matches.iter().map(|ws| ws.base_name.as_str()).collect();
+            Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "{} scripts target workspace {num}: {}",
+                    matches.len(),
+                    names.join(", ")
+                ),
+            ))
+        }
+    }
+}
+
+/// Add or remove all execute permission bits of a script.
+pub fn set_executable(path: &Path, executable: bool) -> io::Result<()> {
+    let mut perms = fs::metadata(path)?.permissions();
+    let mode = perms.mode();
+    perms.set_mode(if executable {
+        mode | 0o111
+    } else {
+        mode & !0o111
+    });
+    fs::set_permissions(path, perms)
+}
+
+/// Whether a script has any execute permission bit set.
+pub fn is_executable(path: &Path) -> io::Result<bool> {
+    Ok(fs::metadata(path)?.permissions().mode() & 0o111 != 0)
+}
+
+/// Move a workspace script into the trash of its directory, under a timestamped name,
+/// and return its new path (see [`TRASH_DIR`]).
+pub fn delete_script(ws: &WorkspaceEntry) -> io::Result<PathBuf> {
+    let trash = trash_dir(&ws.source_dir);
+    let dest = trash.join(format!(
+        "{}-{}",
+        trash_timestamp(SystemTime::now()),
+        ws.base_name
+    ));
+    move_file(&ws.full_path, &dest)?;
+    Ok(dest)
+}
+
+/// Move a workspace script into `dest_dir` and return its new path.
+///
+/// Refuses when `dest_dir` already holds a file of that name. Across filesystems the
+/// script is copied then removed; either way its permission bits, executable included,
+/// are kept.
+pub fn move_script(ws: &WorkspaceEntry, dest_dir: &Path) -> io::Result<PathBuf> {
+    let dest = dest_dir.join(&ws.base_name);
+    move_file(&ws.full_path, &dest)?;
+    Ok(dest)
+}
+
+/// Move the file at `from` to `dest`, creating its directory; see [`move_script`].
+pub(crate) fn move_file(from: &Path, dest: &Path) -> io::Result<()> {
+    // symlink_metadata also sees broken symlinks
+    if dest.symlink_metadata().is_ok() {
+        return Err(io::Error::new(
+            io::ErrorKind::AlreadyExists,
+            format!("{} already exists", dest.to_string_lossy()),
+        ));
+    }
+
+    if let Some(dir) = dest.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    match fs::rename(from, dest) {
+        Ok(()) => {}
+        Err(err) if err.kind() == io::ErrorKind::CrossesDevices => {
+            fs::copy(from, dest)?;
+            fs::remove_file(from)?;
+        }
+        Err(err) => return Err(err),
+    }
+    Ok(())
+}
+
+/// Subfolder of a script directory holding deleted scripts until `hyprspace trash empty`,
+/// each named `<YYYYMMDD-HHMMSS>-workspace-<name>.sh` after its deletion time (UTC)
+pub const TRASH_DIR: &str = ".trash";
+
+/// The trash folder of the script directory `dir`; archived scripts share the trash of
+/// the directory holding their `disabled/` folder.
+pub fn trash_dir(dir: &Path) -> PathBuf {
+    let parent = dir
+        .parent()
+        .filter(|_| dir.file_name().is_some_and(|name| name == ARCHIVE_DIR));
+    parent.unwrap_or(dir).join(TRASH_DIR)
+}
+
+/// `time` as `YYYYMMDD-HHMMSS` in UTC, the prefix of trashed file names.
+pub(crate) fn trash_timestamp(time: SystemTime) -> String {
+    let secs = time.duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs());
+    let (days, rest) = (secs / 86_400, secs % 86_400);
+
+    // Civil date from days since 1970-01-01 (Howard Hinnant's algorithm)
+    let z = days as i64 + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{year:04}{month:02}{day:02}-{:02}{:02}{:02}",
+        rest / 3600,
+        rest % 3600 / 60,
+        rest % 60
+    )
+}
+
+/// Subfolder of a script directory holding archived scripts, out of the list but kept
+pub const ARCHIVE_DIR: &str = "disabled";
+
+/// The archive folder of each script directory
+pub fn archive_dirs(dirs: &[PathBuf]) -> Vec<PathBuf> {
+    dirs.iter().map(|dir| dir.join(ARCHIVE_DIR)).collect()
+}
+
+/// Move a script into the `disabled/` folder of its directory and return its new path.
+pub fn archive_script(ws: &WorkspaceEntry) -> io::Result<PathBuf> {
+    move_script(ws, &ws.source_dir.join(ARCHIVE_DIR))
+}
+
+/// Move an archived script back next to its `disabled/` folder and return its new path.
+pub fn restore_script(ws: &WorkspaceEntry) -> io::Result<PathBuf> {
+    let parent = ws.source_dir.parent().filter(|_| {
+        ws.source_dir
+            .file_name()
+            .is_some_and(|name| name == ARCHIVE_DIR)
+    });
+    let Some(parent) = parent else {
+        return Err(io::Error::other(format!(
+            "{} is not archived",
+            ws.base_name
+        )));
+    };
+    move_script(ws, parent)
+}
+
 // Small helpers for a nicer interactive flow
 
 fn clear_screen() {
+    if !term::supports_color() {
+        return;
+    }
     print!("\x1b[2J\x1b[H");
     let _ = io::stdout().flush();
 }
 
-fn prompt(label: &str) -> io::Result<String> {
+/// Print `label` and read one trimmed line from stdin.
+pub fn prompt(label: &str) -> io::Result<String> {
     print!("{label}");
     io::stdout().flush()?;
     let mut buf = String::new();
@@ -138,7 +918,32 @@ fn prompt_non_empty(label: &str) -> io::Result<String> {
     }
 }
 
-fn prompt_yes_no(label: &str, default_no: bool) -> io::Result<bool> {
+/// Set by `--yes`: every confirmation goes ahead without reading stdin
+static ASSUME_YES: AtomicBool = AtomicBool::new(false);
+
+/// Answer yes to every [`confirm`] from now on (`--yes`).
+pub fn set_assume_yes(yes: bool) {
+    ASSUME_YES.store(yes, Ordering::Relaxed);
+}
+
+/// Whether `--yes` was given
+pub fn assume_yes() -> bool {
+    ASSUME_YES.load(Ordering::Relaxed)
+}
+
+/// Ask before going ahead with an action, `No` by default, or go ahead right away
+/// under `--yes` (printing the question answered).
+///
+/// Unlike [`prompt_yes_no`], which the wizard also asks plain questions with.
+pub fn confirm(label: &str) -> io::Result<bool> {
+    if assume_yes() {
+        println!("{label} [y/N]: y (--yes)");
+        return Ok(true);
+    }
+    prompt_yes_no(label, true)
+}
+
+pub fn prompt_yes_no(label: &str, default_no: bool) -> io::Result<bool> {
     let suffix = if default_no { " [y/N]: " } else { " [Y/n]: " };
     let full = format!("{label}{suffix}");
     let answer = prompt(&full)?.to_lowercase();
@@ -150,75 +955,435 @@ fn prompt_yes_no(label: &str, default_no: bool) -> io::Result<bool> {
     Ok(matches!(answer.as_str(), "y" | "yes"))
 }
 
-/// Create a new workspace script interactively (in normal terminal mode).
-pub fn create_new_script(dir: &Path) -> io::Result<()> {
-    clear_screen();
+/// Print the rule syntax problems of a window, if any
+fn print_rule_problems(window: &WindowSpec) {
+    for problem in window.rule_problems() {
+        println!("     ⚠ {problem}");
+    }
+}
+
+/// Ask for the size, position and command of a window (and its delay when `ask_delay`),
+/// plus a layout hint when the rules leave it tiled.
+///
+/// `workspace_rule` is the leading rule, e.g. `workspace 3 silent`.
+fn prompt_window(
+    workspace_rule: &str,
+    ask_delay: bool,
+    generator: &GeneratorSettings,
+) -> io::Result<WindowSpec> {
+    // Percent of the monitor or absolute pixels, for both size and position
+    let unit = loop {
+        match prompt("  • units: [p]ercent of monitor or [a]bsolute pixels? [P/a]: ")?
+            .to_lowercase()
+            .as_str()
+        {
+            "" | "p" | "percent" => break Unit::Percent,
+            "a" | "px" | "pixels" => break Unit::Pixels,
+            _ => println!("  -> Answer p or a."),
+        }
+    };
+    let examples = match unit {
+        Unit::Percent => ["10%", "15%", "1%", "8%"],
+        _ => ["800", "600", "20", "80"],
+    };
+    let value = |label: &str, example: &str, is_size: bool| loop {
+        let input = prompt_non_empty(&format!("  • {label} (e.g. {example}): "))?;
+        match unit.parse_value(&input, is_size) {
+            Ok(value) => return Ok::<_, io::Error>(value),
+            Err(problem) => println!("  -> Invalid value, {problem}."),
+        }
+    };
+
+    // Size: width / height
+    let width = value("width ", examples[0], true)?;
+    let height = value("height", examples[1], true)?;
+
+    // Position: x / y
+    let pos_x = value("position X", examples[2], false)?;
+    let pos_y = value("position Y", examples[3], false)?;
 
-    // Some simple styling
-    const BOLD: &str = "\x1b[1m";
-    const CYAN: &str = "\x1b[36m";
-    const RESET: &str = "\x1b[0m";
+    // Command to execute
+    let command = prompt_non_empty(
+        "command (e.g. kitty --hold zsh -c \"cava\" or firefox --new-window github.com): ",
+    )?;
 
+    // Optional directory for commands such as `nvim .` that depend on where they start
+    let cwd = prompt("  • working directory (e.g. ~/code/project, empty for the default): ")?;
+    let cwd = (!cwd.is_empty()).then_some(cwd);
+
+    // Optional pause so the previous window can settle first
+    let delay = if ask_delay {
+        loop {
+            let value =
+                prompt("  • delay before this window in seconds (e.g. 1, 0.5, empty for none): ")?;
+            if value.is_empty() {
+                break None;
+            }
+            if is_valid_delay(&value) {
+                break Some(value);
+            }
+            println!("  -> Invalid delay, enter a number of seconds.");
+        }
+    } else {
+        None
+    };
+
+    let rules = fill_template(
+        &generator.rules,
+        &[
+            ("workspace", workspace_rule),
+            ("width", &width),
+            ("height", &height),
+            ("x", &pos_x),
+            ("y", &pos_y),
+            ("command", &command),
+        ],
+    );
+    let mut window = WindowSpec {
+        rules,
+        command,
+        cwd,
+        delay,
+        unit: Some(unit),
+        ..Default::default()
+    };
+
+    // Tiled windows land where the layout puts them, unless told otherwise
+    if !window.is_floating() {
+        let hint = prompt("  • layout hint sent before it (e.g. preselect r, empty for none): ")?;
+        window.layout_hint = (!hint.is_empty()).then_some(hint);
+    }
+    Ok(window)
+}
+
+/// Ask which window, if any, hyprspace focuses once the launch is done.
+fn prompt_focus_window(windows: &mut [WindowSpec]) -> io::Result<()> {
+    loop {
+        let answer = prompt("Window to focus once they are all open (number, empty for none): ")?;
+        if answer.is_empty() {
+            return Ok(());
+        }
+        match answer.parse::<usize>() {
+            Ok(n) if (1..=windows.len()).contains(&n) => {
+                windows[n - 1].focus = true;
+                return Ok(());
+            }
+            _ => println!(
+                "  -> Enter a window number between 1 and {}.",
+                windows.len()
+            ),
+        }
+    }
+}
+
+/// Ask whether windows should keep focus away, and build the matching `workspace N` rule.
+///
+/// Windows of a special workspace always go there silently, as
+/// `movetoworkspacesilent special:<name>` would move them.
+fn prompt_workspace_rule(workspace: &Workspace) -> io::Result<String> {
+    let workspace_num = match workspace {
+        Workspace::Numbered(num) => num,
+        Workspace::Special(name) => return Ok(format!("workspace special:{name} silent")),
+    };
+    // `silent` keeps focus where it is, without it focus follows each new window
+    let silent = prompt_yes_no(
+        "Open windows in the background (keep focus where it is)?",
+        false,
+    )?;
+    Ok(if silent {
+        format!("workspace {workspace_num} silent")
+    } else {
+        format!("workspace {workspace_num}")
+    })
+}
+
+/// Ask whether the script should close the windows already on its workspace first.
+fn prompt_close_existing(workspace: &Workspace) -> io::Result<bool> {
+    prompt_yes_no(
+        &format!(
+            "Close the windows already on {workspace} on each run (no duplicates when re-run)?"
+        ),
+        true,
+    )
+}
+
+/// Before replacing an existing script, summarize what changes and ask for confirmation
+/// (only the summary is shown when `ask` is false).
+///
+/// Returns true straight away when `path` doesn't exist yet.
+pub fn confirm_rewrite(path: &Path, new_content: &str, ask: bool) -> io::Result<bool> {
+    let old_content = match fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(true),
+        Err(err) => return Err(err),
+    };
+
+    let name = path.file_name().unwrap_or_default().to_string_lossy();
+
+    if old_content == new_content {
+        println!("{name} is unchanged.");
+        return Ok(false);
+    }
+
+    let old_windows = ScriptDocument::parse(&old_content).windows();
+    let new_windows = ScriptDocument::parse(new_content).windows();
+    let show_num = |num: Option<u32>| {
+        num.map(|n| n.to_string())
+            .unwrap_or_else(|| "?".to_string())
+    };
+
+    println!("\n{name} already exists and would change:");
     println!(
-        "{BOLD}{CYAN}Hyprspace · New workspace script{RESET}\n",
+        "  workspace: {} → {}",
+        show_num(parse_workspace_num(&old_content)),
+        show_num(parse_workspace_num(new_content))
     );
-    println!("Destination directory: {}", dir.to_string_lossy());
-    println!("Follow the steps to configure your workspace layout.\n");
+    println!("  windows:   {} → {}", old_windows.len(), new_windows.len());
 
-    // 1) Workspace number
-    println!("{BOLD}Step 1/3 · Workspace target{RESET}");
-    let workspace_num: u32 = loop {
-        let value = prompt("Enter workspace number (e.g. 1, 2, 3): ")?;
-        match value.trim().parse::<u32>() {
-            Ok(n) if n > 0 => break n,
+    for window in old_windows.iter().filter(|w| !new_windows.contains(w)) {
+        println!("  - {}  [{}]", window.exec_command(), window.rules);
+    }
+    for window in new_windows.iter().filter(|w| !old_windows.contains(w)) {
+        println!("  + {}  [{}]", window.exec_command(), window.rules);
+    }
+    println!();
+
+    if !ask {
+        return Ok(true);
+    }
+    confirm(&format!("Overwrite {name}?"))
+}
+
+/// Some simple styling, left out when the terminal can't show it
+struct Styles {
+    bold: &'static str,
+    cyan: &'static str,
+    reset: &'static str,
+}
+
+fn styles() -> Styles {
+    if term::supports_color() {
+        Styles {
+            bold: "\x1b[1m",
+            cyan: "\x1b[36m",
+            reset: "\x1b[0m",
+        }
+    } else {
+        Styles {
+            bold: "",
+            cyan: "",
+            reset: "",
+        }
+    }
+}
+
+/// Name and list metadata of a script being created
+struct ScriptIdentity {
+    file_name: String,
+    path: PathBuf,
+    description: Option<String>,
+    icon: Option<String>,
+    order: Option<i32>,
+}
+
+/// Ask for the workspace number, offering `default` when known.
+fn prompt_workspace_num(default: Option<u32>) -> io::Result<u32> {
+    let label = match default {
+        Some(n) => format!("Enter workspace number [{n}]: "),
+        None => "Enter workspace number (e.g. 1, 2, 3): ".to_string(),
+    };
+
+    loop {
+        let value = prompt(&label)?;
+        if value.is_empty()
+            && let Some(n) = default
+        {
+            return Ok(n);
+        }
+        match value.parse::<u32>() {
+            Ok(n) if n > 0 => return Ok(n),
             _ => {
                 println!("Invalid workspace number, please enter a positive integer.");
             }
         }
-    };
-    println!("Will dispatch to workspace {workspace_num}\n");
+    }
+}
 
-    // 2) Script short name
-    println!("{BOLD}Step 2/3 · Script identity{RESET}");
-    let short_name = prompt_non_empty("Enter script short name (e.g. 'backend', 'music', 'dashboard'): ")?;
+/// Ask for the workspace of a new script: a number, or `special:<name>` for a scratchpad.
+fn prompt_workspace() -> io::Result<Workspace> {
+    loop {
+        let value = prompt(
+            "Enter workspace number, or special:<name> for a scratchpad (e.g. 1, 2, special:magic): ",
+        )?;
+        match Workspace::parse(&value) {
+            Some(workspace) => return Ok(workspace),
+            None => {
+                println!("Invalid workspace, please enter a positive integer or special:<name>.")
+            }
+        }
+    }
+}
+
+/// Ask for the short name of a new script in `dir`, returning its file name and path.
+fn prompt_script_path(dir: &Path) -> io::Result<(String, PathBuf)> {
+    let short_name = loop {
+        let value =
+            prompt_non_empty("Enter script short name (e.g. 'backend', 'music', 'dashboard'): ")?;
+        if is_valid_short_name(&value) {
+            break value;
+        }
+        println!("  -> Use only letters, digits, '_' and '-', try again.");
+    };
     let file_name = format!("workspace-{}.sh", short_name);
-    let mut path = dir.to_path_buf();
-    path.push(&file_name);
+    let path = dir.join(&file_name);
 
     if path.exists() {
         println!(
-            "File {} already exists, aborting.",
+            "File {} already exists, you will see what changes before it is replaced.",
             path.to_string_lossy()
         );
-        return Ok(());
     }
 
     println!("Script file will be: {}", path.to_string_lossy());
+    Ok((file_name, path))
+}
+
+/// Ask for the short name, description, icon and order of a new script in `dir`.
+fn prompt_identity(dir: &Path) -> io::Result<ScriptIdentity> {
+    let (file_name, path) = prompt_script_path(dir)?;
+
+    let description = prompt("Optional description (shown in the list, leave empty to skip): ")?;
+    let icon = prompt("Optional icon / emoji (e.g. 🎵, leave empty to skip): ")?;
+    let order = loop {
+        let value = prompt(
+            "Optional sort order in the list (e.g. 10, lower comes first, leave empty to skip): ",
+        )?;
+        if value.is_empty() {
+            break None;
+        }
+        match value.parse::<i32>() {
+            Ok(n) => break Some(n),
+            Err(_) => println!("  -> Invalid order, enter a whole number."),
+        }
+    };
     println!();
 
-    // 3) Build script content
-    println!("{BOLD}Step 3/3 · Windows layout{RESET}");
-    println!("You can now add one or more windows using rule_exec.");
-    println!("For each window, you will choose size, position and command.\n");
+    Ok(ScriptIdentity {
+        file_name,
+        path,
+        description: Some(description).filter(|d| !d.is_empty()),
+        icon: Some(icon).filter(|i| !i.is_empty()),
+        order,
+    })
+}
+
+/// Print the windows collected so far, with their delays and rule problems.
+fn print_windows(windows: &[WindowSpec]) {
+    for (idx, window) in windows.iter().enumerate() {
+        if let Some(seconds) = &window.delay {
+            println!("     (after sleep {seconds})");
+        }
+        if let Some(message) = &window.layout_hint {
+            println!("     (after layoutmsg {message})");
+        }
+        println!(
+            "  {}. {}  [{}]",
+            idx + 1,
+            window.exec_command(),
+            window.rules
+        );
+        if window.focus {
+            println!("     (focused after launch)");
+        }
+        print_rule_problems(window);
+    }
+}
+
+/// Preview the generated script, then write it (executable) once confirmed.
+fn save_new_script(
+    identity: &ScriptIdentity,
+    spec: &ScriptSpec,
+    config: &Config,
+) -> io::Result<()> {
+    let Styles { bold, reset, .. } = styles();
+    let content = build_script_content(spec);
+    let path = &identity.path;
+
+    println!("\n{bold}Preview of the generated script:{reset}\n");
+    println!("----- {} -----", identity.file_name);
+    println!("{content}");
+    println!("---------------------------\n");
 
-    let mut content = String::new();
+    for (idx, window) in spec.windows.iter().enumerate() {
+        if !window.rule_problems().is_empty() {
+            println!("Window #{} has malformed rules:", idx + 1);
+            print_rule_problems(window);
+        }
+    }
+    if let Workspace::Numbered(num) = spec.workspace {
+        for mismatch in workspace_mismatches(num, &spec.windows) {
+            println!("⚠ {mismatch}");
+        }
+    }
 
-    // Header and workspace dispatch
-    content.push_str("#!/bin/bash\n\n");
-    content.push_str(&format!("hyprctl dispatch workspace {num}\n\n", num = workspace_num));
+    if !prompt_yes_no("Save this script?", false)? {
+        println!("Aborted, script was not created.");
+        return Ok(());
+    }
+    if !confirm_rewrite(path, &content, config.confirm.overwrite)? {
+        // An unchanged script was already reported as such
+        if fs::read_to_string(path).is_ok_and(|old| old != content) {
+            println!("Aborted, {} was not changed.", path.to_string_lossy());
+        }
+        return Ok(());
+    }
+
+    // Write file
+    fs::write(path, content)?;
 
-    // rule_exec helper
-    content.push_str(
-        r#"rule_exec() {
-  local rules="$1"
-  shift
-  hyprctl dispatch exec "[$rules] $*"
+    // Make it executable (chmod +x)
+    set_executable(path, true)?;
+
+    println!("Created script: {}", path.to_string_lossy());
+    Ok(())
 }
 
-"#,
-    );
+/// Create a new workspace script interactively (in normal terminal mode).
+///
+/// When `instance` is set, the generated hyprctl calls target that Hyprland instance.
+pub fn create_new_script(dir: &Path, instance: Option<&str>, config: &Config) -> io::Result<()> {
+    let Styles { bold, cyan, reset } = styles();
+    clear_screen();
+
+    println!("{bold}{cyan}Hyprspace · New workspace script{reset}\n",);
+    println!("Destination directory: {}", dir.to_string_lossy());
+    println!("Follow the steps to configure your workspace layout.\n");
+
+    // 1) Workspace number
+    println!("{bold}Step 1/3 · Workspace target{reset}");
+    let workspace = prompt_workspace()?;
+    match &workspace {
+        Workspace::Numbered(num) => println!("Will dispatch to workspace {num}\n"),
+        Workspace::Special(name) => {
+            println!("Will toggle special:{name}, its windows open there silently\n")
+        }
+    }
 
-    let mut window_index = 1usize;
+    // 2) Script short name
+    println!("{bold}Step 2/3 · Script identity{reset}");
+    let identity = prompt_identity(dir)?;
+
+    // 3) Build script content
+    println!("{bold}Step 3/3 · Windows layout{reset}");
+    println!("You can now add one or more windows using rule_exec.");
+    println!("For each window, you will choose size, position and command.");
+    println!("Rule syntax: {}\n", docs::WINDOW_RULES_URL);
+
+    let workspace_rule = prompt_workspace_rule(&workspace)?;
+    let close_existing = prompt_close_existing(&workspace)?;
+    println!();
+
+    let mut windows: Vec<WindowSpec> = Vec::new();
 
     // Add one or more rule_exec blocks
     loop {
@@ -227,63 +1392,309 @@ pub fn create_new_script(dir: &Path) -> io::Result<()> {
             break;
         }
 
+        // A nudge against runaway scripts, asked once when reaching the cap
+        let cap = config.generator.max_windows;
+        if cap > 0
+            && windows.len() == cap as usize
+            && !prompt_yes_no(&format!("You've added {cap} windows, continue?"), true)?
+        {
+            break;
+        }
+
+        let window_index = windows.len() + 1;
         println!();
         println!(
-            "{BOLD}Window #{idx}{RESET} – layout & command",
+            "{bold}Window #{idx}{reset} – layout & command",
             idx = window_index
         );
 
-        // Size: width / height
-        let width = prompt_non_empty("  • width  (e.g. 10%): ")?;
-        let height = prompt_non_empty("  • height (e.g. 15%): ")?;
+        windows.push(prompt_window(
+            &workspace_rule,
+            !windows.is_empty(),
+            &config.generator,
+        )?);
 
-        // Position: x / y
-        let pos_x = prompt_non_empty("  • position X (e.g. 1%): ")?;
-        let pos_y = prompt_non_empty("  • position Y (e.g. 8%): ")?;
+        println!("Window #{idx} added.", idx = window_index);
 
-        // Command to execute
-        let command = prompt_non_empty(
-            "command (e.g. kitty --hold zsh -c \"cava\" or firefox --new-window github.com): ",
-        )?;
+        // Keep a compact running view of the layout so far
+        println!("\n{bold}Windows so far:{reset}");
+        print_windows(&windows);
+        println!();
+    }
+
+    if windows.is_empty() {
+        println!("\nNo windows were added. The script will only switch workspace.");
+    } else {
+        prompt_focus_window(&mut windows)?;
+    }
+
+    let spec = ScriptSpec {
+        workspace,
+        description: identity.description.clone(),
+        icon: identity.icon.clone(),
+        order: identity.order,
+        instance: instance.map(str::to_string),
+        close_existing,
+        helper: config.generator.helper.clone(),
+        windows,
+    };
+    save_new_script(&identity, &spec, config)
+}
 
-        // Append the rule_exec block
-        content.push_str(&format!(
-            "rule_exec \"workspace {num} silent; float; size {w} {h}; move {x} {y}\" \\\n  {cmd}\n\n",
-            num = workspace_num,
-            w = width,
-            h = height,
-            x = pos_x,
-            y = pos_y,
-            cmd = command
+/// Create a new script from `hyprctl dispatch exec` / `rule_exec` / `exec-once` lines
+/// found in the clipboard (in normal terminal mode).
+pub fn create_from_clipboard(
+    dir: &Path,
+    instance: Option<&str>,
+    config: &Config,
+) -> io::Result<()> {
+    let Styles { bold, cyan, reset } = styles();
+    let mut windows = parse_pasted_windows(&clipboard::paste()?);
+    if windows.is_empty() {
+        return Err(io::Error::other(
+            "no `hyprctl dispatch exec`, `rule_exec` or `exec-once` line found in the clipboard",
         ));
+    }
 
-        println!("Window #{idx} added.", idx = window_index);
-        window_index += 1;
+    clear_screen();
+    println!("{bold}{cyan}Hyprspace · New workspace script from the clipboard{reset}\n",);
+    println!("Destination directory: {}", dir.to_string_lossy());
+    println!("Found {} window(s):", windows.len());
+    print_windows(&windows);
+    println!();
+
+    // 1) Workspace number, guessed from the first `workspace N` rule
+    println!("{bold}Step 1/2 · Workspace target{reset}");
+    let workspace_num = prompt_workspace_num(windows.iter().find_map(WindowSpec::workspace_num))?;
+    println!("Will dispatch to workspace {workspace_num}\n");
+
+    // Windows without a workspace rule would open wherever the focus is
+    for window in &mut windows {
+        if window.workspace_num().is_none() {
+            window.rules = if window.rules.is_empty() {
+                format!("workspace {workspace_num} silent")
+            } else {
+                format!("workspace {workspace_num} silent; {}", window.rules)
+            };
+        }
     }
 
-    if window_index == 1 {
-        println!("\nNo windows were added. The script will only switch workspace.");
+    let close_existing = prompt_close_existing(&Workspace::Numbered(workspace_num))?;
+    prompt_focus_window(&mut windows)?;
+    println!();
+
+    // 2) Script short name
+    println!("{bold}Step 2/2 · Script identity{reset}");
+    let identity = prompt_identity(dir)?;
+
+    let spec = ScriptSpec {
+        workspace: Workspace::Numbered(workspace_num),
+        description: identity.description.clone(),
+        icon: identity.icon.clone(),
+        order: identity.order,
+        instance: instance.map(str::to_string),
+        close_existing,
+        helper: config.generator.helper.clone(),
+        windows,
+    };
+    save_new_script(&identity, &spec, config)
+}
+
+/// Copy a script under a new name next to it (in normal terminal mode), asking for the
+/// workspace number the copy targets.
+///
+/// With another number, the `dispatch workspace` line, the close-existing preamble and the
+/// `workspace N` rules of the windows are rewritten; everything else is copied as is.
+pub fn duplicate_script(ws: &WorkspaceEntry, config: &Config) -> io::Result<()> {
+    let content = fs::read_to_string(&ws.full_path)?;
+    let mut doc = ScriptDocument::parse_with_helper(&content, &config.generator.helper);
+
+    println!("Duplicating {}\n", ws.full_path.to_string_lossy());
+    let (_, path) = prompt_script_path(&ws.source_dir)?;
+
+    match ws.workspace_num {
+        Some(old) => {
+            let new = prompt_workspace_num(Some(old))?;
+            if new != old {
+                doc.retarget_workspace(old, new);
+            }
+        }
+        None => println!(
+            "{} has no `hyprctl dispatch workspace N` line, copying it as is.",
+            ws.base_name
+        ),
     }
+    let new_content = doc.render();
 
-    println!("\n{BOLD}Preview of the generated script:{RESET}\n");
-    println!("----- {} -----", file_name);
-    println!("{content}");
-    println!("---------------------------\n");
+    if !confirm_rewrite(&path, &new_content, config.confirm.overwrite)? {
+        // An unchanged script was already reported as such
+        if fs::read_to_string(&path).is_ok_and(|old| old != new_content) {
+            println!("Aborted, {} was not changed.", path.to_string_lossy());
+        }
+        return Ok(());
+    }
 
-    if !prompt_yes_no("Save this script?", false)? {
-        println!("Aborted, script was not created.");
+    fs::write(&path, new_content)?;
+    // Same permission bits as the original, executable included
+    fs::set_permissions(&path, fs::metadata(&ws.full_path)?.permissions())?;
+    println!("Created script: {}", path.to_string_lossy());
+    Ok(())
+}
+
+/// Ask for a monitor resolution as `<width>x<height>`, `default` when the answer is empty.
+fn prompt_resolution(default: Option<(u32, u32)>) -> io::Result<(u32, u32)> {
+    let hint = match default {
+        Some((width, height)) => format!("{width}x{height}, the focused monitor"),
+        None => "e.g. 1920x1080".to_string(),
+    };
+    loop {
+        let answer = prompt(&format!("Reference monitor resolution ({hint}): "))?;
+        if answer.is_empty()
+            && let Some(default) = default
+        {
+            return Ok(default);
+        }
+        let parsed = answer
+            .split_once(['x', 'X', '×'])
+            .and_then(|(w, h)| Some((w.trim().parse::<u32>().ok()?, h.trim().parse::<u32>().ok()?)))
+            .filter(|&(w, h)| w > 0 && h > 0);
+        match parsed {
+            Some(resolution) => return Ok(resolution),
+            None => println!("  -> Enter the resolution as WIDTHxHEIGHT, e.g. 2560x1440."),
+        }
+    }
+}
+
+/// Rewrite the `size` and `move` values of a script between percent of the monitor and
+/// pixels, for a resolution asked for (in normal terminal mode).
+///
+/// Scripts in a single unit switch to the other one; mixed scripts ask which one to use.
+pub fn convert_script_units(
+    ws: &WorkspaceEntry,
+    instance: Option<&str>,
+    config: &Config,
+) -> io::Result<()> {
+    let path = &ws.full_path;
+    let content = fs::read_to_string(path)?;
+    let mut doc = ScriptDocument::parse_with_helper(&content, &config.generator.helper);
+
+    let units: Vec<Unit> = doc.windows().iter().filter_map(|w| w.unit).collect();
+    let to = if units.is_empty() {
+        return Err(io::Error::other(format!(
+            "{} has no numeric size or move values to convert",
+            ws.base_name
+        )));
+    } else if units.iter().all(|&u| u == Unit::Percent) {
+        Unit::Pixels
+    } else if units.iter().all(|&u| u == Unit::Pixels) {
+        Unit::Percent
+    } else {
+        loop {
+            match prompt(
+                "Convert every value to [p]ercent of monitor or [a]bsolute pixels? [p/a]: ",
+            )?
+            .to_lowercase()
+            .as_str()
+            {
+                "p" | "percent" => break Unit::Percent,
+                "a" | "px" | "pixels" => break Unit::Pixels,
+                _ => println!("  -> Answer p or a."),
+            }
+        }
+    };
+
+    println!("Converting {} to {}\n", path.to_string_lossy(), to.label());
+    // Without Hyprland (or on a hyprctl error) there's no default to offer
+    let focused = hyprctl::focused_monitor_size(instance).ok().flatten();
+    let (width, height) = prompt_resolution(focused)?;
+
+    if doc.convert_units(to, width, height) == 0 {
+        println!("Nothing to convert, {} was not changed.", ws.base_name);
         return Ok(());
     }
+    let new_content = doc.render();
 
-    // Write file
-    fs::write(&path, content)?;
+    if !confirm_rewrite(path, &new_content, config.confirm.overwrite)? {
+        println!("Aborted, script was not changed.");
+        return Ok(());
+    }
 
-    // Make it executable (chmod 755)
-    let metadata = fs::metadata(&path)?;
-    let mut perms = metadata.permissions();
-    perms.set_mode(0o755);
-    fs::set_permissions(&path, perms)?;
+    fs::write(path, new_content)?;
+    println!("Updated script: {}", path.to_string_lossy());
+    Ok(())
+}
 
-    println!("Created script: {}", path.to_string_lossy());
+/// Add a window to an existing script (in normal terminal mode).
+///
+/// Scripts that call `hyprctl dispatch exec` directly are offered the standard
+/// helper (`[generator] helper`, `rule_exec` by default) first, so the appended window works.
+pub fn append_window(
+    ws: &WorkspaceEntry,
+    instance: Option<&str>,
+    config: &Config,
+) -> io::Result<()> {
+    let path = &ws.full_path;
+    let Some(workspace) = ws.workspace() else {
+        return Err(io::Error::other(format!(
+            "{} has no `hyprctl dispatch workspace N` line to place the window on",
+            path.to_string_lossy()
+        )));
+    };
+
+    let content = fs::read_to_string(path)?;
+    let mut doc = ScriptDocument::parse_with_helper(&content, &config.generator.helper);
+
+    println!("Adding a window to {}\n", path.to_string_lossy());
+    let workspace_rule = prompt_workspace_rule(&workspace)?;
+    let window = prompt_window(
+        &workspace_rule,
+        !doc.windows().is_empty(),
+        &config.generator,
+    )?;
+    print_rule_problems(&window);
+
+    if !doc.has_helper() {
+        let helper = doc.helper().to_string();
+        println!(
+            "\n{} does not define the {helper} helper, the new window cannot start without it.",
+            ws.base_name
+        );
+        if prompt_yes_no(&format!("Insert the standard {helper} helper?"), false)? {
+            doc.insert_helper(instance);
+        } else {
+            println!("Keeping the script as is, make sure {helper} is defined before it runs.");
+        }
+    }
+
+    doc.push_window(window);
+    let new_content = doc.render();
+
+    if !confirm_rewrite(path, &new_content, config.confirm.overwrite)? {
+        println!("Aborted, script was not changed.");
+        return Ok(());
+    }
+
+    fs::write(path, new_content)?;
+    println!("Updated script: {}", path.to_string_lossy());
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn trash_timestamps_are_utc_dates() {
+        let at = |secs: u64| trash_timestamp(UNIX_EPOCH + Duration::from_secs(secs));
+        assert_eq!(at(0), "19700101-000000");
+        assert_eq!(at(1_709_210_096), "20240229-123456");
+        assert_eq!(at(951_868_799), "20000229-235959");
+        assert_eq!(at(4_107_542_400), "21000301-000000");
+        // Before the epoch reads as the epoch
+        assert_eq!(
+            trash_timestamp(UNIX_EPOCH - Duration::from_secs(60)),
+            "19700101-000000"
+        );
+    }
+}
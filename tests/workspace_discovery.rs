@@ -0,0 +1,308 @@
+//! Workspace discovery against real directories: which files are listed, in what order,
+//! and what the parser reads out of them.
+
+use std::cmp::Reverse;
+use std::env;
+use std::fs;
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
+use std::process;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use hyprspace::fuzzy::score_workspace;
+use hyprspace::list::{badge, badge_width, programs_summary};
+use hyprspace::workspace::{
+    TRASH_DIR, Workspace, list_all_workspaces, parse_special_workspace, parse_workspace_num,
+};
+use hyprspace::{WorkspaceEntry, list_workspaces};
+
+/// A fresh directory under the system temp dir, removed with everything in it on drop
+struct TempDir(PathBuf);
+
+impl TempDir {
+    fn new(label: &str) -> Self {
+        static NEXT: AtomicUsize = AtomicUsize::new(0);
+        let n = NEXT.fetch_add(1, Ordering::Relaxed);
+        let path = env::temp_dir().join(format!("hyprspace-test-{}-{n}-{label}", process::id()));
+        let _ = fs::remove_dir_all(&path);
+        fs::create_dir_all(&path).unwrap();
+        TempDir(path)
+    }
+
+    fn path(&self) -> &Path {
+        &self.0
+    }
+
+    /// Write an executable file called `name` holding `content`.
+    fn script(&self, name: &str, content: &str) -> PathBuf {
+        let path = self.0.join(name);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).unwrap();
+        }
+        fs::write(&path, content).unwrap();
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o755)).unwrap();
+        path
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.0);
+    }
+}
+
+fn names(entries: &[WorkspaceEntry]) -> Vec<&str> {
+    entries.iter().map(|ws| ws.name_short.as_str()).collect()
+}
+
+fn entry<'a>(entries: &'a [WorkspaceEntry], name: &str) -> &'a WorkspaceEntry {
+    entries
+        .iter()
+        .find(|ws| ws.name_short == name)
+        .unwrap_or_else(|| panic!("{name} is not listed"))
+}
+
+#[test]
+fn lists_only_workspace_scripts_of_the_directory_itself() {
+    let dir = TempDir::new("filenames");
+    dir.script(
+        "workspace-dev.sh",
+        "#!/bin/bash\nhyprctl dispatch workspace 1\n",
+    );
+    dir.script("notes.txt", "hyprctl dispatch workspace 2\n");
+    dir.script("workspace-dev.bash", "hyprctl dispatch workspace 3\n");
+    dir.script("old-workspace-dev.sh", "hyprctl dispatch workspace 4\n");
+    dir.script("workspace-dev.sh.bak", "hyprctl dispatch workspace 5\n");
+    dir.script(
+        "disabled/workspace-archived.sh",
+        "hyprctl dispatch workspace 6\n",
+    );
+    dir.script(
+        &format!("{TRASH_DIR}/20260101-000000-workspace-gone.sh"),
+        "hyprctl dispatch workspace 7\n",
+    );
+    fs::create_dir(dir.path().join("workspace-folder.sh")).unwrap();
+
+    let entries = list_workspaces(dir.path()).unwrap();
+
+    assert_eq!(names(&entries), ["dev"]);
+    let dev = &entries[0];
+    assert_eq!(dev.base_name, "workspace-dev.sh");
+    assert_eq!(dev.full_path, dir.path().join("workspace-dev.sh"));
+    assert_eq!(dev.source_dir, dir.path());
+    assert_eq!(dev.workspace_num, Some(1));
+    assert!(dev.warnings.is_empty(), "{:?}", dev.warnings);
+}
+
+#[test]
+fn reads_workspace_numbers_and_headers() {
+    let dir = TempDir::new("parsing");
+    dir.script(
+        "workspace-backend.sh",
+        "#!/bin/bash\n# hyprspace: API and logs\n# hyprspace-icon: 🚀\n\nhyprctl dispatch workspace 2\n",
+    );
+    dir.script(
+        "workspace-commented.sh",
+        "#!/bin/bash\n# hyprctl dispatch workspace 9\n  # hyprctl dispatch workspace 8\nhyprctl dispatch workspace 4\n",
+    );
+    dir.script(
+        "workspace-instance.sh",
+        "#!/bin/bash\nhyprctl --instance abc_123 dispatch workspace 5\n",
+    );
+    dir.script(
+        "workspace-named.sh",
+        "#!/bin/bash\nhyprctl dispatch workspace name:dev\n",
+    );
+    dir.script("workspace-notes.sh", "#!/bin/bash\necho no dispatch here\n");
+    dir.script(
+        "workspace-7-chat.sh",
+        "#!/bin/bash\nhyprctl dispatch exec firefox\n",
+    );
+    dir.script(
+        "workspace-0-notes.sh",
+        "#!/bin/bash\nhyprctl dispatch exec firefox\n",
+    );
+    dir.script(
+        "workspace-scratchpad.sh",
+        "#!/bin/bash\nhyprctl dispatch togglespecialworkspace magic\n",
+    );
+    dir.script(
+        "workspace-tools.sh",
+        "#!/bin/bash\nhyprctl dispatch workspace 6\nhyprctl dispatch exec /usr/bin/kitty\nrule_exec \"float\"\nhyprctl dispatch exec firefox\n",
+    );
+
+    let entries = list_workspaces(dir.path()).unwrap();
+
+    let backend = entry(&entries, "backend");
+    assert_eq!(backend.workspace_num, Some(2));
+    assert_eq!(backend.description.as_deref(), Some("API and logs"));
+    assert_eq!(backend.icon.as_deref(), Some("🚀"));
+    assert_eq!(entry(&entries, "commented").workspace_num, Some(4));
+    assert_eq!(entry(&entries, "instance").workspace_num, Some(5));
+    // Without a dispatch line, a leading number in the name is used
+    assert_eq!(entry(&entries, "7-chat").workspace_num, Some(7));
+    // Hyprland has no workspace 0
+    assert_eq!(entry(&entries, "0-notes").workspace_num, None);
+
+    let scratchpad = entry(&entries, "scratchpad");
+    assert_eq!(scratchpad.workspace_num, None);
+    assert_eq!(scratchpad.special.as_deref(), Some("magic"));
+    assert_eq!(
+        scratchpad.workspace(),
+        Some(Workspace::Special("magic".to_string()))
+    );
+    assert!(scratchpad.warnings.is_empty(), "{:?}", scratchpad.warnings);
+    assert_eq!(badge(scratchpad, badge_width(&entries)), "[special:magic]");
+    assert_eq!(badge(backend, badge_width(&entries)), "[ws          2]");
+
+    // Windows without a program still count
+    let tools = entry(&entries, "tools");
+    assert_eq!(tools.programs, ["kitty", "firefox"]);
+    assert_eq!(tools.window_count, 3);
+    assert_eq!(programs_summary(tools).as_deref(), Some("(kitty, +2)"));
+    assert_eq!(programs_summary(scratchpad), None);
+
+    for name in ["named", "notes"] {
+        let ws = entry(&entries, name);
+        assert_eq!(ws.workspace_num, None, "{name}");
+        assert!(
+            ws.warnings
+                .iter()
+                .any(|w| w.contains("no `hyprctl dispatch workspace N` line")),
+            "{name}: {:?}",
+            ws.warnings
+        );
+    }
+}
+
+#[test]
+fn orders_by_weight_then_file_name() {
+    let dir = TempDir::new("ordering");
+    dir.script(
+        "workspace-zeta.sh",
+        "hyprctl dispatch workspace 1\n# hyprspace-order: 1\n",
+    );
+    dir.script("workspace-beta.sh", "hyprctl dispatch workspace 2\n");
+    dir.script("workspace-alpha.sh", "hyprctl dispatch workspace 3\n");
+    dir.script(
+        "workspace-mid.sh",
+        "hyprctl dispatch workspace 4\n# hyprspace-order: 5\n",
+    );
+    dir.script(
+        "workspace-bad.sh",
+        "hyprctl dispatch workspace 5\n# hyprspace-order: soon\n",
+    );
+
+    let entries = list_workspaces(dir.path()).unwrap();
+
+    assert_eq!(names(&entries), ["zeta", "mid", "alpha", "bad", "beta"]);
+    let bad = entry(&entries, "bad");
+    assert_eq!(bad.order, None);
+    assert!(
+        bad.warnings.iter().any(|w| w.contains("soon")),
+        "{:?}",
+        bad.warnings
+    );
+}
+
+#[test]
+fn merges_several_directories() {
+    let first = TempDir::new("first");
+    let second = TempDir::new("second");
+    first.script("workspace-web.sh", "hyprctl dispatch workspace 1\n");
+    second.script("workspace-api.sh", "hyprctl dispatch workspace 2\n");
+    second.script("workspace-web.sh", "hyprctl dispatch workspace 3\n");
+    let missing = first.path().join("does-not-exist");
+    // Not a directory at all: skipped with a warning
+    let unreadable = first.script("notes.txt", "");
+
+    let dirs = [
+        first.path().to_path_buf(),
+        missing.clone(),
+        unreadable.clone(),
+        second.path().to_path_buf(),
+    ];
+    let entries = list_all_workspaces(&dirs).unwrap();
+
+    assert_eq!(names(&entries), ["api", "web", "web"]);
+    // Same-named scripts keep the order of their directories
+    assert_eq!(entries[1].source_dir, first.path());
+    assert_eq!(entries[2].source_dir, second.path());
+    assert!(list_workspaces(&missing).unwrap().is_empty());
+    assert!(list_workspaces(&unreadable).is_err());
+}
+
+#[test]
+fn parses_the_first_real_dispatch_line() {
+    assert_eq!(
+        parse_workspace_num("hyprctl dispatch workspace 3\nhyprctl dispatch workspace 4"),
+        Some(3)
+    );
+    assert_eq!(
+        parse_workspace_num("  hyprctl   dispatch workspace   12  "),
+        Some(12)
+    );
+    assert_eq!(
+        parse_workspace_num("hyprctl -i 0 dispatch workspace 6"),
+        Some(6)
+    );
+    assert_eq!(
+        parse_workspace_num("# hyprctl dispatch workspace 1\nhyprctl dispatch workspace 2"),
+        Some(2)
+    );
+    assert_eq!(parse_workspace_num("hyprctl dispatch workspace -1"), None);
+    assert_eq!(
+        parse_workspace_num("hyprctl dispatch workspace special:magic"),
+        None
+    );
+    assert_eq!(parse_workspace_num("hyprctl dispatch exec kitty"), None);
+    assert_eq!(
+        parse_workspace_num("echo hyprctl dispatch workspace 5"),
+        None
+    );
+    assert_eq!(parse_workspace_num(""), None);
+
+    assert_eq!(
+        parse_special_workspace("hyprctl dispatch togglespecialworkspace magic").as_deref(),
+        Some("magic")
+    );
+    assert_eq!(
+        parse_special_workspace("hyprctl -i 0 dispatch togglespecialworkspace").as_deref(),
+        Some("special")
+    );
+    assert_eq!(
+        parse_special_workspace("# hyprctl dispatch togglespecialworkspace magic"),
+        None
+    );
+    assert_eq!(
+        Workspace::parse("special:term"),
+        Some(Workspace::Special("term".to_string()))
+    );
+    assert_eq!(Workspace::parse("4"), Some(Workspace::Numbered(4)));
+    assert_eq!(Workspace::parse("special:"), None);
+    assert_eq!(Workspace::parse("0"), None);
+}
+
+#[test]
+fn filters_listed_scripts_fuzzily() {
+    let dir = TempDir::new("filter");
+    dir.script("workspace-backend.sh", "hyprctl dispatch workspace 1\n");
+    dir.script("workspace-frontend.sh", "hyprctl dispatch workspace 2\n");
+    dir.script("workspace-music.sh", "hyprctl dispatch workspace 3\n");
+    let entries = list_workspaces(dir.path()).unwrap();
+
+    let matching = |query: &str| -> Vec<&str> {
+        let mut scored: Vec<(i64, &str)> = entries
+            .iter()
+            .filter_map(|ws| Some((score_workspace(ws, query)?, ws.name_short.as_str())))
+            .collect();
+        scored.sort_by_key(|&(score, _)| Reverse(score));
+        scored.into_iter().map(|(_, name)| name).collect()
+    };
+
+    assert_eq!(matching("bke"), ["backend"]);
+    assert_eq!(matching("MUS"), ["music"]);
+    assert_eq!(matching("end").len(), 2);
+    assert_eq!(matching("backend")[0], "backend");
+    assert!(matching("zzz").is_empty());
+}